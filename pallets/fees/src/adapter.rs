@@ -1,14 +1,16 @@
 use core::marker::PhantomData;
 use frame::deps::frame_support::storage::with_transaction;
 use frame::deps::frame_support::traits::{
+    fungible,
     fungibles::{Dust, Inspect, Mutate, Unbalanced},
     tokens::{DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence},
+    Get,
 };
-use sp_runtime::{traits::Zero, DispatchError, Saturating, TransactionOutcome};
+use sp_runtime::{DispatchError, TransactionOutcome};
 
 use crate::{
     types::{AssetIdOf, BalanceOf},
-    Config, Event, Pallet,
+    Config, Pallet,
 };
 
 /// Shorthand for accessing pallet-assets through the fungibles traits.
@@ -113,49 +115,145 @@ where
         amount: BalanceOf<T>,
         preservation: Preservation,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        with_transaction(|| {
-            let fees = Pallet::<T>::calculate_fees(asset.clone(), source, amount);
+        let fees = Pallet::<T>::calculate_fees(asset.clone(), source, amount)?;
 
+        let transferred = with_transaction(|| {
             // Charge fees on top — each fee goes from source to the fee beneficiary.
-            for (beneficiary, fee_amount) in &fees {
-                if let Err(e) = <Inner<T> as Mutate<T::AccountId>>::transfer(
-                    asset.clone(),
-                    source,
-                    beneficiary,
-                    *fee_amount,
-                    Preservation::Preserve,
-                ) {
-                    return TransactionOutcome::Rollback(Err(e));
-                }
+            if let Err(e) = Pallet::<T>::charge_fees(source, &fees) {
+                return TransactionOutcome::Rollback(Err(e));
             }
 
-            let total_fees: BalanceOf<T> = fees
-                .iter()
-                .map(|(_, a)| *a)
-                .fold(BalanceOf::<T>::zero(), |acc, x| acc.saturating_add(x));
+            // Execute the original transfer
+            match <Inner<T> as Mutate<T::AccountId>>::transfer(
+                asset,
+                source,
+                dest,
+                amount,
+                preservation,
+            ) {
+                Ok(actual) => TransactionOutcome::Commit(Ok(actual)),
+                Err(e) => TransactionOutcome::Rollback(Err(e)),
+            }
+        })?;
+
+        // Fees were charged on top, so the weight of the wrapping call won't account for them.
+        Pallet::<T>::register_fees_weight(&fees);
+        Ok(transferred)
+    }
+}
+
+/// Shorthand for accessing the native currency through the fungible traits.
+type Native<T> = <T as Config>::NativeCurrency;
+
+/// A fungible adapter that charges community and protocol fees on native transfers.
+///
+/// Wraps `Config::NativeCurrency`, charging fees as if the transfer was made
+/// on `Config::NativeAsset`. All read operations are delegated; `transfer` is
+/// intercepted to charge configured fees on top.
+pub struct NativeWithFees<T>(PhantomData<T>);
+
+// ---------------------------------------------------------------------------
+// fungible::Inspect — pure delegation
+// ---------------------------------------------------------------------------
+impl<T: Config> fungible::Inspect<T::AccountId> for NativeWithFees<T> {
+    type Balance = BalanceOf<T>;
+
+    fn total_issuance() -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::total_issuance()
+    }
+
+    fn active_issuance() -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::active_issuance()
+    }
+
+    fn minimum_balance() -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::minimum_balance()
+    }
+
+    fn total_balance(who: &T::AccountId) -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::total_balance(who)
+    }
+
+    fn balance(who: &T::AccountId) -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::balance(who)
+    }
+
+    fn reducible_balance(
+        who: &T::AccountId,
+        preservation: Preservation,
+        force: Fortitude,
+    ) -> BalanceOf<T> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::reducible_balance(who, preservation, force)
+    }
+
+    fn can_deposit(
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+        provenance: Provenance,
+    ) -> DepositConsequence {
+        <Native<T> as fungible::Inspect<T::AccountId>>::can_deposit(who, amount, provenance)
+    }
+
+    fn can_withdraw(who: &T::AccountId, amount: BalanceOf<T>) -> WithdrawConsequence<BalanceOf<T>> {
+        <Native<T> as fungible::Inspect<T::AccountId>>::can_withdraw(who, amount)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// fungible::Unbalanced — pure delegation
+// ---------------------------------------------------------------------------
+impl<T: Config> fungible::Unbalanced<T::AccountId> for NativeWithFees<T> {
+    fn handle_dust(dust: fungible::Dust<T::AccountId, Self>) {
+        <Native<T> as fungible::Unbalanced<T::AccountId>>::handle_dust(fungible::Dust(dust.0));
+    }
+
+    fn write_balance(
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> Result<Option<BalanceOf<T>>, DispatchError> {
+        <Native<T> as fungible::Unbalanced<T::AccountId>>::write_balance(who, amount)
+    }
+
+    fn set_total_issuance(amount: BalanceOf<T>) {
+        <Native<T> as fungible::Unbalanced<T::AccountId>>::set_total_issuance(amount)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// fungible::Mutate — intercept `transfer` to charge fees on top
+// ---------------------------------------------------------------------------
+impl<T: Config> fungible::Mutate<T::AccountId> for NativeWithFees<T>
+where
+    T::AccountId: Eq,
+{
+    fn transfer(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: BalanceOf<T>,
+        preservation: Preservation,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let fees = Pallet::<T>::calculate_fees(T::NativeAsset::get(), source, amount)?;
+
+        let transferred = with_transaction(|| {
+            // Charge fees on top — each fee goes from source to the fee beneficiary.
+            if let Err(e) = Pallet::<T>::charge_fees(source, &fees) {
+                return TransactionOutcome::Rollback(Err(e));
+            }
 
             // Execute the original transfer
-            let result = <Inner<T> as Mutate<T::AccountId>>::transfer(
-                asset.clone(),
+            match <Native<T> as fungible::Mutate<T::AccountId>>::transfer(
                 source,
                 dest,
                 amount,
                 preservation,
-            );
-
-            match result {
-                Ok(actual) => {
-                    if !total_fees.is_zero() {
-                        Pallet::<T>::deposit_event(Event::FeesCharged {
-                            who: source.clone(),
-                            asset,
-                            total_fees,
-                        });
-                    }
-                    TransactionOutcome::Commit(Ok(actual))
-                }
+            ) {
+                Ok(actual) => TransactionOutcome::Commit(Ok(actual)),
                 Err(e) => TransactionOutcome::Rollback(Err(e)),
             }
-        })
+        })?;
+
+        // Fees were charged on top, so the weight of the wrapping call won't account for them.
+        Pallet::<T>::register_fees_weight(&fees);
+        Ok(transferred)
     }
 }
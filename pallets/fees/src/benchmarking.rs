@@ -0,0 +1,294 @@
+//! Benchmarks for Fees Pallet
+
+use super::*;
+use codec::Encode;
+use frame::benchmarking::prelude::*;
+use frame::deps::frame_support::traits::fungibles::Mutate;
+use pallet_assets::BenchmarkHelper as _;
+
+const TRANSFER_AMOUNT: u32 = 1_000_000;
+const FIXED_FEE: u32 = 10;
+
+fn assert_last_event<T: Config>(generic_event: <T as frame_system::Config>::RuntimeEvent) {
+    frame_system::Pallet::<T>::assert_last_event(generic_event);
+}
+
+fn fee_name<T: Config>(i: u32) -> FeeNameOf<T> {
+    BoundedVec::truncate_from(i.encode())
+}
+
+fn fee_entries<T: Config, S: Get<u32>>(
+    n: u32,
+    asset: FeeAssetOf<T>,
+) -> BoundedVec<NamedFeeEntryOf<T>, S> {
+    BoundedVec::truncate_from(
+        (0..n)
+            .map(|i| NamedFeeEntry {
+                name: fee_name::<T>(i),
+                config: FeeConfig::Fixed(FIXED_FEE.into()),
+                beneficiary: account("beneficiary", i, 0),
+                asset: asset.clone(),
+            })
+            .collect(),
+    )
+}
+
+fn create_asset<T: Config>(id: u32) -> Result<AssetIdOf<T>, BenchmarkError> {
+    let id = <T as pallet_assets::Config>::BenchmarkHelper::create_asset_id_parameter(id);
+    let origin = <T as pallet_assets::Config>::ForceOrigin::try_successful_origin()
+        .map_err(|_| BenchmarkError::Weightless)?;
+    let owner = T::Lookup::unlookup(account("owner", 0, 0));
+    pallet_assets::Pallet::<T>::force_create(origin, id.clone(), owner, true, 1u32.into())?;
+    Ok(id.into())
+}
+
+fn community_origin<T: Config>() -> Result<(T::RuntimeOrigin, T::CommunityId), BenchmarkError> {
+    let origin =
+        T::CommunityOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    let community = T::CommunityOrigin::ensure_origin(origin.clone())
+        .map_err(|_| BenchmarkError::Weightless)?;
+    Ok((origin, community))
+}
+
+/// Configures `n` fees, splitting them between protocol and community fees. Each fee is paid
+/// in an asset of its own, either at a conversion rate or at a swap quote, and capped.
+/// Returns the transferred asset and a community member paying the fees.
+fn setup_fees<T: Config>(n: u32) -> Result<(AssetIdOf<T>, T::AccountId), BenchmarkError> {
+    let asset = create_asset::<T>(100)?;
+    let (_, community) = community_origin::<T>()?;
+    let who = <T as Config>::BenchmarkHelper::community_member(&community);
+    pallet_assets::Pallet::<T>::mint_into(asset.clone(), &who, TRANSFER_AMOUNT.into())?;
+
+    let mut entries = Vec::new();
+    for i in 0..n {
+        let fee_asset = create_asset::<T>(200 + i)?;
+        let paid_in = if i % 2 == 0 {
+            ConversionRates::<T>::insert(&asset, &fee_asset, FixedU128::from_u32(2));
+            FeeAsset::FixedRate(fee_asset.clone())
+        } else {
+            <T as Config>::BenchmarkHelper::quote_swap(&asset, &fee_asset);
+            FeeAsset::Swap(fee_asset.clone())
+        };
+        entries.push(NamedFeeEntry {
+            name: fee_name::<T>(i),
+            config: FeeConfig::Fixed(FIXED_FEE.into()),
+            beneficiary: account("beneficiary", i, 0),
+            asset: paid_in,
+        });
+
+        // Worst case: fees are capped, and were already paid within the current period
+        let cap = FeeCap {
+            max: TRANSFER_AMOUNT.into(),
+            period: 100u32.into(),
+        };
+        FeeCaps::<T>::insert(&fee_asset, cap);
+        FeesSpent::<T>::insert(
+            &who,
+            &fee_asset,
+            FeeSpend {
+                since: frame_system::Pallet::<T>::block_number(),
+                paid: FIXED_FEE.into(),
+            },
+        );
+        pallet_assets::Pallet::<T>::mint_into(fee_asset, &who, TRANSFER_AMOUNT.into())?;
+    }
+
+    let protocol = n.min(T::MaxProtocolFees::get()) as usize;
+    let community_entries = entries.split_off(protocol);
+    ProtocolFees::<T>::put(BoundedVec::truncate_from(entries));
+    CommunityFees::<T>::insert(community, BoundedVec::truncate_from(community_entries));
+
+    Ok((asset, who))
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn set_protocol_fee(
+        p: Linear<0, { T::MaxProtocolFees::get() - 1 }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin =
+            T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        ProtocolFees::<T>::put(fee_entries::<T, _>(p, FeeAsset::Transferred));
+        let name = fee_name::<T>(p);
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            name.clone(),
+            FeeConfig::Fixed(FIXED_FEE.into()),
+            account("beneficiary", p, 0),
+        );
+
+        assert_last_event::<T>(Event::<T>::ProtocolFeeSet { name }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_protocol_fee(
+        p: Linear<1, { T::MaxProtocolFees::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin =
+            T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        ProtocolFees::<T>::put(fee_entries::<T, _>(p, FeeAsset::Transferred));
+        let name = fee_name::<T>(p - 1);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, name.clone());
+
+        assert_last_event::<T>(Event::<T>::ProtocolFeeRemoved { name }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_community_fee(
+        c: Linear<0, { T::MaxCommunityFees::get() - 1 }>,
+    ) -> Result<(), BenchmarkError> {
+        let (origin, community) = community_origin::<T>()?;
+        CommunityFees::<T>::insert(community, fee_entries::<T, _>(c, FeeAsset::Transferred));
+        let name = fee_name::<T>(c);
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            name.clone(),
+            FeeConfig::Fixed(FIXED_FEE.into()),
+            account("beneficiary", c, 0),
+        );
+
+        assert_last_event::<T>(Event::<T>::CommunityFeeSet { community, name }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_community_fee(
+        c: Linear<1, { T::MaxCommunityFees::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let (origin, community) = community_origin::<T>()?;
+        CommunityFees::<T>::insert(community, fee_entries::<T, _>(c, FeeAsset::Transferred));
+        let name = fee_name::<T>(c - 1);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, name.clone());
+
+        assert_last_event::<T>(Event::<T>::CommunityFeeRemoved { community, name }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_protocol_fee_asset(
+        p: Linear<1, { T::MaxProtocolFees::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin =
+            T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        ProtocolFees::<T>::put(fee_entries::<T, _>(p, FeeAsset::Transferred));
+        let name = fee_name::<T>(p - 1);
+        let fee_asset = create_asset::<T>(101)?;
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            name.clone(),
+            FeeAsset::FixedRate(fee_asset.clone()),
+        );
+
+        assert_last_event::<T>(
+            Event::<T>::ProtocolFeeAssetSet {
+                name,
+                asset: FeeAsset::FixedRate(fee_asset),
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_community_fee_asset(
+        c: Linear<1, { T::MaxCommunityFees::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let (origin, community) = community_origin::<T>()?;
+        CommunityFees::<T>::insert(community, fee_entries::<T, _>(c, FeeAsset::Transferred));
+        let name = fee_name::<T>(c - 1);
+        let fee_asset = create_asset::<T>(101)?;
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            name.clone(),
+            FeeAsset::FixedRate(fee_asset.clone()),
+        );
+
+        assert_last_event::<T>(
+            Event::<T>::CommunityFeeAssetSet {
+                community,
+                name,
+                asset: FeeAsset::FixedRate(fee_asset),
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_conversion_rate() -> Result<(), BenchmarkError> {
+        let origin =
+            T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let from = create_asset::<T>(100)?;
+        let to = create_asset::<T>(101)?;
+        let rate = Some(FixedU128::from_u32(2));
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, from.clone(), to.clone(), rate);
+
+        assert_last_event::<T>(Event::<T>::ConversionRateSet { from, to, rate }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_fee_cap() -> Result<(), BenchmarkError> {
+        let origin =
+            T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let asset = create_asset::<T>(100)?;
+        let cap = Some(FeeCap {
+            max: TRANSFER_AMOUNT.into(),
+            period: 100u32.into(),
+        });
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, asset.clone(), cap.clone());
+
+        assert_last_event::<T>(Event::<T>::FeeCapSet { asset, cap }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn calculate_fees(n: Linear<0, { Pallet::<T>::max_fees() }>) -> Result<(), BenchmarkError> {
+        let (asset, who) = setup_fees::<T>(n)?;
+        let fees;
+
+        #[block]
+        {
+            fees = Pallet::<T>::calculate_fees(asset, &who, TRANSFER_AMOUNT.into())?;
+        }
+
+        assert_eq!(fees.len() as u32, n);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn charge_fees(n: Linear<0, { Pallet::<T>::max_fees() }>) -> Result<(), BenchmarkError> {
+        let (asset, who) = setup_fees::<T>(n)?;
+        let fees = Pallet::<T>::calculate_fees(asset, &who, TRANSFER_AMOUNT.into())?;
+
+        #[block]
+        {
+            Pallet::<T>::charge_fees(&who, &fees)?;
+        }
+
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -4,14 +4,7 @@ use core::marker::PhantomData;
 
 use codec::{Decode, DecodeWithMemTracking, Encode};
 use frame::deps::{
-    frame_support::{
-        dispatch::DispatchInfo,
-        traits::{
-            fungibles::{Inspect, Mutate},
-            tokens::Preservation,
-            IsSubType,
-        },
-    },
+    frame_support::{dispatch::DispatchInfo, traits::tokens::Preservation},
     frame_system,
 };
 use scale_info::TypeInfo;
@@ -24,31 +17,18 @@ use sp_runtime::{
     Saturating,
 };
 
-use crate::types::{AssetIdOf, BalanceOf};
-
-type Inner<T> = pallet_assets::Pallet<T>;
-
-/// Extracts asset transfer info from a runtime call using `IsSubType`.
-fn extract_asset_transfer<T>(call: &T::RuntimeCall) -> Option<(AssetIdOf<T>, BalanceOf<T>)>
-where
-    T: Config,
-    T::RuntimeCall: IsSubType<pallet_assets::Call<T>>,
-{
-    match call.is_sub_type()? {
-        pallet_assets::Call::transfer { id, amount, .. }
-        | pallet_assets::Call::transfer_keep_alive { id, amount, .. }
-        | pallet_assets::Call::transfer_approved { id, amount, .. } => {
-            Some((id.clone().into(), *amount))
-        }
-        _ => None,
-    }
-}
+use crate::{
+    types::{AssetIdOf, BalanceOf, TransferOf},
+    Settlement,
+};
 
 /// Transaction extension that charges community and protocol fees
-/// on direct pallet-assets calls.
+/// on the transfers recognized by [`Config::TransferMatcher`].
 ///
 /// Fees are charged in `prepare` (before the call executes).
-/// If the call fails, fees are refunded in `post_dispatch_details`.
+/// If the transfers don't go through, as told by their [`Settlement`], fees are refunded in
+/// `post_dispatch_details`. Fees of [`Settlement::Charged`] calls are only refunded when the
+/// whole call fails.
 #[derive(Encode, Decode, DecodeWithMemTracking, Clone, Eq, PartialEq, TypeInfo)]
 #[scale_info(skip_type_params(T))]
 pub struct ChargeFees<T: Config>(#[codec(skip)] PhantomData<T>);
@@ -73,19 +53,29 @@ pub type ChargeFeePre<T> = Vec<(
     <T as frame_system::Config>::AccountId, // beneficiary
 )>;
 
+/// The balances payers hold in the assets they transfer, once fees are charged, to settle
+/// [`Settlement::Debited`] calls with.
+pub type PayerBalancesOf<T> = Vec<TransferOf<T>>;
+
 impl<T> TransactionExtension<T::RuntimeCall> for ChargeFees<T>
 where
     T: Config + Send + Sync,
-    T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>
-        + IsSubType<pallet_assets::Call<T>>,
+    T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
 {
     const IDENTIFIER: &'static str = "ChargeFees";
     type Implicit = ();
-    type Val = Option<(AssetIdOf<T>, BalanceOf<T>, T::AccountId)>;
-    type Pre = ChargeFeePre<T>;
-
-    fn weight(&self, _: &T::RuntimeCall) -> Weight {
-        Weight::from_parts(15_000_000, 0)
+    type Val = (Vec<TransferOf<T>>, Settlement);
+    type Pre = (ChargeFeePre<T>, Option<PayerBalancesOf<T>>);
+
+    fn weight(&self, call: &T::RuntimeCall) -> Weight {
+        // Fees are calculated in `validate` and `prepare`, and charged in `prepare`,
+        // once for each transfer in the call, on top of what the matcher itself reads.
+        let n = Pallet::<T>::max_fees();
+        <T as Config>::WeightInfo::calculate_fees(n)
+            .saturating_mul(2)
+            .saturating_add(<T as Config>::WeightInfo::charge_fees(n))
+            .saturating_mul(T::TransferMatcher::max_transfers(call).into())
+            .saturating_add(T::TransferMatcher::weight(call))
     }
 
     fn validate(
@@ -101,33 +91,53 @@ where
         // Only inspect signed transactions
         let who = match origin.clone().into() {
             Ok(frame_system::RawOrigin::Signed(ref who)) => who.clone(),
-            _ => return Ok((ValidTransaction::default(), None, origin)),
+            _ => {
+                return Ok((
+                    ValidTransaction::default(),
+                    (Vec::new(), Settlement::Atomic),
+                    origin,
+                ))
+            }
         };
 
-        // Check if the call is an asset operation
-        let Some((asset, amount)) = extract_asset_transfer::<T>(call) else {
-            return Ok((ValidTransaction::default(), None, origin));
+        // Collect the transfers performed by the call
+        let mut transfers = Vec::new();
+        let settlement = T::TransferMatcher::match_call(&who, call, &mut transfers)
+            .unwrap_or(Settlement::Atomic);
+
+        // Verify each payer can afford its transfers + fees, per asset.
+        // Fees paid in another asset are quoted here, so underfunded payers are rejected early.
+        let mut required: Vec<(TransferOf<T>, BalanceOf<T>)> = Vec::new();
+        let mut require = |payer: &T::AccountId, asset: &AssetIdOf<T>, amount, fee| match required
+            .iter_mut()
+            .find(|((p, a, _), _)| p == payer && a == asset)
+        {
+            Some(((_, _, amounts), fees)) => {
+                *amounts = amounts.saturating_add(amount);
+                *fees = fees.saturating_add(fee);
+            }
+            None => required.push(((payer.clone(), asset.clone(), amount), fee)),
         };
+        for (payer, asset, amount) in transfers.iter() {
+            require(payer, asset, *amount, BalanceOf::<T>::zero());
+        }
+        // Fees of earlier transfers count towards the caps of the later ones, as when charged
+        let fees = Pallet::<T>::calculate_transfers_fees(&transfers)
+            .map_err(|_| InvalidTransaction::Payment)?;
+        for (payer, (_, fee_asset, fee)) in fees {
+            require(&payer, &fee_asset, BalanceOf::<T>::zero(), fee);
+        }
 
-        // Calculate fees and verify sender can afford transfer + fees
-        let fees = Pallet::<T>::calculate_fees(asset.clone(), &who, amount);
-        let total_fees = fees
-            .iter()
-            .map(|(_, a)| *a)
-            .fold(BalanceOf::<T>::zero(), |a, b| a.saturating_add(b));
-
-        if !total_fees.is_zero() {
-            let balance = <Inner<T> as Inspect<T::AccountId>>::balance(asset.clone(), &who);
-            if balance < amount.saturating_add(total_fees) {
-                return Err(InvalidTransaction::Payment.into());
+        for ((payer, asset, amounts), total_fees) in required {
+            if !total_fees.is_zero() {
+                let balance = Pallet::<T>::balance(asset, &payer);
+                if balance < amounts.saturating_add(total_fees) {
+                    return Err(InvalidTransaction::Payment.into());
+                }
             }
         }
 
-        Ok((
-            ValidTransaction::default(),
-            Some((asset, amount, who)),
-            origin,
-        ))
+        Ok((ValidTransaction::default(), (transfers, settlement), origin))
     }
 
     fn prepare(
@@ -138,36 +148,32 @@ where
         _info: &DispatchInfoOf<T::RuntimeCall>,
         _len: usize,
     ) -> Result<Self::Pre, sp_runtime::transaction_validity::TransactionValidityError> {
-        let Some((asset, amount, who)) = val else {
-            return Ok(Vec::new());
-        };
-
-        let fees = Pallet::<T>::calculate_fees(asset.clone(), &who, amount);
-        let mut total_fees = BalanceOf::<T>::zero();
+        let (transfers, settlement) = val;
         let mut charged = Vec::new();
 
-        for (beneficiary, fee_amount) in fees {
-            <Inner<T> as Mutate<T::AccountId>>::transfer(
-                asset.clone(),
-                &who,
-                &beneficiary,
-                fee_amount,
-                Preservation::Preserve,
-            )
-            .map_err(|_| InvalidTransaction::Payment)?;
-            total_fees = total_fees.saturating_add(fee_amount);
-            charged.push((who.clone(), asset.clone(), fee_amount, beneficiary));
+        for (who, asset, amount) in transfers.iter() {
+            let fees = Pallet::<T>::calculate_fees(asset.clone(), who, *amount)
+                .map_err(|_| InvalidTransaction::Payment)?;
+            Pallet::<T>::charge_fees(who, &fees).map_err(|_| InvalidTransaction::Payment)?;
+            charged.extend(
+                fees.into_iter()
+                    .map(|(beneficiary, asset, fee)| (who.clone(), asset, fee, beneficiary)),
+            );
         }
 
-        if !total_fees.is_zero() {
-            Pallet::<T>::deposit_event(Event::FeesCharged {
-                who,
-                asset,
-                total_fees,
-            });
-        }
+        // Debited calls are settled comparing these balances to the ones after dispatching
+        let balances = matches!(settlement, Settlement::Debited).then(|| {
+            let mut balances: PayerBalancesOf<T> = Vec::new();
+            for (who, asset, _) in transfers {
+                if !balances.iter().any(|(p, a, _)| *p == who && *a == asset) {
+                    let balance = Pallet::<T>::balance(asset.clone(), &who);
+                    balances.push((who, asset, balance));
+                }
+            }
+            balances
+        });
 
-        Ok(charged)
+        Ok((charged, balances))
     }
 
     fn post_dispatch_details(
@@ -177,18 +183,29 @@ where
         _len: usize,
         result: &DispatchResult,
     ) -> Result<Weight, sp_runtime::transaction_validity::TransactionValidityError> {
-        // Refund fees if the call failed
-        if result.is_err() {
-            for (payer, asset, amount, beneficiary) in pre {
+        // Refund fees if the call failed, or its transfers didn't go through
+        let (charged, balances) = pre;
+        let executed = || {
+            balances.is_none_or(|balances| {
+                balances
+                    .into_iter()
+                    .any(|(payer, asset, balance)| Pallet::<T>::balance(asset, &payer) != balance)
+            })
+        };
+        if !charged.is_empty() && (result.is_err() || !executed()) {
+            for (payer, asset, amount, beneficiary) in charged {
                 // Best-effort refund — use Expendable since the beneficiary account
                 // may need to be fully drained to return the fee
-                let _ = <Inner<T> as Mutate<T::AccountId>>::transfer(
-                    asset,
+                let refunded = Pallet::<T>::transfer(
+                    asset.clone(),
                     &beneficiary,
                     &payer,
                     amount,
                     Preservation::Expendable,
                 );
+                if refunded.is_ok() {
+                    Pallet::<T>::release_fees_spent(&payer, &asset, amount);
+                }
             }
         }
         Ok(Weight::zero())
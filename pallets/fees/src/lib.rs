@@ -9,9 +9,15 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
+use frame::deps::frame_support::traits::{
+    fungible, fungibles,
+    tokens::{Fortitude, Preservation},
+};
 use frame::prelude::*;
-use sp_runtime::{traits::Zero, Permill, Saturating};
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128, Permill, Saturating};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -19,12 +25,18 @@ mod tests;
 
 mod adapter;
 mod extensions;
+mod matchers;
+pub mod runtime_api;
 pub mod types;
+pub mod weights;
 
 pub use adapter::*;
 pub use extensions::*;
+pub use matchers::*;
 pub use pallet::*;
+pub use runtime_api::*;
 pub use types::*;
+pub use weights::*;
 
 #[frame::pallet]
 pub mod pallet {
@@ -34,8 +46,11 @@ pub mod pallet {
     pub trait Config:
         frame_system::Config<RuntimeEvent: From<Event<Self>>> + pallet_assets::Config
     {
+        /// Weight information for extrinsics and fee charging in this pallet.
+        type WeightInfo: WeightInfo;
+
         /// Community identifier type.
-        type CommunityId: Parameter + MaxEncodedLen + Copy;
+        type CommunityId: Parameter + MaxEncodedLen + MaybeSerializeDeserialize + Copy;
 
         /// Maximum length of a fee name.
         #[pallet::constant]
@@ -57,6 +72,23 @@ pub mod pallet {
 
         /// Maps accounts to their community.
         type CommunityDetector: AccountCommunity<Self::AccountId, Self::CommunityId>;
+
+        /// The native currency, used for transfers of [`Config::NativeAsset`].
+        type NativeCurrency: fungible::Mutate<Self::AccountId, Balance = BalanceOf<Self>>;
+
+        /// Asset key that stands for the native currency.
+        #[pallet::constant]
+        type NativeAsset: Get<AssetIdOf<Self>>;
+
+        /// Recognizes the calls [`ChargeFees`] charges fees on.
+        type TransferMatcher: TransferMatcher<Self>;
+
+        /// Prices fees paid with [`FeeAsset::Swap`].
+        type Swap: SwapQuote<AssetIdOf<Self>, BalanceOf<Self>>;
+
+        /// A helper to prepare benchmarking tests.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: BenchmarkHelper<Self>;
     }
 
     #[pallet::pallet]
@@ -77,6 +109,73 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Governance-set conversion rates between assets, used to price fees paid
+    /// with [`FeeAsset::FixedRate`]. Keyed by `(from, to)`, the rate is the amount
+    /// of `to` a unit of `from` is worth.
+    #[pallet::storage]
+    pub type ConversionRates<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        FixedU128,
+    >;
+
+    /// Governance-set caps on the fees an account pays in an asset per period.
+    /// Fees beyond the cap are not charged until the period ends.
+    #[pallet::storage]
+    pub type FeeCaps<T: Config> = StorageMap<_, Blake2_128Concat, AssetIdOf<T>, FeeCapOf<T>>;
+
+    /// Fees paid by an account in a capped asset during its current period.
+    ///
+    /// Entries are pruned whenever they're touched again: they're reset when charging fees
+    /// after their period is over, and removed once refunded down to nothing, once their
+    /// period is over, or once the asset is no longer capped. So there's at most an entry
+    /// per account and capped asset it paid fees in, and entries that aren't touched again
+    /// only linger until the account next pays or is refunded fees in that asset.
+    #[pallet::storage]
+    pub type FeesSpent<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        FeeSpendOf<T>,
+    >;
+
+    #[pallet::genesis_config]
+    #[derive(frame::deps::frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Genesis protocol fees: name, config, beneficiary, fee asset
+        pub protocol_fees: Vec<GenesisFeeOf<T>>,
+        /// Genesis community fees: community, (name, config, beneficiary, fee asset)
+        pub community_fees: Vec<(T::CommunityId, GenesisFeeOf<T>)>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            let entry = |(name, config, beneficiary, asset): &GenesisFeeOf<T>| {
+                assert!(config.is_valid(), "invalid fee config");
+                NamedFeeEntry {
+                    name: FeeNameOf::<T>::try_from(name.clone()).expect("fee name too long"),
+                    config: config.clone(),
+                    beneficiary: beneficiary.clone(),
+                    asset: asset.clone(),
+                }
+            };
+
+            for fee in &self.protocol_fees {
+                ProtocolFees::<T>::try_append(entry(fee)).expect("too many protocol fees");
+            }
+            for (community, fee) in &self.community_fees {
+                CommunityFees::<T>::try_append(community, entry(fee))
+                    .expect("too many community fees");
+            }
+        }
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -94,6 +193,24 @@ pub mod pallet {
             community: T::CommunityId,
             name: FeeNameOf<T>,
         },
+        ConversionRateSet {
+            from: AssetIdOf<T>,
+            to: AssetIdOf<T>,
+            rate: Option<FixedU128>,
+        },
+        FeeCapSet {
+            asset: AssetIdOf<T>,
+            cap: Option<FeeCapOf<T>>,
+        },
+        ProtocolFeeAssetSet {
+            name: FeeNameOf<T>,
+            asset: FeeAssetOf<T>,
+        },
+        CommunityFeeAssetSet {
+            community: T::CommunityId,
+            name: FeeNameOf<T>,
+            asset: FeeAssetOf<T>,
+        },
         /// Fees were charged on a transfer.
         FeesCharged {
             who: T::AccountId,
@@ -110,14 +227,15 @@ pub mod pallet {
         FeeNotFound,
         /// Invalid fee configuration (e.g. min > max in PercentageClamped).
         InvalidFeeConfig,
+        /// A fee is paid in an asset there's no rate or quote to convert it into.
+        FeeAssetUnpriced,
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Set or update a protocol-level fee. Requires `AdminOrigin`.
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000_000, 0)
-            .saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        #[pallet::weight(<T as Config>::WeightInfo::set_protocol_fee(T::MaxProtocolFees::get()))]
         pub fn set_protocol_fee(
             origin: OriginFor<T>,
             name: FeeNameOf<T>,
@@ -135,6 +253,7 @@ pub mod pallet {
                         name: name.clone(),
                         config,
                         beneficiary,
+                        asset: FeeAsset::Transferred,
                     })
                     .map_err(|_| Error::<T>::TooManyFees)?;
                 }
@@ -146,8 +265,7 @@ pub mod pallet {
 
         /// Remove a protocol-level fee. Requires `AdminOrigin`.
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000_000, 0)
-            .saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_protocol_fee(T::MaxProtocolFees::get()))]
         pub fn remove_protocol_fee(origin: OriginFor<T>, name: FeeNameOf<T>) -> DispatchResult {
             T::AdminOrigin::ensure_origin(origin)?;
             ProtocolFees::<T>::try_mutate(|fees| {
@@ -164,8 +282,7 @@ pub mod pallet {
 
         /// Set or update a community-level fee. Requires `CommunityOrigin`.
         #[pallet::call_index(2)]
-        #[pallet::weight(Weight::from_parts(10_000_000, 0)
-            .saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        #[pallet::weight(<T as Config>::WeightInfo::set_community_fee(T::MaxCommunityFees::get()))]
         pub fn set_community_fee(
             origin: OriginFor<T>,
             name: FeeNameOf<T>,
@@ -183,6 +300,7 @@ pub mod pallet {
                         name: name.clone(),
                         config,
                         beneficiary,
+                        asset: FeeAsset::Transferred,
                     })
                     .map_err(|_| Error::<T>::TooManyFees)?;
                 }
@@ -194,8 +312,7 @@ pub mod pallet {
 
         /// Remove a community-level fee. Requires `CommunityOrigin`.
         #[pallet::call_index(3)]
-        #[pallet::weight(Weight::from_parts(10_000_000, 0)
-            .saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_community_fee(T::MaxCommunityFees::get()))]
         pub fn remove_community_fee(origin: OriginFor<T>, name: FeeNameOf<T>) -> DispatchResult {
             let community = T::CommunityOrigin::ensure_origin(origin)?;
             CommunityFees::<T>::try_mutate(community, |fees| {
@@ -209,56 +326,436 @@ pub mod pallet {
             Self::deposit_event(Event::CommunityFeeRemoved { community, name });
             Ok(())
         }
+
+        /// Set the asset a protocol-level fee is paid in. Requires `AdminOrigin`.
+        #[pallet::call_index(4)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_protocol_fee_asset(T::MaxProtocolFees::get()))]
+        pub fn set_protocol_fee_asset(
+            origin: OriginFor<T>,
+            name: FeeNameOf<T>,
+            asset: FeeAssetOf<T>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ProtocolFees::<T>::try_mutate(|fees| {
+                let entry = fees
+                    .iter_mut()
+                    .find(|e| e.name == name)
+                    .ok_or(Error::<T>::FeeNotFound)?;
+                entry.asset = asset.clone();
+                Ok::<_, DispatchError>(())
+            })?;
+            Self::deposit_event(Event::ProtocolFeeAssetSet { name, asset });
+            Ok(())
+        }
+
+        /// Set the asset a community-level fee is paid in. Requires `CommunityOrigin`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_community_fee_asset(T::MaxCommunityFees::get()))]
+        pub fn set_community_fee_asset(
+            origin: OriginFor<T>,
+            name: FeeNameOf<T>,
+            asset: FeeAssetOf<T>,
+        ) -> DispatchResult {
+            let community = T::CommunityOrigin::ensure_origin(origin)?;
+            CommunityFees::<T>::try_mutate(community, |fees| {
+                let entry = fees
+                    .iter_mut()
+                    .find(|e| e.name == name)
+                    .ok_or(Error::<T>::FeeNotFound)?;
+                entry.asset = asset.clone();
+                Ok::<_, DispatchError>(())
+            })?;
+            Self::deposit_event(Event::CommunityFeeAssetSet {
+                community,
+                name,
+                asset,
+            });
+            Ok(())
+        }
+
+        /// Set or clear the rate used to convert fees from `from` into `to`.
+        /// Requires `AdminOrigin`.
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_conversion_rate())]
+        pub fn set_conversion_rate(
+            origin: OriginFor<T>,
+            from: AssetIdOf<T>,
+            to: AssetIdOf<T>,
+            rate: Option<FixedU128>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                rate.is_none_or(|r| !r.is_zero()),
+                Error::<T>::InvalidFeeConfig
+            );
+            ConversionRates::<T>::set(&from, &to, rate);
+            Self::deposit_event(Event::ConversionRateSet { from, to, rate });
+            Ok(())
+        }
+
+        /// Set or clear the cap on the fees each account pays in `asset` per period.
+        /// Requires `AdminOrigin`.
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_fee_cap())]
+        pub fn set_fee_cap(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+            cap: Option<FeeCapOf<T>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                cap.as_ref().is_none_or(|c| !c.period.is_zero()),
+                Error::<T>::InvalidFeeConfig
+            );
+            FeeCaps::<T>::set(&asset, cap.clone());
+            Self::deposit_event(Event::FeeCapSet { asset, cap });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
         /// Calculate all applicable fees for a transfer of `amount` on `asset` by `who`.
         /// Fees are rounded up to the asset's minimum balance to avoid dust.
         /// Total fees are capped at `amount` — if they exceed it, each fee is
-        /// scaled down proportionally.
-        /// Returns a list of (beneficiary, fee_amount) pairs.
+        /// scaled down proportionally. Fees paid in another asset are converted
+        /// after capping, and fail with [`Error::FeeAssetUnpriced`] if there's no price
+        /// to convert them. Fees beyond what `who` may still pay under [`FeeCaps`]
+        /// in the current period are waived.
+        /// Returns a list of (beneficiary, fee_asset, fee_amount) entries.
         pub fn calculate_fees(
             asset: AssetIdOf<T>,
             who: &T::AccountId,
             amount: BalanceOf<T>,
-        ) -> Vec<(T::AccountId, BalanceOf<T>)> {
-            use frame::deps::frame_support::traits::fungibles::Inspect;
-            let min_balance =
-                Some(<pallet_assets::Pallet<T> as Inspect<T::AccountId>>::minimum_balance(asset));
-            let mut fees = Vec::new();
+        ) -> Result<Vec<FeeOf<T>>, DispatchError> {
+            Ok(Self::itemize_fees(asset, who, amount, &mut Vec::new())?
+                .into_iter()
+                .map(|(_, fee)| fee)
+                .collect())
+        }
 
-            // Protocol fees always apply
-            for entry in ProtocolFees::<T>::get().iter() {
-                let fee = entry.config.calculate(amount, min_balance);
-                if !fee.is_zero() {
-                    fees.push((entry.beneficiary.clone(), fee));
+        /// The fees of several `transfers` made at once, along with who pays them.
+        /// Unlike calculating the fees of each transfer on its own, the fees of a
+        /// transfer count towards the [`FeeCaps`] of the transfers that follow it.
+        pub(crate) fn calculate_transfers_fees(
+            transfers: &[TransferOf<T>],
+        ) -> Result<Vec<PayerFeeOf<T>>, DispatchError> {
+            let mut allowances = Vec::new();
+            let mut fees = Vec::new();
+            for (who, asset, amount) in transfers {
+                for (_, fee) in Self::itemize_fees(asset.clone(), who, *amount, &mut allowances)? {
+                    fees.push((who.clone(), fee));
                 }
             }
+            Ok(fees)
+        }
+
+        /// Itemized fees for a transfer of `amount` on `asset` by `who`, named after
+        /// the fee entry they come from. Backs the [`FeesApi`] runtime API.
+        pub fn quote_fees(
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        ) -> Result<Vec<FeeQuoteOf<T>>, DispatchError> {
+            Ok(Self::itemize_fees(asset, &who, amount, &mut Vec::new())?
+                .into_iter()
+                .map(|(name, (beneficiary, asset, amount))| FeeQuote {
+                    name: name.into_inner(),
+                    beneficiary,
+                    asset,
+                    amount,
+                })
+                .collect())
+        }
+
+        /// The fees [`Self::calculate_fees`] returns, along with the name of their entry.
+        /// `allowances` keeps what's left of the fee caps of fees calculated before.
+        fn itemize_fees(
+            asset: AssetIdOf<T>,
+            who: &T::AccountId,
+            amount: BalanceOf<T>,
+            allowances: &mut FeeAllowancesOf<T>,
+        ) -> Result<Vec<NamedFeeOf<T>>, DispatchError> {
+            let min_balance = Some(Self::minimum_balance(asset.clone()));
+            let mut fees = Vec::new();
 
-            // Community fees apply if the sender belongs to a community
-            if let Some(community) = T::CommunityDetector::community_of(who) {
-                for entry in CommunityFees::<T>::get(community).iter() {
-                    let fee = entry.config.calculate(amount, min_balance);
-                    if !fee.is_zero() {
-                        fees.push((entry.beneficiary.clone(), fee));
-                    }
+            let protocol_fees = ProtocolFees::<T>::get();
+            let community_fees = T::CommunityDetector::community_of(who)
+                .map(CommunityFees::<T>::get)
+                .unwrap_or_default();
+
+            // Protocol fees always apply, community fees if the sender belongs to a community
+            for entry in protocol_fees.iter().chain(community_fees.iter()) {
+                // Converted fees are rounded up to the fee asset's minimum balance instead
+                let converted = entry.asset.converts(&asset);
+                let fee = entry
+                    .config
+                    .calculate(amount, if converted { None } else { min_balance });
+                if !fee.is_zero() {
+                    fees.push((entry, converted, fee));
                 }
             }
 
             // Cap total fees at the transfer amount
             let total: BalanceOf<T> = fees
                 .iter()
-                .map(|(_, f)| *f)
+                .map(|(_, _, f)| *f)
                 .fold(BalanceOf::<T>::zero(), |a, b| a.saturating_add(b));
 
             if total > amount && !amount.is_zero() {
                 let ratio = Permill::from_rational(amount, total);
-                for (_, fee) in fees.iter_mut() {
+                for (_, _, fee) in fees.iter_mut() {
                     *fee = ratio.mul_ceil(*fee);
                 }
             }
 
-            fees
+            let fees = fees
+                .into_iter()
+                .map(|(entry, converted, fee)| {
+                    let (fee_asset, fee) = if converted {
+                        Self::convert_fee(asset.clone(), &entry.asset, fee)?
+                    } else {
+                        (asset.clone(), fee)
+                    };
+                    Ok((
+                        entry.name.clone(),
+                        (entry.beneficiary.clone(), fee_asset, fee),
+                    ))
+                })
+                .collect::<Result<_, DispatchError>>()?;
+
+            Ok(Self::apply_fee_caps(who, fees, allowances))
+        }
+
+        /// Reduce `fees` to what `who` may still pay under [`FeeCaps`] in the
+        /// current period, in order, dropping the fees that are fully waived.
+        fn apply_fee_caps(
+            who: &T::AccountId,
+            fees: Vec<NamedFeeOf<T>>,
+            allowances: &mut FeeAllowancesOf<T>,
+        ) -> Vec<NamedFeeOf<T>> {
+            let now = frame_system::Pallet::<T>::block_number();
+            fees.into_iter()
+                .filter_map(|(name, (beneficiary, asset, fee))| {
+                    let i = match allowances
+                        .iter()
+                        .position(|(payer, a, _)| payer == who && *a == asset)
+                    {
+                        Some(i) => i,
+                        None => {
+                            let allowance = Self::fee_allowance(who, &asset, now);
+                            allowances.push((who.clone(), asset.clone(), allowance));
+                            allowances.len() - 1
+                        }
+                    };
+                    let fee = match &mut allowances[i].2 {
+                        Some(allowance) => {
+                            let fee = fee.min(*allowance);
+                            *allowance = allowance.saturating_sub(fee);
+                            fee
+                        }
+                        None => fee,
+                    };
+                    (!fee.is_zero()).then_some((name, (beneficiary, asset, fee)))
+                })
+                .collect()
+        }
+
+        /// What `who` may still pay in fees on `asset` in the current period,
+        /// or `None` if the asset is not capped.
+        fn fee_allowance(
+            who: &T::AccountId,
+            asset: &AssetIdOf<T>,
+            now: BlockNumberFor<T>,
+        ) -> Option<BalanceOf<T>> {
+            let cap = FeeCaps::<T>::get(asset)?;
+            let paid = FeesSpent::<T>::get(who, asset)
+                .map(|spend| spend.paid_at(&cap, now))
+                .unwrap_or_default();
+            Some(cap.max.saturating_sub(paid))
+        }
+
+        /// Track `amount` of fees paid by `who` in `asset` towards its cap, if any,
+        /// starting a new period if the previous one is over. What was tracked for an
+        /// asset that's no longer capped is removed.
+        fn record_fees_spent(who: &T::AccountId, asset: &AssetIdOf<T>, amount: BalanceOf<T>) {
+            let Some(cap) = FeeCaps::<T>::get(asset) else {
+                FeesSpent::<T>::remove(who, asset);
+                return;
+            };
+            let now = frame_system::Pallet::<T>::block_number();
+            FeesSpent::<T>::mutate(who, asset, |spend| match spend {
+                Some(spend) if now < spend.since.saturating_add(cap.period) => {
+                    spend.paid = spend.paid.saturating_add(amount);
+                }
+                _ => {
+                    *spend = Some(FeeSpend {
+                        since: now,
+                        paid: amount,
+                    })
+                }
+            });
+        }
+
+        /// Return `amount` of fees refunded to `who` in `asset` to its allowance
+        /// for the current period, removing what's tracked once there's nothing left
+        /// to count towards the cap.
+        pub(crate) fn release_fees_spent(
+            who: &T::AccountId,
+            asset: &AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        ) {
+            let cap = FeeCaps::<T>::get(asset);
+            let now = frame_system::Pallet::<T>::block_number();
+            FeesSpent::<T>::mutate_exists(who, asset, |maybe_spend| {
+                let Some(spend) = maybe_spend else {
+                    return;
+                };
+                spend.paid = cap
+                    .map(|cap| spend.paid_at(&cap, now))
+                    .unwrap_or_default()
+                    .saturating_sub(amount);
+                if spend.paid.is_zero() {
+                    *maybe_spend = None;
+                }
+            });
+        }
+
+        /// Convert a `fee` on `asset` into the asset it's paid in, rounding it up to
+        /// the fee asset's minimum balance. Fails if there's no price, rather than
+        /// charging the fee in an asset it wasn't meant to be paid in.
+        fn convert_fee(
+            asset: AssetIdOf<T>,
+            fee_asset: &FeeAssetOf<T>,
+            fee: BalanceOf<T>,
+        ) -> Result<(AssetIdOf<T>, BalanceOf<T>), Error<T>> {
+            let (fee_asset, fee) = match fee_asset {
+                FeeAsset::Transferred => Some((asset, fee)),
+                FeeAsset::FixedRate(to) => ConversionRates::<T>::get(&asset, to)
+                    .map(|rate| (to.clone(), rate.saturating_mul_int(fee))),
+                FeeAsset::Swap(to) => {
+                    T::Swap::quote_swap(asset.clone(), to.clone(), fee).map(|q| (to.clone(), q))
+                }
+            }
+            .ok_or(Error::<T>::FeeAssetUnpriced)?;
+            let min_balance = Self::minimum_balance(fee_asset.clone());
+            Ok((fee_asset, fee.max(min_balance)))
+        }
+
+        /// Transfer each fee from `who` to its beneficiary, depositing a
+        /// `FeesCharged` event for each asset the fees were paid in.
+        pub(crate) fn charge_fees(who: &T::AccountId, fees: &[FeeOf<T>]) -> DispatchResult {
+            let mut totals: Vec<(AssetIdOf<T>, BalanceOf<T>)> = Vec::new();
+            for (beneficiary, asset, fee) in fees {
+                Self::transfer(
+                    asset.clone(),
+                    who,
+                    beneficiary,
+                    *fee,
+                    Preservation::Preserve,
+                )?;
+                match totals.iter_mut().find(|(a, _)| a == asset) {
+                    Some((_, total)) => *total = total.saturating_add(*fee),
+                    None => totals.push((asset.clone(), *fee)),
+                }
+            }
+            for (asset, total_fees) in totals {
+                Self::record_fees_spent(who, &asset, total_fees);
+                Self::deposit_event(Event::FeesCharged {
+                    who: who.clone(),
+                    asset,
+                    total_fees,
+                });
+            }
+            Ok(())
+        }
+
+        /// Upper bound of the fees that may apply to a single transfer.
+        pub fn max_fees() -> u32 {
+            T::MaxProtocolFees::get().saturating_add(T::MaxCommunityFees::get())
+        }
+
+        /// Register the weight of calculating and charging `fees`, for fees charged
+        /// within a call whose weight does not account for them.
+        pub(crate) fn register_fees_weight(fees: &[FeeOf<T>]) {
+            let n = fees.len() as u32;
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                <T as Config>::WeightInfo::calculate_fees(n)
+                    .saturating_add(<T as Config>::WeightInfo::charge_fees(n)),
+                DispatchClass::Normal,
+            );
+        }
+
+        /// Whether `asset` is the key for the native currency.
+        pub fn is_native(asset: &AssetIdOf<T>) -> bool {
+            *asset == T::NativeAsset::get()
+        }
+
+        /// Minimum balance of `asset`, resolving the native currency.
+        pub fn minimum_balance(asset: AssetIdOf<T>) -> BalanceOf<T> {
+            if Self::is_native(&asset) {
+                <T::NativeCurrency as fungible::Inspect<T::AccountId>>::minimum_balance()
+            } else {
+                <pallet_assets::Pallet<T> as fungibles::Inspect<T::AccountId>>::minimum_balance(
+                    asset,
+                )
+            }
+        }
+
+        /// Balance of `who` in `asset`, resolving the native currency.
+        pub fn balance(asset: AssetIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+            if Self::is_native(&asset) {
+                <T::NativeCurrency as fungible::Inspect<T::AccountId>>::balance(who)
+            } else {
+                <pallet_assets::Pallet<T> as fungibles::Inspect<T::AccountId>>::balance(asset, who)
+            }
+        }
+
+        /// Reducible balance of `who` in `asset`, resolving the native currency.
+        pub fn reducible_balance(
+            asset: AssetIdOf<T>,
+            who: &T::AccountId,
+            preservation: Preservation,
+        ) -> BalanceOf<T> {
+            if Self::is_native(&asset) {
+                <T::NativeCurrency as fungible::Inspect<T::AccountId>>::reducible_balance(
+                    who,
+                    preservation,
+                    Fortitude::Polite,
+                )
+            } else {
+                <pallet_assets::Pallet<T> as fungibles::Inspect<T::AccountId>>::reducible_balance(
+                    asset,
+                    who,
+                    preservation,
+                    Fortitude::Polite,
+                )
+            }
+        }
+
+        /// Transfer `amount` of `asset` without charging fees, resolving the native currency.
+        pub(crate) fn transfer(
+            asset: AssetIdOf<T>,
+            source: &T::AccountId,
+            dest: &T::AccountId,
+            amount: BalanceOf<T>,
+            preservation: Preservation,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            if Self::is_native(&asset) {
+                <T::NativeCurrency as fungible::Mutate<T::AccountId>>::transfer(
+                    source,
+                    dest,
+                    amount,
+                    preservation,
+                )
+            } else {
+                <pallet_assets::Pallet<T> as fungibles::Mutate<T::AccountId>>::transfer(
+                    asset,
+                    source,
+                    dest,
+                    amount,
+                    preservation,
+                )
+            }
         }
     }
 }
@@ -0,0 +1,444 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use impl_trait_for_tuples::impl_for_tuples;
+
+use frame::deps::frame_support::{
+    traits::{tokens::Preservation, Get, IsSubType},
+    weights::Weight,
+};
+use sp_runtime::{traits::Zero, Saturating};
+#[cfg(any(test, feature = "proxy"))]
+use {frame::deps::frame_support::traits::InstanceFilter, sp_runtime::traits::StaticLookup};
+#[cfg(any(test, feature = "proxy", feature = "utility"))]
+use {frame::deps::frame_support::traits::IsType, frame::deps::frame_system};
+
+use crate::{
+    types::{AssetIdOf, BalanceOf, TransferOf},
+    Config, Pallet, WeightInfo,
+};
+
+/// How to tell whether the transfers matched in a call went through, so the fees charged on
+/// them are refunded otherwise.
+#[derive(Clone, Copy, Debug)]
+pub enum Settlement {
+    /// The call fails whenever any of its transfers does.
+    Atomic,
+    /// The call may succeed even if its transfers fail, though they go through or fail
+    /// together. They're told to have gone through if the balances the payers hold in the
+    /// transferred assets changed while dispatching the call, so transfers to oneself are
+    /// refunded as if they failed.
+    Debited,
+    /// The call may succeed even if some of its transfers fail, and there's no telling which.
+    /// Fees are charged on all of them, and only refunded if the call itself fails.
+    Charged,
+}
+
+impl Settlement {
+    /// The settlement of a call that fails whenever the call it wraps, settled as `self`, does.
+    pub fn nested(self) -> Self {
+        match self {
+            Self::Atomic => Self::Atomic,
+            _ => Self::Charged,
+        }
+    }
+}
+
+/// Recognizes calls that move funds, so [`crate::ChargeFees`] can charge fees on them.
+///
+/// Matchers can be combined in tuples; the first one that recognizes a call wins.
+pub trait TransferMatcher<T: Config> {
+    /// Appends the transfers performed when `who` dispatches `call` to `transfers`, and
+    /// returns how to tell whether they went through. Returns `None` if the call is not
+    /// recognized by this matcher.
+    fn match_call(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement>;
+
+    /// An upper bound of the transfers `match_call` may find in `call`,
+    /// used to weigh [`crate::ChargeFees`] before dispatching.
+    fn max_transfers(call: &T::RuntimeCall) -> u32;
+
+    /// The weight of matching `call` and settling its transfers, on top of charging their fees.
+    fn weight(_call: &T::RuntimeCall) -> Weight {
+        Weight::zero()
+    }
+}
+
+#[impl_for_tuples(8)]
+impl<T: Config> TransferMatcher<T> for Tuple {
+    fn match_call(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        for_tuples!( #(
+            if let Some(settlement) = Tuple::match_call(who, call, transfers) {
+                return Some(settlement);
+            }
+        )* );
+        None
+    }
+
+    fn max_transfers(call: &T::RuntimeCall) -> u32 {
+        for_tuples!( #(
+            let transfers = Tuple::max_transfers(call);
+            if transfers > 0 {
+                return transfers;
+            }
+        )* );
+        0
+    }
+
+    fn weight(call: &T::RuntimeCall) -> Weight {
+        for_tuples!( #(
+            if Tuple::max_transfers(call) > 0 {
+                return Tuple::weight(call);
+            }
+        )* );
+        Weight::zero()
+    }
+}
+
+/// The weight of [`sweep_amount`]: reading the reducible balance and calculating its fees.
+fn sweep_weight<T: Config>() -> Weight {
+    T::DbWeight::get()
+        .reads(2)
+        .saturating_add(<T as Config>::WeightInfo::calculate_fees(
+            Pallet::<T>::max_fees(),
+        ))
+}
+
+/// The amount left to transfer when `who` sweeps `reducible` of `asset`,
+/// after setting aside the fees charged on top in that same asset.
+fn sweep_amount<T: Config>(
+    asset: AssetIdOf<T>,
+    who: &T::AccountId,
+    reducible: BalanceOf<T>,
+) -> BalanceOf<T> {
+    // Unpriced fees reject the transfer anyway, when charging them
+    let fees = Pallet::<T>::calculate_fees(asset.clone(), who, reducible)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, fee_asset, _)| *fee_asset == asset)
+        .map(|(_, _, fee)| fee)
+        .fold(BalanceOf::<T>::zero(), |a, b| a.saturating_add(b));
+    reducible.saturating_sub(fees)
+}
+
+/// Matches `pallet_assets` transfer calls.
+pub struct AssetsTransfers<T>(PhantomData<T>);
+
+impl<T> TransferMatcher<T> for AssetsTransfers<T>
+where
+    T: Config,
+    T::RuntimeCall: IsSubType<pallet_assets::Call<T>>,
+{
+    fn match_call(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        let call = call.is_sub_type()?;
+        match call {
+            pallet_assets::Call::transfer { id, amount, .. }
+            | pallet_assets::Call::transfer_keep_alive { id, amount, .. }
+            | pallet_assets::Call::transfer_approved { id, amount, .. } => {
+                transfers.push((who.clone(), id.clone().into(), *amount));
+            }
+            pallet_assets::Call::transfer_all { id, keep_alive, .. } => {
+                let asset: AssetIdOf<T> = id.clone().into();
+                let preservation = if *keep_alive {
+                    Preservation::Preserve
+                } else {
+                    Preservation::Expendable
+                };
+                let reducible = Pallet::<T>::reducible_balance(asset.clone(), who, preservation);
+                let amount = sweep_amount::<T>(asset.clone(), who, reducible);
+                transfers.push((who.clone(), asset, amount));
+            }
+            _ => return None,
+        }
+        Some(Settlement::Atomic)
+    }
+
+    fn max_transfers(call: &T::RuntimeCall) -> u32 {
+        match call.is_sub_type() {
+            Some(pallet_assets::Call::transfer { .. })
+            | Some(pallet_assets::Call::transfer_keep_alive { .. })
+            | Some(pallet_assets::Call::transfer_approved { .. })
+            | Some(pallet_assets::Call::transfer_all { .. }) => 1,
+            _ => 0,
+        }
+    }
+
+    fn weight(call: &T::RuntimeCall) -> Weight {
+        match call.is_sub_type() {
+            Some(pallet_assets::Call::transfer_all { .. }) => sweep_weight::<T>(),
+            _ => Weight::zero(),
+        }
+    }
+}
+
+/// Matches `pallet_balances` transfer calls, charging fees on [`Config::NativeAsset`].
+#[cfg(any(test, feature = "balances"))]
+pub struct BalancesTransfers<T, I = ()>(PhantomData<(T, I)>);
+
+#[cfg(any(test, feature = "balances"))]
+impl<T, I> TransferMatcher<T> for BalancesTransfers<T, I>
+where
+    T: Config + pallet_balances::Config<I>,
+    I: 'static,
+    T::RuntimeCall: IsSubType<pallet_balances::Call<T, I>>,
+    <T as pallet_balances::Config<I>>::Balance: Into<BalanceOf<T>>,
+{
+    fn match_call(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        let call = call.is_sub_type()?;
+        let asset = T::NativeAsset::get();
+        match call {
+            pallet_balances::Call::transfer_allow_death { value, .. }
+            | pallet_balances::Call::transfer_keep_alive { value, .. } => {
+                transfers.push((who.clone(), asset, (*value).into()));
+            }
+            pallet_balances::Call::transfer_all { keep_alive, .. } => {
+                let preservation = if *keep_alive {
+                    Preservation::Preserve
+                } else {
+                    Preservation::Expendable
+                };
+                let reducible = Pallet::<T>::reducible_balance(asset.clone(), who, preservation);
+                let amount = sweep_amount::<T>(asset.clone(), who, reducible);
+                transfers.push((who.clone(), asset, amount));
+            }
+            _ => return None,
+        }
+        Some(Settlement::Atomic)
+    }
+
+    fn max_transfers(call: &T::RuntimeCall) -> u32 {
+        match call.is_sub_type() {
+            Some(pallet_balances::Call::transfer_allow_death { .. })
+            | Some(pallet_balances::Call::transfer_keep_alive { .. })
+            | Some(pallet_balances::Call::transfer_all { .. }) => 1,
+            _ => 0,
+        }
+    }
+
+    fn weight(call: &T::RuntimeCall) -> Weight {
+        match call.is_sub_type() {
+            Some(pallet_balances::Call::transfer_all { .. }) => sweep_weight::<T>(),
+            _ => Weight::zero(),
+        }
+    }
+}
+
+/// Matches `pallet_utility` batches and derivative calls, looking into the
+/// wrapped calls with [`Config::TransferMatcher`].
+///
+/// `batch` and `force_batch` go on when one of their calls fails, so there's no telling which
+/// of their transfers went through: fees are charged on all of them, without refunding the
+/// ones of the calls that failed. `batch_all` fails with any of its calls, so it's settled as
+/// atomically as the calls in it are.
+#[cfg(any(test, feature = "utility"))]
+pub struct UtilityCalls<T>(PhantomData<T>);
+
+#[cfg(any(test, feature = "utility"))]
+impl<T> TransferMatcher<T> for UtilityCalls<T>
+where
+    T: Config + pallet_utility::Config,
+    <T as frame_system::Config>::RuntimeCall: IsSubType<pallet_utility::Call<T>>,
+{
+    fn match_call(
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        match call.is_sub_type() {
+            Some(pallet_utility::Call::batch_all { calls }) => {
+                let mut settlement = None;
+                for call in calls {
+                    let Some(matched) =
+                        T::TransferMatcher::match_call(who, call.into_ref(), transfers)
+                    else {
+                        continue;
+                    };
+                    settlement = match (settlement, matched.nested()) {
+                        (Some(Settlement::Charged), _) | (_, Settlement::Charged) => {
+                            Some(Settlement::Charged)
+                        }
+                        _ => Some(Settlement::Atomic),
+                    };
+                }
+                settlement
+            }
+            Some(pallet_utility::Call::batch { calls })
+            | Some(pallet_utility::Call::force_batch { calls }) => {
+                let mut matched = false;
+                for call in calls {
+                    matched |=
+                        T::TransferMatcher::match_call(who, call.into_ref(), transfers).is_some();
+                }
+                matched.then_some(Settlement::Charged)
+            }
+            Some(pallet_utility::Call::as_derivative { index, call }) => {
+                let derivative = pallet_utility::derivative_account_id(who.clone(), *index);
+                T::TransferMatcher::match_call(&derivative, (**call).into_ref(), transfers)
+                    .map(Settlement::nested)
+            }
+            _ => None,
+        }
+    }
+
+    fn max_transfers(call: &<T as frame_system::Config>::RuntimeCall) -> u32 {
+        match call.is_sub_type() {
+            Some(pallet_utility::Call::batch { calls })
+            | Some(pallet_utility::Call::batch_all { calls })
+            | Some(pallet_utility::Call::force_batch { calls }) => calls
+                .iter()
+                .map(|call| T::TransferMatcher::max_transfers(call.into_ref()))
+                .fold(0, u32::saturating_add),
+            Some(pallet_utility::Call::as_derivative { call, .. }) => {
+                T::TransferMatcher::max_transfers((**call).into_ref())
+            }
+            _ => 0,
+        }
+    }
+
+    fn weight(call: &<T as frame_system::Config>::RuntimeCall) -> Weight {
+        match call.is_sub_type() {
+            Some(pallet_utility::Call::batch { calls })
+            | Some(pallet_utility::Call::batch_all { calls })
+            | Some(pallet_utility::Call::force_batch { calls }) => calls
+                .iter()
+                .map(|call| T::TransferMatcher::weight(call.into_ref()))
+                .fold(Weight::zero(), Weight::saturating_add),
+            Some(pallet_utility::Call::as_derivative { call, .. }) => {
+                T::TransferMatcher::weight((**call).into_ref())
+            }
+            _ => Weight::zero(),
+        }
+    }
+}
+
+/// Matches `pallet_proxy` calls, looking into the proxied call with
+/// [`Config::TransferMatcher`]. Fees are charged to the proxied account.
+///
+/// Proxy calls succeed even if the proxied call fails, so fees are refunded unless the
+/// balances of the proxied account were [`Settlement::Debited`]. That's only known for the
+/// outermost call of a transaction, so the fees of proxy calls wrapped in other calls are only
+/// refunded if the whole call fails.
+#[cfg(any(test, feature = "proxy"))]
+pub struct ProxyCalls<T>(PhantomData<T>);
+
+#[cfg(any(test, feature = "proxy"))]
+impl<T> TransferMatcher<T> for ProxyCalls<T>
+where
+    T: Config + pallet_proxy::Config,
+    <T as frame_system::Config>::RuntimeCall: IsSubType<pallet_proxy::Call<T>>,
+{
+    fn match_call(
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        let (delegate, real, force_proxy_type, call) = match call.is_sub_type() {
+            Some(pallet_proxy::Call::proxy {
+                real,
+                force_proxy_type,
+                call,
+            }) => (Ok(who.clone()), real, force_proxy_type, call),
+            Some(pallet_proxy::Call::proxy_announced {
+                delegate,
+                real,
+                force_proxy_type,
+                call,
+            }) => (
+                T::Lookup::lookup(delegate.clone()),
+                real,
+                force_proxy_type,
+                call,
+            ),
+            _ => return None,
+        };
+        let (Ok(delegate), Ok(real)) = (delegate, T::Lookup::lookup(real.clone())) else {
+            return None;
+        };
+        // Only charge the proxied account if `who` may actually make this call on its behalf.
+        let proxy =
+            pallet_proxy::Pallet::<T>::find_proxy(&real, &delegate, force_proxy_type.clone())
+                .ok()?;
+        if !proxy.proxy_type.filter(&**call) {
+            return None;
+        }
+        T::TransferMatcher::match_call(&real, (**call).into_ref(), transfers).map(|settlement| {
+            match settlement {
+                Settlement::Atomic => Settlement::Debited,
+                _ => Settlement::Charged,
+            }
+        })
+    }
+
+    fn max_transfers(call: &<T as frame_system::Config>::RuntimeCall) -> u32 {
+        match call.is_sub_type() {
+            Some(pallet_proxy::Call::proxy { call, .. })
+            | Some(pallet_proxy::Call::proxy_announced { call, .. }) => {
+                T::TransferMatcher::max_transfers((**call).into_ref())
+            }
+            _ => 0,
+        }
+    }
+
+    fn weight(call: &<T as frame_system::Config>::RuntimeCall) -> Weight {
+        match call.is_sub_type() {
+            // Finding the proxy, and reading the balances of each transfer before and after
+            // dispatching to settle the call.
+            Some(pallet_proxy::Call::proxy { call, .. })
+            | Some(pallet_proxy::Call::proxy_announced { call, .. }) => {
+                let call = (**call).into_ref();
+                let balances = T::TransferMatcher::max_transfers(call).saturating_mul(2);
+                T::TransferMatcher::weight(call)
+                    .saturating_add(T::DbWeight::get().reads(1 + balances as u64))
+            }
+            _ => Weight::zero(),
+        }
+    }
+}
+
+/// Matches `fc-pallet-payments` payment creation.
+#[cfg(any(test, feature = "payments"))]
+pub struct PaymentsPay<T>(PhantomData<T>);
+
+#[cfg(any(test, feature = "payments"))]
+impl<T> TransferMatcher<T> for PaymentsPay<T>
+where
+    T: Config + fc_pallet_payments::Config,
+    T::RuntimeCall: IsSubType<fc_pallet_payments::Call<T>>,
+    fc_pallet_payments::AssetIdOf<T>: Into<AssetIdOf<T>>,
+    fc_pallet_payments::BalanceOf<T>: Into<BalanceOf<T>>,
+{
+    fn match_call(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        transfers: &mut Vec<TransferOf<T>>,
+    ) -> Option<Settlement> {
+        match call.is_sub_type() {
+            Some(fc_pallet_payments::Call::pay { asset, amount, .. }) => {
+                transfers.push((who.clone(), asset.clone().into(), (*amount).into()));
+                Some(Settlement::Atomic)
+            }
+            _ => None,
+        }
+    }
+
+    fn max_transfers(call: &T::RuntimeCall) -> u32 {
+        match call.is_sub_type() {
+            Some(fc_pallet_payments::Call::pay { .. }) => 1,
+            _ => 0,
+        }
+    }
+}
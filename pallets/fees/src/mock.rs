@@ -1,7 +1,11 @@
 pub use crate::{types::*, Config};
 use frame::{
     deps::{
-        frame_support::{derive_impl, parameter_types, traits::AsEnsureOriginWithArg},
+        frame_support::{
+            derive_impl, parameter_types,
+            traits::{AsEnsureOriginWithArg, EqualPrivilegeOnly, InstanceFilter},
+            PalletId,
+        },
         frame_system::{EnsureRoot, EnsureSigned},
         sp_runtime::BuildStorage,
     },
@@ -24,6 +28,8 @@ pub const FEE_RECEIVER_COMMUNITY: AccountId = 51;
 pub const ASSET_ADMIN: AccountId = 3;
 
 pub const ASSET_ID: AssetId = 1;
+pub const NATIVE_ASSET: AssetId = 0;
+pub const STABLE_ID: AssetId = 2;
 pub const INITIAL_BALANCE: Balance = 10_000;
 
 #[frame_construct_runtime]
@@ -42,12 +48,24 @@ pub mod runtime {
 
     #[runtime::pallet_index(0)]
     pub type System = frame_system;
+    #[runtime::pallet_index(1)]
+    pub type Utility = pallet_utility;
+    #[runtime::pallet_index(2)]
+    pub type Proxy = pallet_proxy;
+    #[runtime::pallet_index(5)]
+    pub type Scheduler = pallet_scheduler;
+    #[runtime::pallet_index(6)]
+    pub type Preimage = pallet_preimage;
     #[runtime::pallet_index(10)]
     pub type Balances = pallet_balances;
     #[runtime::pallet_index(11)]
     pub type Assets = pallet_assets;
+    #[runtime::pallet_index(12)]
+    pub type AssetsHolder = pallet_assets_holder;
     #[runtime::pallet_index(20)]
     pub type Fees = crate;
+    #[runtime::pallet_index(21)]
+    pub type Payments = fc_pallet_payments;
 }
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
@@ -67,7 +85,135 @@ impl pallet_assets::Config for Test {
     type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
     type ForceOrigin = EnsureRoot<AccountId>;
     type Freezer = ();
-    type Holder = ();
+    type Holder = AssetsHolder;
+}
+
+impl pallet_assets_holder::Config for Test {
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type RuntimeEvent = RuntimeEvent;
+}
+
+impl pallet_utility::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = ();
+}
+
+/// Proxy types, to test the matcher only charges fees on calls a proxy may make.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+)]
+pub enum ProxyType {
+    #[default]
+    Any,
+    NonTransfer,
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+    fn filter(&self, call: &RuntimeCall) -> bool {
+        match self {
+            ProxyType::Any => true,
+            ProxyType::NonTransfer => {
+                !matches!(call, RuntimeCall::Assets(_) | RuntimeCall::Balances(_))
+            }
+        }
+    }
+
+    fn is_superset(&self, o: &Self) -> bool {
+        self == &ProxyType::Any || self == o
+    }
+}
+
+impl pallet_proxy::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ConstU64<1>;
+    type ProxyDepositFactor = ConstU64<1>;
+    type MaxProxies = ConstU32<4>;
+    type WeightInfo = ();
+    type MaxPending = ConstU32<2>;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = ConstU64<1>;
+    type AnnouncementDepositFactor = ConstU64<1>;
+    type BlockNumberProvider = System;
+}
+
+parameter_types! {
+    pub MaxWeight: Weight = Weight::from_parts(2_000_000_000_000, u64::MAX);
+    pub const PaymentPalletId: PalletId = PalletId(*b"payments");
+    pub const IncentivePercentage: Percent = Percent::from_percent(10);
+}
+
+impl pallet_preimage::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type ManagerOrigin = EnsureRoot<AccountId>;
+    type Consideration = ();
+    type WeightInfo = ();
+}
+
+impl pallet_scheduler::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeOrigin = RuntimeOrigin;
+    type PalletsOrigin = OriginCaller;
+    type RuntimeCall = RuntimeCall;
+    type MaximumWeight = MaxWeight;
+    type ScheduleOrigin = EnsureRoot<AccountId>;
+    type OriginPrivilegeCmp = EqualPrivilegeOnly;
+    type MaxScheduledPerBlock = ConstU32<100>;
+    type WeightInfo = ();
+    type Preimages = Preimage;
+    type BlockNumberProvider = System;
+}
+
+/// Sequential payment ids for tests.
+pub struct PaymentIdGenerator;
+impl fc_pallet_payments::GeneratePaymentId<AccountId> for PaymentIdGenerator {
+    type PaymentId = u32;
+
+    fn generate(_: &AccountId, _: &AccountId) -> Option<u32> {
+        let id = fc_pallet_payments::Payment::<Test>::iter().count() as u32;
+        Some(id + 1)
+    }
+}
+
+impl fc_pallet_payments::Config for Test {
+    type PalletsOrigin = OriginCaller;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type WeightInfo = ();
+    type SenderOrigin = EnsureSigned<AccountId>;
+    type BeneficiaryOrigin = EnsureSigned<AccountId>;
+    type DisputeResolver = frame_system::EnsureRootWithSuccess<AccountId, ConstU64<1>>;
+    type PaymentId = u32;
+    type Assets = Assets;
+    type AssetsHold = AssetsHolder;
+    type BlockNumberProvider = System;
+    type FeeHandler = ();
+    type Scheduler = Scheduler;
+    type Preimages = ();
+    type OnPaymentStatusChanged = ();
+    type GeneratePaymentId = PaymentIdGenerator;
+    type PalletId = PaymentPalletId;
+    type IncentivePercentage = IncentivePercentage;
+    type MaxRemarkLength = ConstU32<50>;
+    type MaxFees = ConstU32<50>;
+    type MaxDiscounts = ConstU32<50>;
+    type CancelBufferBlockLength = ConstU64<10>;
 }
 
 /// Dummy community detector for tests.
@@ -75,8 +221,9 @@ pub struct DummyAccountCommunity;
 impl AccountCommunity<AccountId, CommunityId> for DummyAccountCommunity {
     fn community_of(who: &AccountId) -> Option<CommunityId> {
         match who {
-            100..=199 => Some(1), // community 1
-            200..=299 => Some(2), // community 2
+            100..=199 => Some(1),     // community 1
+            200..=299 => Some(2),     // community 2
+            1_000..=1_099 => Some(0), // community 0, the one benchmarks use
             _ => None,
         }
     }
@@ -86,8 +233,28 @@ parameter_types! {
     pub const MaxFeeNameLen: u32 = 64;
     pub const MaxProtocolFees: u32 = 10;
     pub const MaxCommunityFees: u32 = 10;
+    pub const NativeAsset: AssetId = NATIVE_ASSET;
+    pub static SwapPairs: Vec<(AssetId, AssetId)> = vec![(ASSET_ID, STABLE_ID)];
+}
+
+/// Quotes the assets of [`SwapPairs`] at half their amount, e.g. `ASSET_ID` in `STABLE_ID`.
+pub struct MockSwap;
+impl SwapQuote<AssetId, Balance> for MockSwap {
+    fn quote_swap(asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<Balance> {
+        SwapPairs::get()
+            .contains(&(asset_in, asset_out))
+            .then_some(amount_in / 2)
+    }
 }
 
+pub type TransferMatcher = (
+    crate::AssetsTransfers<Test>,
+    crate::BalancesTransfers<Test>,
+    crate::UtilityCalls<Test>,
+    crate::ProxyCalls<Test>,
+    crate::PaymentsPay<Test>,
+);
+
 impl Config for Test {
     type CommunityId = CommunityId;
     type MaxFeeNameLen = MaxFeeNameLen;
@@ -96,6 +263,29 @@ impl Config for Test {
     type AdminOrigin = EnsureRoot<AccountId>;
     type CommunityOrigin = EnsureSigned<AccountId>; // signer = community id for tests
     type CommunityDetector = DummyAccountCommunity;
+    type NativeCurrency = Balances;
+    type NativeAsset = NativeAsset;
+    type TransferMatcher = TransferMatcher;
+    type Swap = MockSwap;
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = BenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::types::BenchmarkHelper<Test> for BenchmarkHelper {
+    fn community_member(community: &CommunityId) -> AccountId {
+        match community {
+            0 => 1_000,
+            c => c * 100,
+        }
+    }
+
+    fn quote_swap(asset_in: &AssetId, asset_out: &AssetId) {
+        SwapPairs::mutate(|pairs| pairs.push((*asset_in, *asset_out)));
+    }
 }
 
 pub(crate) fn new_test_ext() -> TestExternalities {
@@ -119,8 +309,14 @@ pub(crate) fn new_test_ext() -> TestExternalities {
     .unwrap();
 
     pallet_assets::GenesisConfig::<Test> {
-        assets: vec![(ASSET_ID, ASSET_ADMIN, true, 1)],
-        metadata: vec![(ASSET_ID, "Test Token".into(), "TEST".into(), 0)],
+        assets: vec![
+            (ASSET_ID, ASSET_ADMIN, true, 1),
+            (STABLE_ID, ASSET_ADMIN, true, 1),
+        ],
+        metadata: vec![
+            (ASSET_ID, "Test Token".into(), "TEST".into(), 0),
+            (STABLE_ID, "Stable Token".into(), "USD".into(), 0),
+        ],
         accounts: vec![
             (ASSET_ID, MEMBER_1A, INITIAL_BALANCE),
             (ASSET_ID, MEMBER_1B, INITIAL_BALANCE),
@@ -128,6 +324,8 @@ pub(crate) fn new_test_ext() -> TestExternalities {
             (ASSET_ID, NO_COMMUNITY, INITIAL_BALANCE),
             (ASSET_ID, FEE_RECEIVER_PROTOCOL, 0),
             (ASSET_ID, FEE_RECEIVER_COMMUNITY, 0),
+            (STABLE_ID, MEMBER_1A, INITIAL_BALANCE),
+            (STABLE_ID, NO_COMMUNITY, INITIAL_BALANCE),
         ],
         next_asset_id: None,
         reserves: vec![],
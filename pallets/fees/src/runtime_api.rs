@@ -0,0 +1,25 @@
+//! Runtime API to quote the fees charged on transfers.
+
+use alloc::vec::Vec;
+use codec::Codec;
+use sp_runtime::DispatchError;
+
+use crate::types::FeeQuote;
+
+frame::deps::sp_api::decl_runtime_apis! {
+    /// Lets wallets show the fees of a transfer before signing it.
+    pub trait FeesApi<AccountId, AssetId, Balance>
+    where
+        AccountId: Codec,
+        AssetId: Codec,
+        Balance: Codec,
+    {
+        /// The itemized fees charged when `who` transfers `amount` of `asset`,
+        /// as they would currently apply, or why the transfer can't be charged fees.
+        fn quote_fees(
+            who: AccountId,
+            asset: AssetId,
+            amount: Balance,
+        ) -> Result<Vec<FeeQuote<AccountId, AssetId, Balance>>, DispatchError>;
+    }
+}
@@ -1,12 +1,14 @@
 use frame::deps::frame_support::{assert_noop, assert_ok, traits::fungibles::Mutate};
 use frame::deps::frame_support::{dispatch::DispatchInfo, traits::tokens::Preservation};
 use sp_runtime::{
-    traits::DispatchTransaction, transaction_validity::InvalidTransaction, BoundedVec, Permill,
+    traits::{DispatchTransaction, Dispatchable},
+    transaction_validity::InvalidTransaction,
+    BoundedVec, Permill,
 };
 
 use crate::{
     mock::*, types::FeeConfig, ChargeFees, CommunityFees as CommunityFeesStorage, Error, Event,
-    ProtocolFees, WithFees,
+    NativeWithFees, ProtocolFees, WithFees,
 };
 
 fn fee_name(s: &[u8]) -> BoundedVec<u8, MaxFeeNameLen> {
@@ -18,6 +20,11 @@ fn balance_of(asset: AssetId, who: AccountId) -> Balance {
     <Assets as Inspect<_>>::balance(asset, &who)
 }
 
+fn native_balance_of(who: AccountId) -> Balance {
+    use frame::deps::frame_support::traits::fungible::Inspect;
+    <Balances as Inspect<_>>::balance(&who)
+}
+
 /// Helper to run the ChargeFees extension lifecycle on a call.
 fn run_extension(
     who: AccountId,
@@ -544,22 +551,15 @@ mod adapter {
 }
 
 // ============================================================================
-// Transaction extension tests
+// Native adapter (NativeWithFees) tests
 // ============================================================================
 
-mod extension {
+mod native_adapter {
     use super::*;
-
-    fn transfer_call(dest: AccountId, amount: Balance) -> RuntimeCall {
-        RuntimeCall::Assets(pallet_assets::Call::transfer {
-            id: ASSET_ID,
-            target: dest,
-            amount,
-        })
-    }
+    use frame::deps::frame_support::traits::fungible;
 
     #[test]
-    fn charges_protocol_fee_on_assets_transfer() {
+    fn transfer_with_protocol_and_community_fees_charges_on_top() {
         new_test_ext().execute_with(|| {
             assert_ok!(Fees::set_protocol_fee(
                 RuntimeOrigin::root(),
@@ -567,116 +567,1329 @@ mod extension {
                 FeeConfig::Percentage(Permill::from_percent(5)),
                 FEE_RECEIVER_PROTOCOL,
             ));
+            assert_ok!(Fees::set_community_fee(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeConfig::Fixed(20),
+                FEE_RECEIVER_COMMUNITY,
+            ));
 
-            let call = transfer_call(MEMBER_1B, 1000);
-            assert_ok!(run_extension(NO_COMMUNITY, &call));
+            assert_ok!(
+                <NativeWithFees<Test> as fungible::Mutate<AccountId>>::transfer(
+                    &MEMBER_1A,
+                    &NO_COMMUNITY,
+                    1000,
+                    Preservation::Preserve,
+                )
+            );
 
-            // Fee was charged in prepare (before the actual call ran)
-            // 5% of 1000 = 50
+            // Sender pays 1000 (transfer) + 50 (5% fee) + 20 (community fee)
+            assert_eq!(native_balance_of(MEMBER_1A), INITIAL_BALANCE - 1070);
+            assert_eq!(native_balance_of(NO_COMMUNITY), INITIAL_BALANCE + 1000);
             assert_eq!(
-                balance_of(ASSET_ID, NO_COMMUNITY),
-                INITIAL_BALANCE - 50 // only fees deducted (test_run uses a noop call body)
+                native_balance_of(FEE_RECEIVER_PROTOCOL),
+                INITIAL_BALANCE + 50
+            );
+            assert_eq!(
+                native_balance_of(FEE_RECEIVER_COMMUNITY),
+                INITIAL_BALANCE + 20
+            );
+            // Assets are untouched
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            System::assert_has_event(
+                Event::FeesCharged {
+                    who: MEMBER_1A,
+                    asset: NATIVE_ASSET,
+                    total_fees: 70,
+                }
+                .into(),
             );
-            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 50);
         });
     }
 
     #[test]
-    fn charges_community_and_protocol_fees() {
+    fn transfer_without_fees_works_normally() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(
+                <NativeWithFees<Test> as fungible::Mutate<AccountId>>::transfer(
+                    &MEMBER_1A,
+                    &NO_COMMUNITY,
+                    500,
+                    Preservation::Preserve,
+                )
+            );
+
+            assert_eq!(native_balance_of(MEMBER_1A), INITIAL_BALANCE - 500);
+            assert_eq!(native_balance_of(NO_COMMUNITY), INITIAL_BALANCE + 500);
+        });
+    }
+
+    #[test]
+    fn atomic_rollback_if_transfer_fails() {
         new_test_ext().execute_with(|| {
             assert_ok!(Fees::set_protocol_fee(
                 RuntimeOrigin::root(),
                 fee_name(b"protocol"),
-                FeeConfig::Fixed(10),
+                FeeConfig::Fixed(100),
                 FEE_RECEIVER_PROTOCOL,
             ));
-            assert_ok!(Fees::set_community_fee(
-                RuntimeOrigin::signed(1), // community 1
-                fee_name(b"community"),
-                FeeConfig::Fixed(20),
-                FEE_RECEIVER_COMMUNITY,
-            ));
 
-            let call = transfer_call(MEMBER_1B, 500);
-            assert_ok!(run_extension(MEMBER_1A, &call));
+            // The fee is affordable, but the transfer on top of it is not
+            assert_noop!(
+                <NativeWithFees<Test> as fungible::Mutate<AccountId>>::transfer(
+                    &NO_COMMUNITY,
+                    &MEMBER_1A,
+                    INITIAL_BALANCE - 50,
+                    Preservation::Preserve,
+                ),
+                sp_runtime::TokenError::FundsUnavailable,
+            );
 
-            // Protocol + community fees charged
+            assert_eq!(native_balance_of(NO_COMMUNITY), INITIAL_BALANCE);
+            assert_eq!(native_balance_of(FEE_RECEIVER_PROTOCOL), INITIAL_BALANCE);
+        });
+    }
+
+    #[test]
+    fn reads_are_delegated() {
+        new_test_ext().execute_with(|| {
+            use fungible::Inspect;
             assert_eq!(
-                balance_of(ASSET_ID, MEMBER_1A),
-                INITIAL_BALANCE - 30 // 10 + 20
+                <NativeWithFees<Test> as Inspect<AccountId>>::balance(&MEMBER_1A),
+                <Balances as Inspect<AccountId>>::balance(&MEMBER_1A),
+            );
+            assert_eq!(
+                <NativeWithFees<Test> as Inspect<AccountId>>::total_issuance(),
+                <Balances as Inspect<AccountId>>::total_issuance(),
+            );
+            assert_eq!(
+                <NativeWithFees<Test> as Inspect<AccountId>>::minimum_balance(),
+                <Balances as Inspect<AccountId>>::minimum_balance(),
             );
-            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
-            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_COMMUNITY), 20);
         });
     }
+}
+
+// ============================================================================
+// Fee asset tests
+// ============================================================================
+
+mod fee_assets {
+    use super::*;
+    use crate::{types::FeeAsset, ConversionRates};
+    use sp_runtime::FixedU128;
+
+    fn protocol_fee_in(config: FeeConfig<Balance>, asset: FeeAsset<AssetId>) {
+        assert_ok!(Fees::set_protocol_fee(
+            RuntimeOrigin::root(),
+            fee_name(b"protocol"),
+            config,
+            FEE_RECEIVER_PROTOCOL,
+        ));
+        assert_ok!(Fees::set_protocol_fee_asset(
+            RuntimeOrigin::root(),
+            fee_name(b"protocol"),
+            asset,
+        ));
+    }
+
+    fn transfer_call(dest: AccountId, amount: Balance) -> RuntimeCall {
+        RuntimeCall::Assets(pallet_assets::Call::transfer {
+            id: ASSET_ID,
+            target: dest,
+            amount,
+        })
+    }
 
     #[test]
-    fn no_fees_on_non_asset_calls() {
+    fn set_protocol_fee_asset_works() {
         new_test_ext().execute_with(|| {
+            protocol_fee_in(FeeConfig::Fixed(10), FeeAsset::Swap(STABLE_ID));
+            assert_eq!(
+                ProtocolFees::<Test>::get()[0].asset,
+                FeeAsset::Swap(STABLE_ID)
+            );
+            System::assert_last_event(
+                Event::ProtocolFeeAssetSet {
+                    name: fee_name(b"protocol"),
+                    asset: FeeAsset::Swap(STABLE_ID),
+                }
+                .into(),
+            );
+
+            // Updating the fee keeps the asset it's paid in
             assert_ok!(Fees::set_protocol_fee(
                 RuntimeOrigin::root(),
                 fee_name(b"protocol"),
-                FeeConfig::Fixed(100),
+                FeeConfig::Fixed(20),
                 FEE_RECEIVER_PROTOCOL,
             ));
+            assert_eq!(
+                ProtocolFees::<Test>::get()[0].asset,
+                FeeAsset::Swap(STABLE_ID)
+            );
+        });
+    }
 
-            // A non-asset call (system remark)
-            let call = RuntimeCall::System(frame::deps::frame_system::Call::remark {
-                remark: b"hello".to_vec(),
-            });
-            assert_ok!(run_extension(MEMBER_1A, &call));
+    #[test]
+    fn set_fee_asset_fails_for_unknown_fee() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(
+                Fees::set_protocol_fee_asset(
+                    RuntimeOrigin::root(),
+                    fee_name(b"nope"),
+                    FeeAsset::Swap(STABLE_ID),
+                ),
+                Error::<Test>::FeeNotFound
+            );
+            assert_noop!(
+                Fees::set_community_fee_asset(
+                    RuntimeOrigin::signed(1),
+                    fee_name(b"nope"),
+                    FeeAsset::Swap(STABLE_ID),
+                ),
+                Error::<Test>::FeeNotFound
+            );
+        });
+    }
 
-            // No fees charged
-            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
-            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+    #[test]
+    fn set_community_fee_asset_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_community_fee(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeConfig::Fixed(10),
+                FEE_RECEIVER_COMMUNITY,
+            ));
+            assert_ok!(Fees::set_community_fee_asset(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeAsset::FixedRate(STABLE_ID),
+            ));
+            assert_eq!(
+                CommunityFeesStorage::<Test>::get(1)[0].asset,
+                FeeAsset::FixedRate(STABLE_ID)
+            );
+            System::assert_last_event(
+                Event::CommunityFeeAssetSet {
+                    community: 1,
+                    name: fee_name(b"community"),
+                    asset: FeeAsset::FixedRate(STABLE_ID),
+                }
+                .into(),
+            );
         });
     }
 
     #[test]
-    fn rejects_if_insufficient_balance_for_fees() {
+    fn set_conversion_rate_works() {
         new_test_ext().execute_with(|| {
-            // 50% fee + transfer of almost full balance = unaffordable
-            assert_ok!(Fees::set_protocol_fee(
+            let rate = FixedU128::from_rational(1, 2);
+            assert_noop!(
+                Fees::set_conversion_rate(
+                    RuntimeOrigin::signed(MEMBER_1A),
+                    ASSET_ID,
+                    STABLE_ID,
+                    Some(rate)
+                ),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_noop!(
+                Fees::set_conversion_rate(
+                    RuntimeOrigin::root(),
+                    ASSET_ID,
+                    STABLE_ID,
+                    Some(FixedU128::from_inner(0))
+                ),
+                Error::<Test>::InvalidFeeConfig
+            );
+
+            assert_ok!(Fees::set_conversion_rate(
                 RuntimeOrigin::root(),
-                fee_name(b"big_fee"),
-                FeeConfig::Percentage(Permill::from_percent(50)),
-                FEE_RECEIVER_PROTOCOL,
+                ASSET_ID,
+                STABLE_ID,
+                Some(rate)
             ));
+            assert_eq!(
+                ConversionRates::<Test>::get(ASSET_ID, STABLE_ID),
+                Some(rate)
+            );
+            System::assert_last_event(
+                Event::ConversionRateSet {
+                    from: ASSET_ID,
+                    to: STABLE_ID,
+                    rate: Some(rate),
+                }
+                .into(),
+            );
 
-            // Transfer that leaves no room for the 50% fee
-            let call = transfer_call(MEMBER_1B, INITIAL_BALANCE);
-            assert_noop!(run_extension(MEMBER_1A, &call), InvalidTransaction::Payment,);
-
-            // Nothing changed
-            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            assert_ok!(Fees::set_conversion_rate(
+                RuntimeOrigin::root(),
+                ASSET_ID,
+                STABLE_ID,
+                None
+            ));
+            assert_eq!(ConversionRates::<Test>::get(ASSET_ID, STABLE_ID), None);
         });
     }
 
     #[test]
-    fn refunds_fees_on_dispatch_failure() {
+    fn extension_charges_fee_at_fixed_rate() {
         new_test_ext().execute_with(|| {
-            assert_ok!(Fees::set_protocol_fee(
+            protocol_fee_in(
+                FeeConfig::Percentage(Permill::from_percent(5)),
+                FeeAsset::FixedRate(STABLE_ID),
+            );
+            assert_ok!(Fees::set_conversion_rate(
                 RuntimeOrigin::root(),
-                fee_name(b"protocol"),
-                FeeConfig::Fixed(50),
-                FEE_RECEIVER_PROTOCOL,
+                ASSET_ID,
+                STABLE_ID,
+                Some(FixedU128::from_rational(1, 2))
             ));
 
-            let ext = ChargeFees::<Test>::default();
-            let info = DispatchInfo::default();
-            let call = transfer_call(MEMBER_1B, 1000);
+            assert_ok!(run_extension(NO_COMMUNITY, &transfer_call(MEMBER_1B, 1000)));
 
-            // Simulate a failing dispatch
-            let result = ext.test_run(RuntimeOrigin::signed(MEMBER_1A), &call, &info, 0, 0, |_| {
-                Err(sp_runtime::DispatchError::Other("simulated failure").into())
+            // 5% of 1000 = 50, worth 25 in the stable asset
+            assert_eq!(balance_of(STABLE_ID, NO_COMMUNITY), INITIAL_BALANCE - 25);
+            assert_eq!(balance_of(STABLE_ID, FEE_RECEIVER_PROTOCOL), 25);
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+            System::assert_has_event(
+                Event::FeesCharged {
+                    who: NO_COMMUNITY,
+                    asset: STABLE_ID,
+                    total_fees: 25,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn extension_charges_fee_at_swap_price() {
+        new_test_ext().execute_with(|| {
+            protocol_fee_in(FeeConfig::Fixed(40), FeeAsset::Swap(STABLE_ID));
+
+            assert_ok!(run_extension(NO_COMMUNITY, &transfer_call(MEMBER_1B, 1000)));
+
+            // 40 quoted at half in the stable asset
+            assert_eq!(balance_of(STABLE_ID, NO_COMMUNITY), INITIAL_BALANCE - 20);
+            assert_eq!(balance_of(STABLE_ID, FEE_RECEIVER_PROTOCOL), 20);
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+        });
+    }
+
+    #[test]
+    fn rejects_fees_without_price() {
+        new_test_ext().execute_with(|| {
+            // No conversion rate set for the pair
+            protocol_fee_in(FeeConfig::Fixed(40), FeeAsset::FixedRate(STABLE_ID));
+
+            assert_eq!(
+                Fees::calculate_fees(ASSET_ID, &NO_COMMUNITY, 1000),
+                Err(Error::<Test>::FeeAssetUnpriced.into())
+            );
+            assert_eq!(
+                Fees::quote_fees(NO_COMMUNITY, ASSET_ID, 1000),
+                Err(Error::<Test>::FeeAssetUnpriced.into())
+            );
+            // Rather than charging the fee in the transferred asset
+            assert_noop!(
+                run_extension(NO_COMMUNITY, &transfer_call(MEMBER_1B, 1000)),
+                InvalidTransaction::Payment
+            );
+            assert_noop!(
+                <WithFees<Test> as Mutate<_>>::transfer(
+                    ASSET_ID,
+                    &NO_COMMUNITY,
+                    &MEMBER_1B,
+                    1000,
+                    Preservation::Preserve,
+                ),
+                Error::<Test>::FeeAssetUnpriced
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_fees_without_swap_quote() {
+        new_test_ext().execute_with(|| {
+            // The swap only quotes `ASSET_ID` into `STABLE_ID`, not the other way around
+            protocol_fee_in(FeeConfig::Fixed(40), FeeAsset::Swap(ASSET_ID));
+            let transfer = RuntimeCall::Assets(pallet_assets::Call::transfer {
+                id: STABLE_ID,
+                target: MEMBER_1B,
+                amount: 1000,
             });
 
-            // The dispatch result is the inner error
-            assert!(result.unwrap().is_err());
+            assert_noop!(
+                run_extension(NO_COMMUNITY, &transfer),
+                InvalidTransaction::Payment
+            );
+            assert_eq!(balance_of(STABLE_ID, NO_COMMUNITY), INITIAL_BALANCE);
+        });
+    }
 
-            // Fees should have been refunded
-            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
-            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+    #[test]
+    fn extension_rejects_if_insufficient_balance_in_fee_asset() {
+        new_test_ext().execute_with(|| {
+            protocol_fee_in(FeeConfig::Fixed(40), FeeAsset::Swap(STABLE_ID));
+
+            // MEMBER_1B holds no stable asset
+            assert_noop!(
+                run_extension(MEMBER_1B, &transfer_call(MEMBER_1A, 1000)),
+                InvalidTransaction::Payment,
+            );
+        });
+    }
+
+    #[test]
+    fn adapter_charges_fee_in_fee_asset() {
+        new_test_ext().execute_with(|| {
+            protocol_fee_in(FeeConfig::Fixed(40), FeeAsset::Swap(STABLE_ID));
+
+            assert_ok!(<WithFees<Test> as Mutate<AccountId>>::transfer(
+                ASSET_ID,
+                &NO_COMMUNITY,
+                &MEMBER_1A,
+                1000,
+                Preservation::Preserve,
+            ));
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE - 1000);
+            assert_eq!(balance_of(STABLE_ID, NO_COMMUNITY), INITIAL_BALANCE - 20);
+            assert_eq!(balance_of(STABLE_ID, FEE_RECEIVER_PROTOCOL), 20);
+        });
+    }
+}
+
+mod fee_caps {
+    use super::*;
+    use crate::{
+        types::{FeeCap, FeeSpend},
+        FeeCaps, FeesSpent, Pallet,
+    };
+
+    const CAP: FeeCap<Balance, u64> = FeeCap {
+        max: 100,
+        period: 10,
+    };
+
+    fn capped_protocol_fee(fee: Balance) {
+        assert_ok!(Fees::set_protocol_fee(
+            RuntimeOrigin::root(),
+            fee_name(b"protocol"),
+            FeeConfig::Fixed(fee),
+            FEE_RECEIVER_PROTOCOL,
+        ));
+        assert_ok!(Fees::set_fee_cap(
+            RuntimeOrigin::root(),
+            ASSET_ID,
+            Some(CAP)
+        ));
+    }
+
+    fn transfer_call(dest: AccountId, amount: Balance) -> RuntimeCall {
+        RuntimeCall::Assets(pallet_assets::Call::transfer {
+            id: ASSET_ID,
+            target: dest,
+            amount,
+        })
+    }
+
+    #[test]
+    fn set_fee_cap_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_fee_cap(
+                RuntimeOrigin::root(),
+                ASSET_ID,
+                Some(CAP)
+            ));
+            assert_eq!(FeeCaps::<Test>::get(ASSET_ID), Some(CAP));
+            System::assert_last_event(
+                Event::FeeCapSet {
+                    asset: ASSET_ID,
+                    cap: Some(CAP),
+                }
+                .into(),
+            );
+
+            assert_ok!(Fees::set_fee_cap(RuntimeOrigin::root(), ASSET_ID, None));
+            assert_eq!(FeeCaps::<Test>::get(ASSET_ID), None);
+        });
+    }
+
+    #[test]
+    fn set_fee_cap_requires_admin() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(
+                Fees::set_fee_cap(RuntimeOrigin::signed(MEMBER_1A), ASSET_ID, Some(CAP)),
+                sp_runtime::DispatchError::BadOrigin,
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_empty_period() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(
+                Fees::set_fee_cap(
+                    RuntimeOrigin::root(),
+                    ASSET_ID,
+                    Some(FeeCap {
+                        max: 100,
+                        period: 0
+                    })
+                ),
+                Error::<Test>::InvalidFeeConfig,
+            );
+        });
+    }
+
+    #[test]
+    fn stops_charging_once_cap_is_reached() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(40);
+
+            for _ in 0..4 {
+                assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            }
+
+            // 40 + 40 + 20 (up to the cap) + 0
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 100);
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE - 100);
+            assert_eq!(
+                FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID),
+                Some(FeeSpend {
+                    since: 1,
+                    paid: 100
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn caps_are_tracked_per_account() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(100);
+
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            assert_ok!(run_extension(NO_COMMUNITY, &transfer_call(MEMBER_1B, 1000)));
+
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 200);
+        });
+    }
+
+    #[test]
+    fn validates_batches_against_the_cap_left_by_earlier_transfers() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(100);
+
+            // Enough for both transfers and the capped fees, not for each transfer paying 100
+            let half = (INITIAL_BALANCE - 100) / 2;
+            let call = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![
+                    transfer_call(MEMBER_1B, half),
+                    transfer_call(MEMBER_2A, half),
+                ],
+            });
+            assert_ok!(run_extension(MEMBER_1A, &call));
+
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 100);
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE - 100);
+        });
+    }
+
+    #[test]
+    fn quotes_zero_fees_once_capped() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(100);
+            assert_eq!(
+                Pallet::<Test>::calculate_fees(ASSET_ID, &MEMBER_1A, 1000),
+                Ok(vec![(FEE_RECEIVER_PROTOCOL, ASSET_ID, 100)])
+            );
+
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+
+            assert_eq!(
+                Pallet::<Test>::calculate_fees(ASSET_ID, &MEMBER_1A, 1000),
+                Ok(vec![])
+            );
+        });
+    }
+
+    #[test]
+    fn cap_resets_after_period() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(100);
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+
+            // Still within the period
+            System::set_block_number(10);
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 100);
+
+            System::set_block_number(11);
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 200);
+            assert_eq!(
+                FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID),
+                Some(FeeSpend {
+                    since: 11,
+                    paid: 100
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn uncapped_assets_are_not_tracked() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(40);
+            assert_ok!(Fees::set_fee_cap(RuntimeOrigin::root(), ASSET_ID, None));
+
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 40);
+            assert_eq!(FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID), None);
+        });
+    }
+
+    #[test]
+    fn refunded_fees_do_not_count_towards_cap() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(40);
+
+            let ext = ChargeFees::<Test>::default();
+            let info = DispatchInfo::default();
+            let call = transfer_call(MEMBER_1B, 1000);
+            let result = ext.test_run(RuntimeOrigin::signed(MEMBER_1A), &call, &info, 0, 0, |_| {
+                Err(sp_runtime::DispatchError::Other("simulated failure").into())
+            });
+            assert!(result.unwrap().is_err());
+
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            // Nothing's left to count towards the cap
+            assert_eq!(FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID), None);
+        });
+    }
+
+    #[test]
+    fn prunes_fees_spent_once_uncapped() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(40);
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            assert!(FeesSpent::<Test>::contains_key(MEMBER_1A, ASSET_ID));
+
+            // The next fee paid in the asset drops what was tracked for it
+            assert_ok!(Fees::set_fee_cap(RuntimeOrigin::root(), ASSET_ID, None));
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+            assert_eq!(FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID), None);
+        });
+    }
+
+    #[test]
+    fn prunes_fees_spent_refunded_after_the_period() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(40);
+            assert_ok!(run_extension(MEMBER_1A, &transfer_call(MEMBER_1B, 1000)));
+
+            // Refunding fees once the period is over leaves nothing to count towards the cap
+            System::set_block_number(11);
+            Pallet::<Test>::release_fees_spent(&MEMBER_1A, &ASSET_ID, 10);
+            assert_eq!(FeesSpent::<Test>::get(MEMBER_1A, ASSET_ID), None);
+        });
+    }
+
+    #[test]
+    fn adapter_respects_cap() {
+        new_test_ext().execute_with(|| {
+            capped_protocol_fee(60);
+
+            for _ in 0..2 {
+                assert_ok!(<WithFees<Test> as Mutate<AccountId>>::transfer(
+                    ASSET_ID,
+                    &NO_COMMUNITY,
+                    &MEMBER_1A,
+                    1000,
+                    Preservation::Preserve,
+                ));
+            }
+
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 100);
+        });
+    }
+}
+
+mod genesis {
+    use super::*;
+    use crate::{types::FeeAsset, GenesisConfig};
+    use frame::{deps::frame_system, testing_prelude::TestExternalities};
+    use sp_runtime::BuildStorage;
+
+    #[test]
+    fn sets_protocol_and_community_fees() {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .unwrap();
+        GenesisConfig::<Test> {
+            protocol_fees: vec![(
+                b"protocol".to_vec(),
+                FeeConfig::Fixed(10),
+                FEE_RECEIVER_PROTOCOL,
+                FeeAsset::Transferred,
+            )],
+            community_fees: vec![(
+                1,
+                (
+                    b"community".to_vec(),
+                    FeeConfig::Percentage(Permill::from_percent(1)),
+                    FEE_RECEIVER_COMMUNITY,
+                    FeeAsset::Swap(STABLE_ID),
+                ),
+            )],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        TestExternalities::new(t).execute_with(|| {
+            let protocol = ProtocolFees::<Test>::get();
+            assert_eq!(protocol.len(), 1);
+            assert_eq!(protocol[0].name, fee_name(b"protocol"));
+            assert_eq!(protocol[0].config, FeeConfig::Fixed(10));
+            assert_eq!(protocol[0].beneficiary, FEE_RECEIVER_PROTOCOL);
+
+            let community = CommunityFeesStorage::<Test>::get(1);
+            assert_eq!(community.len(), 1);
+            assert_eq!(community[0].name, fee_name(b"community"));
+            assert_eq!(community[0].asset, FeeAsset::Swap(STABLE_ID));
+            assert!(CommunityFeesStorage::<Test>::get(2).is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid fee config")]
+    fn rejects_invalid_fee_config() {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .unwrap();
+        GenesisConfig::<Test> {
+            protocol_fees: vec![(
+                b"protocol".to_vec(),
+                FeeConfig::PercentageClamped {
+                    rate: Permill::from_percent(1),
+                    min: 10,
+                    max: 5,
+                },
+                FEE_RECEIVER_PROTOCOL,
+                FeeAsset::Transferred,
+            )],
+            community_fees: vec![],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    }
+}
+
+mod quotes {
+    use super::*;
+    use crate::{
+        types::{FeeAsset, FeeCap, FeeQuote},
+        Pallet,
+    };
+
+    #[test]
+    fn itemizes_protocol_and_community_fees() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(10),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+            assert_ok!(Fees::set_community_fee(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeConfig::Fixed(40),
+                FEE_RECEIVER_COMMUNITY,
+            ));
+            assert_ok!(Fees::set_community_fee_asset(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeAsset::Swap(STABLE_ID),
+            ));
+
+            assert_eq!(
+                Pallet::<Test>::quote_fees(MEMBER_1A, ASSET_ID, 1000),
+                Ok(vec![
+                    FeeQuote {
+                        name: b"protocol".to_vec(),
+                        beneficiary: FEE_RECEIVER_PROTOCOL,
+                        asset: ASSET_ID,
+                        amount: 10,
+                    },
+                    FeeQuote {
+                        name: b"community".to_vec(),
+                        beneficiary: FEE_RECEIVER_COMMUNITY,
+                        asset: STABLE_ID,
+                        amount: 20,
+                    },
+                ])
+            );
+
+            // Non-members only pay protocol fees
+            assert_eq!(
+                Pallet::<Test>::quote_fees(NO_COMMUNITY, ASSET_ID, 1000)
+                    .unwrap()
+                    .len(),
+                1
+            );
+        });
+    }
+
+    #[test]
+    fn includes_proportional_capping() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(60),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+            assert_ok!(Fees::set_community_fee(
+                RuntimeOrigin::signed(1),
+                fee_name(b"community"),
+                FeeConfig::Fixed(40),
+                FEE_RECEIVER_COMMUNITY,
+            ));
+
+            // Fees of 100 on a transfer of 50 are scaled down by half
+            let amounts: Vec<_> = Pallet::<Test>::quote_fees(MEMBER_1A, ASSET_ID, 50)
+                .unwrap()
+                .into_iter()
+                .map(|quote| quote.amount)
+                .collect();
+            assert_eq!(amounts, vec![30, 20]);
+        });
+    }
+
+    #[test]
+    fn matches_charged_fees_under_caps() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(40),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+            assert_ok!(Fees::set_fee_cap(
+                RuntimeOrigin::root(),
+                ASSET_ID,
+                Some(FeeCap {
+                    max: 50,
+                    period: 10
+                })
+            ));
+            assert_ok!(<WithFees<Test> as Mutate<AccountId>>::transfer(
+                ASSET_ID,
+                &NO_COMMUNITY,
+                &MEMBER_1A,
+                1000,
+                Preservation::Preserve,
+            ));
+
+            let quotes = Pallet::<Test>::quote_fees(NO_COMMUNITY, ASSET_ID, 1000).unwrap();
+            assert_eq!(quotes.len(), 1);
+            assert_eq!(quotes[0].amount, 10);
+        });
+    }
+}
+
+// ============================================================================
+// Transaction extension tests
+// ============================================================================
+
+mod extension {
+    use super::*;
+
+    fn transfer_call(dest: AccountId, amount: Balance) -> RuntimeCall {
+        RuntimeCall::Assets(pallet_assets::Call::transfer {
+            id: ASSET_ID,
+            target: dest,
+            amount,
+        })
+    }
+
+    #[test]
+    fn charges_protocol_fee_on_assets_transfer() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Percentage(Permill::from_percent(5)),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+
+            let call = transfer_call(MEMBER_1B, 1000);
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            // Fee was charged in prepare (before the actual call ran)
+            // 5% of 1000 = 50
+            assert_eq!(
+                balance_of(ASSET_ID, NO_COMMUNITY),
+                INITIAL_BALANCE - 50 // only fees deducted (test_run uses a noop call body)
+            );
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 50);
+        });
+    }
+
+    #[test]
+    fn charges_community_and_protocol_fees() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(10),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+            assert_ok!(Fees::set_community_fee(
+                RuntimeOrigin::signed(1), // community 1
+                fee_name(b"community"),
+                FeeConfig::Fixed(20),
+                FEE_RECEIVER_COMMUNITY,
+            ));
+
+            let call = transfer_call(MEMBER_1B, 500);
+            assert_ok!(run_extension(MEMBER_1A, &call));
+
+            // Protocol + community fees charged
+            assert_eq!(
+                balance_of(ASSET_ID, MEMBER_1A),
+                INITIAL_BALANCE - 30 // 10 + 20
+            );
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_COMMUNITY), 20);
+        });
+    }
+
+    #[test]
+    fn no_fees_on_non_asset_calls() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(100),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+
+            // A non-asset call (system remark)
+            let call = RuntimeCall::System(frame::deps::frame_system::Call::remark {
+                remark: b"hello".to_vec(),
+            });
+            assert_ok!(run_extension(MEMBER_1A, &call));
+
+            // No fees charged
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+        });
+    }
+
+    #[test]
+    fn rejects_if_insufficient_balance_for_fees() {
+        new_test_ext().execute_with(|| {
+            // 50% fee + transfer of almost full balance = unaffordable
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"big_fee"),
+                FeeConfig::Percentage(Permill::from_percent(50)),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+
+            // Transfer that leaves no room for the 50% fee
+            let call = transfer_call(MEMBER_1B, INITIAL_BALANCE);
+            assert_noop!(run_extension(MEMBER_1A, &call), InvalidTransaction::Payment,);
+
+            // Nothing changed
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+        });
+    }
+
+    #[test]
+    fn refunds_fees_on_dispatch_failure() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Fees::set_protocol_fee(
+                RuntimeOrigin::root(),
+                fee_name(b"protocol"),
+                FeeConfig::Fixed(50),
+                FEE_RECEIVER_PROTOCOL,
+            ));
+
+            let ext = ChargeFees::<Test>::default();
+            let info = DispatchInfo::default();
+            let call = transfer_call(MEMBER_1B, 1000);
+
+            // Simulate a failing dispatch
+            let result = ext.test_run(RuntimeOrigin::signed(MEMBER_1A), &call, &info, 0, 0, |_| {
+                Err(sp_runtime::DispatchError::Other("simulated failure").into())
+            });
+
+            // The dispatch result is the inner error
+            assert!(result.unwrap().is_err());
+
+            // Fees should have been refunded
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+        });
+    }
+
+    fn protocol_fixed_fee(amount: Balance) {
+        assert_ok!(Fees::set_protocol_fee(
+            RuntimeOrigin::root(),
+            fee_name(b"protocol"),
+            FeeConfig::Fixed(amount),
+            FEE_RECEIVER_PROTOCOL,
+        ));
+    }
+
+    /// Runs the ChargeFees extension lifecycle on a call, actually dispatching it.
+    fn dispatch_with_extension(
+        who: AccountId,
+        call: &RuntimeCall,
+    ) -> <ChargeFees<Test> as DispatchTransaction<RuntimeCall>>::Result {
+        let ext = ChargeFees::<Test>::default();
+        let info = DispatchInfo::default();
+        ext.test_run(RuntimeOrigin::signed(who), call, &info, 0, 0, |origin| {
+            call.clone().dispatch(origin)
+        })
+    }
+
+    #[test]
+    fn charges_fees_on_transfer_all() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let call = RuntimeCall::Assets(pallet_assets::Call::transfer_all {
+                id: ASSET_ID,
+                dest: MEMBER_1B,
+                keep_alive: false,
+            });
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            // The fee is set aside before sweeping the rest of the balance
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE - 10);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
+        });
+    }
+
+    #[test]
+    fn charges_native_fees_on_balances_transfer() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let call = RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+                dest: MEMBER_1B,
+                value: 1000,
+            });
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            // Fees are paid in the native currency, assets are untouched
+            assert_eq!(native_balance_of(NO_COMMUNITY), INITIAL_BALANCE - 10);
+            assert_eq!(
+                native_balance_of(FEE_RECEIVER_PROTOCOL),
+                INITIAL_BALANCE + 10
+            );
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+            System::assert_has_event(
+                Event::FeesCharged {
+                    who: NO_COMMUNITY,
+                    asset: NATIVE_ASSET,
+                    total_fees: 10,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn charges_fees_on_batched_transfers() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let call = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![
+                    transfer_call(MEMBER_1B, 100),
+                    RuntimeCall::System(frame::deps::frame_system::Call::remark {
+                        remark: b"hello".to_vec(),
+                    }),
+                    RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                        calls: vec![transfer_call(MEMBER_2A, 200)],
+                    }),
+                ],
+            });
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            // One fee per transfer, including the nested batch
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE - 20);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 20);
+        });
+    }
+
+    #[test]
+    fn rejects_batch_if_insufficient_balance_for_all_transfers() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            // Each transfer is affordable on its own, but not together
+            let call = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![
+                    transfer_call(MEMBER_1B, INITIAL_BALANCE / 2),
+                    transfer_call(MEMBER_2A, INITIAL_BALANCE / 2),
+                ],
+            });
+            assert_noop!(
+                run_extension(NO_COMMUNITY, &call),
+                InvalidTransaction::Payment,
+            );
+        });
+    }
+
+    /// A transfer of all but what's left of `who`'s balance once the fees of `transfers` are
+    /// charged, which fails as it would reap the account.
+    fn failing_transfer(who: AccountId, transfers: Balance) -> RuntimeCall {
+        RuntimeCall::Assets(pallet_assets::Call::transfer_keep_alive {
+            id: ASSET_ID,
+            target: MEMBER_2A,
+            amount: balance_of(ASSET_ID, who) - transfers,
+        })
+    }
+
+    #[test]
+    fn charges_every_transfer_of_non_atomic_batches() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            // A failing call doesn't fail these batches, so there's no telling which transfers
+            // went through: the fees of all of them are kept.
+            for batch in [
+                |calls| RuntimeCall::Utility(pallet_utility::Call::batch { calls }),
+                |calls| RuntimeCall::Utility(pallet_utility::Call::force_batch { calls }),
+                |calls| {
+                    RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                        calls: vec![RuntimeCall::Utility(pallet_utility::Call::batch { calls })],
+                    })
+                },
+            ] {
+                let before = balance_of(ASSET_ID, NO_COMMUNITY);
+                let fees = balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL);
+                let call = batch(vec![
+                    transfer_call(MEMBER_1B, 100),
+                    failing_transfer(NO_COMMUNITY, 100 + 20),
+                ]);
+                assert_ok!(dispatch_with_extension(NO_COMMUNITY, &call).unwrap());
+
+                assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), before - 100 - 20);
+                assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), fees + 20);
+            }
+        });
+    }
+
+    #[test]
+    fn refunds_non_atomic_batches_that_fail() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            // The batch is wrapped in one that fails with any of its calls
+            let call = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![
+                    RuntimeCall::Utility(pallet_utility::Call::batch {
+                        calls: vec![transfer_call(MEMBER_1B, 100)],
+                    }),
+                    failing_transfer(NO_COMMUNITY, 100 + 20),
+                ],
+            });
+            assert!(dispatch_with_extension(NO_COMMUNITY, &call)
+                .unwrap()
+                .is_err());
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+        });
+    }
+
+    #[test]
+    fn charges_derivative_account_on_as_derivative() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let derivative = pallet_utility::derivative_account_id(NO_COMMUNITY, 0);
+            assert_ok!(<Assets as Mutate<_>>::mint_into(
+                ASSET_ID,
+                &derivative,
+                1000
+            ));
+
+            let call = RuntimeCall::Utility(pallet_utility::Call::as_derivative {
+                index: 0,
+                call: Box::new(transfer_call(MEMBER_1B, 100)),
+            });
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            assert_eq!(balance_of(ASSET_ID, derivative), 990);
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+        });
+    }
+
+    #[test]
+    fn charges_real_account_on_proxy_calls() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+            assert_ok!(Proxy::add_proxy(
+                RuntimeOrigin::signed(NO_COMMUNITY),
+                MEMBER_1A,
+                ProxyType::Any,
+                0
+            ));
+
+            let call = RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+                real: NO_COMMUNITY,
+                force_proxy_type: None,
+                call: Box::new(transfer_call(MEMBER_1B, 100)),
+            });
+            // Fees are kept once the proxied call goes through
+            assert_ok!(dispatch_with_extension(MEMBER_1A, &call));
+
+            assert_eq!(
+                balance_of(ASSET_ID, NO_COMMUNITY),
+                INITIAL_BALANCE - 100 - 10
+            );
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
+        });
+    }
+
+    #[test]
+    fn ignores_proxy_calls_from_non_proxies() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let call = RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+                real: NO_COMMUNITY,
+                force_proxy_type: None,
+                call: Box::new(transfer_call(MEMBER_1B, 100)),
+            });
+            assert_ok!(run_extension(MEMBER_1A, &call));
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, MEMBER_1A), INITIAL_BALANCE);
+        });
+    }
+
+    fn add_proxy(proxy_type: ProxyType) {
+        assert_ok!(Proxy::add_proxy(
+            RuntimeOrigin::signed(NO_COMMUNITY),
+            MEMBER_1A,
+            proxy_type,
+            0
+        ));
+    }
+
+    fn proxy_call(call: RuntimeCall) -> RuntimeCall {
+        RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+            real: NO_COMMUNITY,
+            force_proxy_type: None,
+            call: Box::new(call),
+        })
+    }
+
+    #[test]
+    fn refunds_fees_of_proxied_transfers_that_failed() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+            add_proxy(ProxyType::Any);
+
+            // Once the fee is paid, the transfer would reap the account, and fails
+            let call = proxy_call(RuntimeCall::Assets(
+                pallet_assets::Call::transfer_keep_alive {
+                    id: ASSET_ID,
+                    target: MEMBER_1B,
+                    amount: INITIAL_BALANCE - 10,
+                },
+            ));
+            // The proxy call itself succeeds
+            assert_ok!(dispatch_with_extension(MEMBER_1A, &call).unwrap());
+            System::assert_has_event(
+                pallet_proxy::Event::ProxyExecuted {
+                    result: Err(pallet_assets::Error::<Test>::BalanceLow.into()),
+                }
+                .into(),
+            );
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 0);
+        });
+    }
+
+    #[test]
+    fn ignores_proxied_calls_the_proxy_type_filters() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+            add_proxy(ProxyType::NonTransfer);
+
+            let call = proxy_call(transfer_call(MEMBER_1B, 100));
+            assert_ok!(run_extension(MEMBER_1A, &call));
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE);
+        });
+    }
+
+    #[test]
+    fn keeps_the_fees_of_nested_proxied_transfers() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+            add_proxy(ProxyType::Any);
+
+            // Only the outcome of the outermost proxy call is known
+            let call = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![proxy_call(failing_transfer(NO_COMMUNITY, 10))],
+            });
+            assert_ok!(dispatch_with_extension(MEMBER_1A, &call).unwrap());
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE - 10);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
+        });
+    }
+
+    #[test]
+    fn weighs_what_matchers_read() {
+        new_test_ext().execute_with(|| {
+            use sp_runtime::traits::TransactionExtension;
+            let ext = ChargeFees::<Test>::default();
+
+            let transfer = transfer_call(MEMBER_1B, 100);
+            let transfer_all = RuntimeCall::Assets(pallet_assets::Call::transfer_all {
+                id: ASSET_ID,
+                dest: MEMBER_1B,
+                keep_alive: false,
+            });
+            assert!(ext.weight(&transfer_all).all_gt(ext.weight(&transfer)));
+        });
+    }
+
+    #[test]
+    fn charges_fees_on_payments() {
+        new_test_ext().execute_with(|| {
+            protocol_fixed_fee(10);
+
+            let call = RuntimeCall::Payments(fc_pallet_payments::Call::pay {
+                beneficiary: MEMBER_1B,
+                asset: ASSET_ID,
+                amount: 500,
+                remark: None,
+            });
+            assert_ok!(run_extension(NO_COMMUNITY, &call));
+
+            assert_eq!(balance_of(ASSET_ID, NO_COMMUNITY), INITIAL_BALANCE - 10);
+            assert_eq!(balance_of(ASSET_ID, FEE_RECEIVER_PROTOCOL), 10);
         });
     }
 }
@@ -1,4 +1,6 @@
+use alloc::vec::Vec;
 use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame::deps::frame_system;
 use scale_info::TypeInfo;
 use sp_runtime::Permill;
 
@@ -10,6 +12,40 @@ pub type AssetIdOf<T> = <T as pallet_assets::Config>::AssetId;
 pub type FeeNameOf<T> = sp_runtime::BoundedVec<u8, <T as Config>::MaxFeeNameLen>;
 pub type FeeConfigOf<T> = FeeConfig<BalanceOf<T>>;
 pub type NamedFeeEntryOf<T> = NamedFeeEntry<T>;
+pub type FeeAssetOf<T> = FeeAsset<AssetIdOf<T>>;
+pub type FeeCapOf<T> = FeeCap<BalanceOf<T>, frame_system::pallet_prelude::BlockNumberFor<T>>;
+pub type FeeQuoteOf<T> =
+    FeeQuote<<T as frame_system::Config>::AccountId, AssetIdOf<T>, BalanceOf<T>>;
+/// A fee set at genesis: (name, config, beneficiary, asset the fee is paid in).
+pub type GenesisFeeOf<T> = (
+    Vec<u8>,
+    FeeConfigOf<T>,
+    <T as frame_system::Config>::AccountId,
+    FeeAssetOf<T>,
+);
+pub type FeeSpendOf<T> = FeeSpend<BalanceOf<T>, frame_system::pallet_prelude::BlockNumberFor<T>>;
+/// A fee to charge: (beneficiary, asset, amount).
+pub type FeeOf<T> = (
+    <T as frame_system::Config>::AccountId,
+    AssetIdOf<T>,
+    BalanceOf<T>,
+);
+/// A fee to charge, along with the name of the entry it comes from.
+pub type NamedFeeOf<T> = (FeeNameOf<T>, FeeOf<T>);
+/// A fee to charge, along with the account that pays it.
+pub type PayerFeeOf<T> = (<T as frame_system::Config>::AccountId, FeeOf<T>);
+/// What's left of the fee cap of each (payer, asset), or `None` if the asset is not capped.
+pub(crate) type FeeAllowancesOf<T> = Vec<(
+    <T as frame_system::Config>::AccountId,
+    AssetIdOf<T>,
+    Option<BalanceOf<T>>,
+)>;
+/// A transfer recognized in a call: (payer, asset, amount).
+pub type TransferOf<T> = (
+    <T as frame_system::Config>::AccountId,
+    AssetIdOf<T>,
+    BalanceOf<T>,
+);
 
 /// Maps an account to the community it belongs to.
 pub trait AccountCommunity<AccountId, CommunityId> {
@@ -22,9 +58,71 @@ impl<A, C> AccountCommunity<A, C> for () {
     }
 }
 
+/// Quotes how much of an asset an amount of another one is worth,
+/// e.g. backed by the pools of a swap pallet.
+pub trait SwapQuote<AssetId, Balance> {
+    /// The amount of `asset_out` that `amount_in` of `asset_in` would swap for.
+    fn quote_swap(asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<Balance>;
+}
+
+impl<A, B> SwapQuote<A, B> for () {
+    fn quote_swap(_: A, _: A, _: B) -> Option<B> {
+        None
+    }
+}
+
+/// Describes which asset a fee is paid in.
+///
+/// Fees are always calculated on the transferred amount, then converted
+/// into the fee asset. If no price is available for the pair, calculating
+/// the fee fails with [`crate::Error::FeeAssetUnpriced`], rejecting the transfer.
+#[derive(
+    Clone,
+    Default,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum FeeAsset<AssetId> {
+    /// Paid in the transferred asset.
+    #[default]
+    Transferred,
+    /// Paid in the given asset, at the conversion rate set by governance.
+    FixedRate(AssetId),
+    /// Paid in the given asset, at the price quoted by [`SwapQuote`].
+    Swap(AssetId),
+}
+
+impl<AssetId: PartialEq> FeeAsset<AssetId> {
+    /// Returns `true` if a fee on `asset` has to be converted into another asset.
+    pub fn converts(&self, asset: &AssetId) -> bool {
+        match self {
+            FeeAsset::Transferred => false,
+            FeeAsset::FixedRate(fee_asset) | FeeAsset::Swap(fee_asset) => fee_asset != asset,
+        }
+    }
+}
+
 /// Describes how a fee amount is calculated from a transfer amount.
 #[derive(
-    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+    Clone,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub enum FeeConfig<Balance> {
     /// A fixed fee amount regardless of transfer size.
@@ -74,6 +172,43 @@ impl<Balance: sp_runtime::traits::AtLeast32BitUnsigned + Copy> FeeConfig<Balance
     }
 }
 
+/// A cap on the total fees an account pays in an asset within a period of blocks.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub struct FeeCap<Balance, BlockNumber> {
+    /// Maximum amount of fees charged within a period.
+    pub max: Balance,
+    /// Length of the period in blocks.
+    pub period: BlockNumber,
+}
+
+/// The fees an account paid in an asset during its current period.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub struct FeeSpend<Balance, BlockNumber> {
+    /// Block at which the period started.
+    pub since: BlockNumber,
+    /// Fees paid since then.
+    pub paid: Balance,
+}
+
+impl<Balance, BlockNumber> FeeSpend<Balance, BlockNumber>
+where
+    Balance: sp_runtime::traits::Zero + Copy,
+    BlockNumber: sp_runtime::traits::AtLeast32BitUnsigned + Copy,
+{
+    /// Fees paid within the period of `cap` that is current at `now`.
+    pub fn paid_at(&self, cap: &FeeCap<Balance, BlockNumber>, now: BlockNumber) -> Balance {
+        if now < self.since.saturating_add(cap.period) {
+            self.paid
+        } else {
+            Balance::zero()
+        }
+    }
+}
+
 /// A named fee entry stored on-chain.
 #[derive(
     Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
@@ -84,4 +219,24 @@ pub struct NamedFeeEntry<T: Config> {
     pub name: FeeNameOf<T>,
     pub config: FeeConfigOf<T>,
     pub beneficiary: T::AccountId,
+    pub asset: FeeAssetOf<T>,
+}
+
+/// A fee a transfer would be charged, as quoted by [`crate::FeesApi`].
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq)]
+pub struct FeeQuote<AccountId, AssetId, Balance> {
+    /// Name of the fee entry.
+    pub name: Vec<u8>,
+    pub beneficiary: AccountId,
+    /// Asset the fee is paid in.
+    pub asset: AssetId,
+    pub amount: Balance,
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<T: Config> {
+    /// Returns an account that belongs to `community`, so community fees apply to it.
+    fn community_member(community: &T::CommunityId) -> T::AccountId;
+    /// Makes [`Config::Swap`] quote swaps of `asset_in` for `asset_out`.
+    fn quote_swap(asset_in: &AssetIdOf<T>, asset_out: &AssetIdOf<T>);
 }
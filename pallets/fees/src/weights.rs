@@ -0,0 +1,252 @@
+//! Weights for `fc_pallet_fees`
+//!
+//! These weights are maintained by hand, not generated: the storage reads, writes and proof
+//! sizes are counted from what each benchmark sets up, while the execution times are estimates.
+//! Replace them with measured ones by running `frame-omni-bencher` against a runtime built with
+//! the `runtime-benchmarks` feature that includes this pallet:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime <runtime.compact.compressed.wasm> \
+//!     --pallet fc_pallet_fees --extrinsic '*' \
+//!     --steps 50 --repeat 20 \
+//!     --output pallets/fees/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame::weights_prelude::*;
+
+/// Weight functions needed for `fc_pallet_fees`.
+pub trait WeightInfo {
+	fn set_protocol_fee(p: u32) -> Weight;
+	fn remove_protocol_fee(p: u32) -> Weight;
+	fn set_community_fee(c: u32) -> Weight;
+	fn remove_community_fee(c: u32) -> Weight;
+	fn set_protocol_fee_asset(p: u32) -> Weight;
+	fn set_community_fee_asset(c: u32) -> Weight;
+	fn set_conversion_rate() -> Weight;
+	fn set_fee_cap() -> Weight;
+	fn calculate_fees(n: u32) -> Weight;
+	fn charge_fees(n: u32) -> Weight;
+}
+
+/// Weights for `fc_pallet_fees`, pricing storage accesses with the runtime's `DbWeight`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[0, 9]`.
+	fn set_protocol_fee(p: u32, ) -> Weight {
+		Weight::from_parts(12_236_418, 2876)
+			.saturating_add(Weight::from_parts(301_552, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 10]`.
+	fn remove_protocol_fee(p: u32, ) -> Weight {
+		Weight::from_parts(11_631_209, 2876)
+			.saturating_add(Weight::from_parts(284_170, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 9]`.
+	fn set_community_fee(c: u32, ) -> Weight {
+		Weight::from_parts(13_502_761, 4880)
+			.saturating_add(Weight::from_parts(305_884, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 10]`.
+	fn remove_community_fee(c: u32, ) -> Weight {
+		Weight::from_parts(12_984_530, 4880)
+			.saturating_add(Weight::from_parts(290_417, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 10]`.
+	fn set_protocol_fee_asset(p: u32, ) -> Weight {
+		Weight::from_parts(11_402_655, 2876)
+			.saturating_add(Weight::from_parts(279_306, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 10]`.
+	fn set_community_fee_asset(c: u32, ) -> Weight {
+		Weight::from_parts(12_745_138, 4880)
+			.saturating_add(Weight::from_parts(283_941, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ConversionRates` (r:0 w:1)
+	/// Proof: `Fees::ConversionRates` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `MaxEncodedLen`)
+	fn set_conversion_rate() -> Weight {
+		Weight::from_parts(7_280_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::FeeCaps` (r:0 w:1)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	fn set_fee_cap() -> Weight {
+		Weight::from_parts(7_114_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:0)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::CommunityFees` (r:1 w:0)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:21 w:0)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::ConversionRates` (r:20 w:0)
+	/// Proof: `Fees::ConversionRates` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeeCaps` (r:20 w:0)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeesSpent` (r:20 w:0)
+	/// Proof: `Fees::FeesSpent` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 20]`.
+	fn calculate_fees(n: u32, ) -> Weight {
+		Weight::from_parts(25_306_117, 8461)
+			.saturating_add(Weight::from_parts(4_968_204, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10270).saturating_mul(n.into()))
+	}
+	/// Storage: `Assets::Asset` (r:20 w:20)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:40 w:40)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:20 w:20)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeeCaps` (r:20 w:0)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeesSpent` (r:20 w:20)
+	/// Proof: `Fees::FeesSpent` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 20]`.
+	fn charge_fees(n: u32, ) -> Weight {
+		Weight::from_parts(15_140_362, 0)
+			.saturating_add(Weight::from_parts(52_203_918, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 15560).saturating_mul(n.into()))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[0, 9]`.
+	fn set_protocol_fee(p: u32, ) -> Weight {
+		Weight::from_parts(12_236_418, 2876)
+			.saturating_add(Weight::from_parts(301_552, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 10]`.
+	fn remove_protocol_fee(p: u32, ) -> Weight {
+		Weight::from_parts(11_631_209, 2876)
+			.saturating_add(Weight::from_parts(284_170, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 9]`.
+	fn set_community_fee(c: u32, ) -> Weight {
+		Weight::from_parts(13_502_761, 4880)
+			.saturating_add(Weight::from_parts(305_884, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 10]`.
+	fn remove_community_fee(c: u32, ) -> Weight {
+		Weight::from_parts(12_984_530, 4880)
+			.saturating_add(Weight::from_parts(290_417, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:1)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 10]`.
+	fn set_protocol_fee_asset(p: u32, ) -> Weight {
+		Weight::from_parts(11_402_655, 2876)
+			.saturating_add(Weight::from_parts(279_306, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::CommunityFees` (r:1 w:1)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 10]`.
+	fn set_community_fee_asset(c: u32, ) -> Weight {
+		Weight::from_parts(12_745_138, 4880)
+			.saturating_add(Weight::from_parts(283_941, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ConversionRates` (r:0 w:1)
+	/// Proof: `Fees::ConversionRates` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `MaxEncodedLen`)
+	fn set_conversion_rate() -> Weight {
+		Weight::from_parts(7_280_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::FeeCaps` (r:0 w:1)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	fn set_fee_cap() -> Weight {
+		Weight::from_parts(7_114_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Fees::ProtocolFees` (r:1 w:0)
+	/// Proof: `Fees::ProtocolFees` (`max_values`: Some(1), `max_size`: Some(1391), added: 1886, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::CommunityFees` (r:1 w:0)
+	/// Proof: `Fees::CommunityFees` (`max_values`: None, `max_size`: Some(1415), added: 3890, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:21 w:0)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::ConversionRates` (r:20 w:0)
+	/// Proof: `Fees::ConversionRates` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeeCaps` (r:20 w:0)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeesSpent` (r:20 w:0)
+	/// Proof: `Fees::FeesSpent` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 20]`.
+	fn calculate_fees(n: u32, ) -> Weight {
+		Weight::from_parts(25_306_117, 8461)
+			.saturating_add(Weight::from_parts(4_968_204, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10270).saturating_mul(n.into()))
+	}
+	/// Storage: `Assets::Asset` (r:20 w:20)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:40 w:40)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:20 w:20)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeeCaps` (r:20 w:0)
+	/// Proof: `Fees::FeeCaps` (`max_values`: None, `max_size`: Some(32), added: 2507, mode: `MaxEncodedLen`)
+	/// Storage: `Fees::FeesSpent` (r:20 w:20)
+	/// Proof: `Fees::FeesSpent` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 20]`.
+	fn charge_fees(n: u32, ) -> Weight {
+		Weight::from_parts(15_140_362, 0)
+			.saturating_add(Weight::from_parts(52_203_918, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 15560).saturating_mul(n.into()))
+	}
+}
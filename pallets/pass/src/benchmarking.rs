@@ -1,17 +1,24 @@
 use super::*;
-use crate::{filter::DeviceFilter, AuthenticatedDevice, DeviceOf, Pallet};
+use crate::{
+    filter::{
+        ArgsFilter, AssetSpendLimit, CallInspector, CallMatcher, CallRule, DeviceFilter,
+        SpendBudget, SpendMatcher, ValidityWindow,
+    },
+    ApprovalPolicy, AuthenticatedDevice, DeviceOf, DeviceRateLimit, Pallet, PeriodSpends,
+    ProposalOf, Proposals, Recoveries, RecoveryOf, SessionOf, Spender,
+};
 
 use frame_benchmarking::v2::*;
 use frame_support::{
     assert_ok,
-    dispatch::{DispatchInfo, GetDispatchInfo},
+    dispatch::{DispatchInfo, GetDispatchInfo, PostDispatchInfo},
     traits::OriginTrait,
 };
 use frame_system::RawOrigin;
 use sp_core::blake2_256;
 use sp_runtime::traits::{
-    transaction_extension::DispatchTransaction, AsTransactionAuthorizedOrigin, DispatchInfoOf,
-    Hash, TxBaseImplication,
+    transaction_extension::DispatchTransaction, AsTransactionAuthorizedOrigin, Bounded,
+    DispatchInfoOf, Hash, TxBaseImplication,
 };
 
 fn assert_has_event<T: Config<I>, I: 'static>(generic_event: T::RuntimeEvent) {
@@ -58,10 +65,256 @@ where
     T::Hashing::hash(b).into()
 }
 
+/// Sets up the maximum number of guardians for a pass account, including the one given by a
+/// successful [`Config::GuardianOrigin`], which is returned.
+fn setup_guardians<T: Config<I>, I: 'static>(
+    address: &T::AccountId,
+    admin_device_id: DeviceId,
+    threshold: u32,
+) -> Result<(T::RuntimeOrigin, T::AccountId), BenchmarkError> {
+    let origin = T::GuardianOrigin::try_successful_origin()
+        .map_err(|_| BenchmarkError::Stop("Cannot prepare guardian origin"))?;
+    let guardian = T::GuardianOrigin::ensure_origin(origin.clone())
+        .map_err(|_| BenchmarkError::Stop("Cannot prepare guardian origin"))?;
+
+    let guardians = core::iter::once(guardian.clone())
+        .chain((1..T::MaxGuardians::get()).map(|i| account("guardian", i, 0)))
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("within bounds");
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(2, RecoveryOf::<T, I>::max_encoded_len()),
+    );
+
+    AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+    Pallet::<T, I>::set_guardians(
+        RawOrigin::Signed(address.clone()).into(),
+        guardians,
+        threshold,
+        T::MinRecoveryDelay::get(),
+    )?;
+
+    Ok((origin, guardian))
+}
+
+/// Initiates a recovery with a new device, revoking the admin device.
+fn do_initiate_recovery<T: Config<I>, I: 'static>(
+    guardian: T::RuntimeOrigin,
+    address: &T::AccountId,
+    admin_device_id: DeviceId,
+) -> Result<DeviceId, BenchmarkError> {
+    let attestation = T::BenchmarkHelper::device_attestation(&address.encode());
+    let device_id = *attestation.device_id();
+    Pallet::<T, I>::initiate_recovery(
+        guardian,
+        T::Lookup::unlookup(address.clone()),
+        attestation,
+        Some(admin_device_id),
+    )?;
+    Ok(device_id)
+}
+
+/// Adds a second device to `address`, leaving its admin device authenticated.
+fn do_add_device<T: Config<I>, I: 'static>(
+    address: &T::AccountId,
+    admin_device_id: DeviceId,
+) -> Result<DeviceId, BenchmarkError> {
+    let attestation = T::BenchmarkHelper::device_attestation(&address.encode());
+    let device_id = *(attestation.clone().device_id());
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(2, DeviceOf::<T, I>::max_encoded_len()),
+    );
+
+    AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+    Pallet::<T, I>::add_device(
+        RawOrigin::Signed(address.clone()).into(),
+        attestation,
+        DeviceFilter::Admin,
+    )?;
+
+    Ok(device_id)
+}
+
+/// Adds a second `Admin` device to a pass account and sets an approval policy requiring both
+/// devices to approve any call. Returns the id of the second device.
+fn setup_approval_policy<T: Config<I>, I: 'static>(
+    address: &T::AccountId,
+    admin_device_id: DeviceId,
+) -> Result<DeviceId, BenchmarkError> {
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(2, DeviceOf::<T, I>::max_encoded_len()),
+    );
+    let attestation = T::BenchmarkHelper::device_attestation(&address.encode());
+    let device_id = *attestation.device_id();
+    AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+    Pallet::<T, I>::add_device(
+        RawOrigin::Signed(address.clone()).into(),
+        attestation,
+        DeviceFilter::Admin,
+    )?;
+
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(
+            T::MaxPendingProposals::get() as usize + 1,
+            ProposalOf::<T, I>::max_encoded_len(),
+        ),
+    );
+    Pallet::<T, I>::set_approval_policy(
+        RawOrigin::Signed(address.clone()).into(),
+        Some(approval_policy::<T, I>()),
+    )?;
+
+    Ok(device_id)
+}
+
+/// Adds a `Scoped` device to `address` whose filter allows each of `calls` only through its
+/// last rules, with budgeted limits for the spends, and gives it a rate limit of its own. Sets
+/// an approval policy with as many entries as allowed, none of them matching `calls`, so the
+/// device authenticates them without approvals. Returns the id of the device.
+fn setup_scoped_device<T: Config<I>, I: 'static>(
+    address: &T::AccountId,
+    admin_device_id: DeviceId,
+    calls: &[RuntimeCallFor<T>],
+) -> Result<DeviceId, BenchmarkError> {
+    let mut rules = Vec::<CallRule<_, _>>::new();
+    for call in calls {
+        let (pallet, index) = <T::CallMatcher as CallMatcher<_>>::call_indices(call);
+        if rules
+            .iter()
+            .any(|r| (r.pallet, r.call) == (pallet, Some(index)))
+        {
+            continue;
+        }
+        let spend = T::SpendMatcher::spending_amount(call).map(|(asset, _)| AssetSpendLimit {
+            asset,
+            max_amount: Bounded::max_value(),
+            budget: Some(SpendBudget {
+                amount: Bounded::max_value(),
+                period: 1,
+            }),
+        });
+        rules.push(CallRule {
+            pallet,
+            call: Some(index),
+            args: ArgsFilter::Any,
+            spend,
+        });
+    }
+    // Rules matching the same calls, but not their arguments, are scanned first
+    let fillers = T::MaxFilteredCalls::get()
+        .checked_sub(rules.len() as u32)
+        .ok_or(BenchmarkError::Stop("Not enough rules for the calls"))?;
+    let filler = CallRule {
+        args: ArgsFilter::Hash([0; 32]),
+        spend: None,
+        ..rules
+            .last()
+            .cloned()
+            .ok_or(BenchmarkError::Stop("No calls"))?
+    };
+    let rules = core::iter::repeat_n(filler, fillers as usize)
+        .chain(rules)
+        .collect::<Vec<_>>();
+    let spends = calls
+        .iter()
+        .filter_map(T::SpendMatcher::spending_amount)
+        .map(|(asset, _)| (asset, Bounded::max_value()))
+        .cycle()
+        .take(T::MaxFilteredAssets::get() as usize)
+        .collect::<Vec<_>>();
+
+    let attestation = T::BenchmarkHelper::device_attestation(&address.encode());
+    let device_id = *attestation.device_id();
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(2, DeviceOf::<T, I>::max_encoded_len()),
+    );
+    AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+    Pallet::<T, I>::add_device(
+        RawOrigin::Signed(address.clone()).into(),
+        attestation,
+        DeviceFilter::Scoped {
+            rules: rules.try_into().expect("within bounds"),
+            validity: Some(ValidityWindow {
+                from: 0,
+                until: u32::MAX,
+            }),
+        },
+    )?;
+    Pallet::<T, I>::set_device_rate_limit(
+        RawOrigin::Signed(address.clone()).into(),
+        device_id,
+        Some(DeviceRateLimit {
+            max_transactions: u32::MAX,
+            period: 1,
+        }),
+    )?;
+
+    T::DeviceConsideration::ensure_successful(
+        address,
+        Footprint::from_parts(
+            T::MaxPendingProposals::get() as usize + 1,
+            ProposalOf::<T, I>::max_encoded_len(),
+        ),
+    );
+    Pallet::<T, I>::set_approval_policy(
+        RawOrigin::Signed(address.clone()).into(),
+        Some(ApprovalPolicy {
+            threshold: 2,
+            timeout: 1,
+            calls: (0..T::MaxFilteredCalls::get())
+                .map(|i| (u8::MAX, Some(i as u8)))
+                .collect::<Vec<_>>()
+                .try_into()
+                .expect("within bounds"),
+            spends: spends.try_into().expect("within bounds"),
+        }),
+    )?;
+    AuthenticatedDevice::<T, I>::kill();
+
+    Ok(device_id)
+}
+
+/// A policy requiring two devices to approve any call in the first pallets.
+fn approval_policy<T: Config<I>, I: 'static>() -> crate::ApprovalPolicyOf<T, I> {
+    ApprovalPolicy {
+        threshold: 2,
+        timeout: 1,
+        calls: (0..T::MaxFilteredCalls::get())
+            .map(|i| (i as u8, None))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("within bounds"),
+        spends: Default::default(),
+    }
+}
+
+/// Proposes a call on behalf of a device, returning the call and its hash.
+fn do_propose<T: Config<I>, I: 'static>(
+    address: &T::AccountId,
+    device_id: DeviceId,
+) -> Result<(<T as frame_system::Config>::RuntimeCall, T::Hash), BenchmarkError> {
+    let call: <T as frame_system::Config>::RuntimeCall =
+        frame_system::Call::<T>::remark { remark: vec![] }.into();
+    AuthenticatedDevice::<T, I>::put((address.clone(), device_id));
+    Pallet::<T, I>::approve_call(
+        RawOrigin::Signed(address.clone()).into(),
+        Box::new(call.clone()),
+    )
+    .map_err(|e| e.error)?;
+    let call_hash = T::Hashing::hash_of(&call);
+    Ok((call, call_hash))
+}
+
 #[instance_benchmarks(
 where
     T::Hash: Into<HashedUserId>,
     DispatchInfoOf<RuntimeCallFor<T>>: From<DispatchInfo>,
+    RuntimeCallFor<T>: Dispatchable<PostInfo = PostDispatchInfo>,
     OriginFor<T>: From<frame_system::Origin<T>> + AsTransactionAuthorizedOrigin,
 )]
 mod benchmarks {
@@ -99,17 +352,38 @@ mod benchmarks {
     }
 
     #[benchmark]
-    pub fn authenticate() -> Result<(), BenchmarkError> {
+    pub fn authenticate(c: Linear<0, 100>, l: Linear<0, 16384>) -> Result<(), BenchmarkError> {
         // Setup code
+        // Credentials are challenged with the current block, which must be the one the
+        // extension runs at
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
         let user_id = hash::<T>(b"my-account");
-        let device_id = do_register::<T, I>(user_id)?;
+        let admin_device_id = do_register::<T, I>(user_id)?;
+        let address = Pallet::<T, I>::address_for(user_id);
 
-        let call: RuntimeCallFor<T> = frame_system::Call::remark {
-            remark: b"Hello, world".to_vec(),
+        // A remark of `l` bytes and `c` spends, nested as deep as the inspector allows
+        let remark: RuntimeCallFor<T> = frame_system::Call::remark {
+            remark: vec![0; l as usize],
         }
         .into();
+        let spend = T::BenchmarkHelper::spend_call()
+            .unwrap_or_else(|| frame_system::Call::remark { remark: vec![] }.into());
+        let leaves = core::iter::once(remark.clone())
+            .chain(core::iter::repeat_n(spend.clone(), c as usize))
+            .collect();
+        let mut call = T::BenchmarkHelper::nested_call(leaves);
+        for _ in 1..<T::CallInspector as CallInspector<_>>::MAX_DEPTH {
+            call = T::BenchmarkHelper::nested_call(vec![call]);
+        }
+
+        let device_id = setup_scoped_device::<T, I>(
+            &address,
+            admin_device_id,
+            &[call.clone(), remark, spend.clone()],
+        )?;
         let ext = PassAuthenticate::<T, I>::from(
             device_id,
+            0,
             T::BenchmarkHelper::credential(
                 user_id,
                 device_id,
@@ -120,17 +394,30 @@ mod benchmarks {
         #[block]
         {
             assert_ok!(ext
-                .validate_only(
+                .test_run(
                     RawOrigin::None.into(),
                     &call,
                     &call.get_dispatch_info().into(),
                     call.encoded_size(),
-                    TransactionSource::External,
-                    0
+                    0,
+                    |_| Ok(PostDispatchInfo::default()),
                 )
                 .map(|_| ()));
         }
 
+        // Verification code
+        assert_eq!(
+            Pallet::<T, I>::device_transactions_in_period(&address, &device_id),
+            1
+        );
+        if let Some((asset, _)) = T::SpendMatcher::spending_amount(&spend).filter(|_| c > 0) {
+            assert!(PeriodSpends::<T, I>::contains_key((
+                &address,
+                Spender::Device(device_id),
+                asset
+            )));
+        }
+
         Ok(())
     }
 
@@ -214,9 +501,9 @@ mod benchmarks {
 
         let address = Pallet::<T, I>::address_for(user_id);
         let new_session_key: T::AccountId = account("session-key", 0, 0);
-        T::DeviceConsideration::ensure_successful(
+        T::SessionKeyConsideration::ensure_successful(
             &address,
-            Footprint::from_parts(2, T::AccountId::max_encoded_len()),
+            Footprint::from_parts(1, SessionOf::<T, I>::max_encoded_len()),
         );
 
         // Need a non-Admin filter for session keys
@@ -232,14 +519,16 @@ mod benchmarks {
             RawOrigin::Signed(address.clone()),
             T::Lookup::unlookup(new_session_key.clone()),
             None,
-            filter,
+            filter.clone(),
         );
 
         // Verification code
         assert_has_event::<T, I>(
             Event::SessionCreated {
                 session_key_hash: T::Hashing::hash(&new_session_key.encode()),
-                until: T::MaxSessionDuration::get(),
+                until: T::BlockNumberProvider::current_block_number()
+                    .saturating_add(T::MaxSessionDuration::get()),
+                filter,
             }
             .into(),
         );
@@ -255,9 +544,50 @@ mod benchmarks {
 
         let address = Pallet::<T, I>::address_for(user_id);
         let session_key: T::AccountId = account("session-key", 0, 0);
-        T::DeviceConsideration::ensure_successful(
+        T::SessionKeyConsideration::ensure_successful(
+            &address,
+            Footprint::from_parts(1, SessionOf::<T, I>::max_encoded_len()),
+        );
+
+        let filter: crate::DeviceFilterOf<T, I> = DeviceFilter::Pallets(
+            alloc::collections::BTreeSet::from([0u8])
+                .try_into()
+                .expect("within bounds"),
+        );
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+        Pallet::<T, I>::add_session_key(
+            RawOrigin::Signed(address.clone()).into(),
+            T::Lookup::unlookup(session_key.clone()),
+            None,
+            filter.clone(),
+        )?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address), session_key.clone());
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::SessionRemoved {
+                session_key,
+                filter,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn renew_session_key() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let session_key: T::AccountId = account("session-key", 0, 0);
+        T::SessionKeyConsideration::ensure_successful(
             &address,
-            Footprint::from_parts(2, T::AccountId::max_encoded_len()),
+            Footprint::from_parts(1, SessionOf::<T, I>::max_encoded_len()),
         );
 
         let filter: crate::DeviceFilterOf<T, I> = DeviceFilter::Pallets(
@@ -270,14 +600,530 @@ mod benchmarks {
             RawOrigin::Signed(address.clone()).into(),
             T::Lookup::unlookup(session_key.clone()),
             None,
-            filter,
+            filter.clone(),
         )?;
 
         #[extrinsic_call]
-        _(RawOrigin::Root, session_key.clone());
+        _(RawOrigin::Signed(address), session_key.clone(), None);
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::SessionRenewed {
+                session_key_hash: T::Hashing::hash(&session_key.encode()),
+                until: T::BlockNumberProvider::current_block_number()
+                    .saturating_add(T::MaxSessionDuration::get()),
+                filter,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn set_guardians() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let max_guardians = T::MaxGuardians::get();
+        // Worst case: replacing the guardians cancels a recovery in progress
+        let (guardian, _) = setup_guardians::<T, I>(&address, admin_device_id, max_guardians)?;
+        do_initiate_recovery::<T, I>(guardian, &address, admin_device_id)?;
+        let guardians: BoundedVec<_, _> = (0..max_guardians)
+            .map(|i| account("guardian", i, 0))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("within bounds");
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            guardians,
+            max_guardians,
+            T::MinRecoveryDelay::get(),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::RecoveryCancelled {
+                who: address.clone(),
+            }
+            .into(),
+        );
+        assert_has_event::<T, I>(
+            Event::GuardiansSet {
+                who: address,
+                threshold: max_guardians,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn remove_guardians() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let (guardian, _) =
+            setup_guardians::<T, I>(&address, admin_device_id, T::MaxGuardians::get())?;
+        do_initiate_recovery::<T, I>(guardian, &address, admin_device_id)?;
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address.clone()));
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::RecoveryCancelled {
+                who: address.clone(),
+            }
+            .into(),
+        );
+        assert_has_event::<T, I>(Event::GuardiansRemoved { who: address }.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn initiate_recovery() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let (origin, guardian) = setup_guardians::<T, I>(&address, admin_device_id, 1)?;
+        let attestation = T::BenchmarkHelper::device_attestation(&address.encode());
+        let device_id = *attestation.device_id();
+
+        #[extrinsic_call]
+        _(
+            origin.into_caller(),
+            T::Lookup::unlookup(address.clone()),
+            attestation,
+            Some(admin_device_id),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::RecoveryInitiated {
+                who: address.clone(),
+                guardian,
+                device_id,
+            }
+            .into(),
+        );
+        assert!(Recoveries::<T, I>::get(&address).is_some_and(|r| r.executable_at.is_some()));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn approve_recovery() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let max_guardians = T::MaxGuardians::get();
+        let (origin, guardian) = setup_guardians::<T, I>(&address, admin_device_id, max_guardians)?;
+        do_initiate_recovery::<T, I>(origin.clone(), &address, admin_device_id)?;
+
+        // Every other guardian has already approved, so this approval meets the threshold
+        Recoveries::<T, I>::mutate(&address, |recovery| {
+            if let Some(recovery) = recovery {
+                recovery.approvals = (1..max_guardians)
+                    .map(|i| account("guardian", i, 0))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .expect("within bounds");
+            }
+        });
+
+        #[extrinsic_call]
+        _(origin.into_caller(), T::Lookup::unlookup(address.clone()));
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::RecoveryApproved {
+                who: address.clone(),
+                guardian,
+            }
+            .into(),
+        );
+        assert!(Recoveries::<T, I>::get(&address).is_some_and(|r| r.executable_at.is_some()));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn cancel_recovery() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let (guardian, _) =
+            setup_guardians::<T, I>(&address, admin_device_id, T::MaxGuardians::get())?;
+        do_initiate_recovery::<T, I>(guardian, &address, admin_device_id)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address.clone()));
+
+        // Verification code
+        assert_has_event::<T, I>(Event::RecoveryCancelled { who: address }.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn finish_recovery() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let (guardian, _) = setup_guardians::<T, I>(&address, admin_device_id, 1)?;
+        let device_id = do_initiate_recovery::<T, I>(guardian, &address, admin_device_id)?;
+        T::BlockNumberProvider::set_block_number(
+            T::BlockNumberProvider::current_block_number()
+                .saturating_add(T::MinRecoveryDelay::get()),
+        );
+        let caller: T::AccountId = whitelisted_caller();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(address.clone()),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::DeviceRemoved {
+                who: address.clone(),
+                device_id: admin_device_id,
+            }
+            .into(),
+        );
+        assert_has_event::<T, I>(
+            Event::RecoveryFinished {
+                who: address,
+                device_id,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn kill_account(
+        d: Linear<1, { T::MaxDevicesPerAccount::get() }>,
+        s: Linear<0, { T::MaxSessionsPerAccount::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        T::DeviceConsideration::ensure_successful(
+            &address,
+            Footprint::from_parts(d as usize, DeviceOf::<T, I>::max_encoded_len()),
+        );
+        for _ in 1..d {
+            AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+            Pallet::<T, I>::add_device(
+                RawOrigin::Signed(address.clone()).into(),
+                T::BenchmarkHelper::device_attestation(&address.encode()),
+                DeviceFilter::Admin,
+            )?;
+        }
+
+        T::SessionKeyConsideration::ensure_successful(
+            &address,
+            Footprint::from_parts(s as usize, SessionOf::<T, I>::max_encoded_len()),
+        );
+        let filter: crate::DeviceFilterOf<T, I> = DeviceFilter::Pallets(
+            alloc::collections::BTreeSet::from([0u8])
+                .try_into()
+                .expect("within bounds"),
+        );
+        for i in 0..s {
+            AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+            Pallet::<T, I>::add_session_key(
+                RawOrigin::Signed(address.clone()).into(),
+                T::Lookup::unlookup(account("session-key", i, 0)),
+                None,
+                filter.clone(),
+            )?;
+        }
+
+        let beneficiary: T::AccountId = whitelisted_caller();
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            T::Lookup::unlookup(beneficiary),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(Event::AccountKilled { who: address }.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn set_approval_policy() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        T::DeviceConsideration::ensure_successful(
+            &address,
+            Footprint::from_parts(1, ProposalOf::<T, I>::max_encoded_len()),
+        );
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            Some(approval_policy::<T, I>()),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::ApprovalPolicySet {
+                who: address,
+                threshold: 2,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn approve_call() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let device_id = setup_approval_policy::<T, I>(&address, admin_device_id)?;
+        let (call, call_hash) = do_propose::<T, I>(&address, admin_device_id)?;
+        AuthenticatedDevice::<T, I>::put((address.clone(), device_id));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address.clone()), Box::new(call));
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::ProposalExecuted {
+                who: address,
+                call_hash,
+                result: Ok(()),
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn cancel_proposal() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        setup_approval_policy::<T, I>(&address, admin_device_id)?;
+        let (_, call_hash) = do_propose::<T, I>(&address, admin_device_id)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address.clone()), call_hash);
 
         // Verification code
-        assert_has_event::<T, I>(Event::SessionRemoved { session_key }.into());
+        assert!(!Proposals::<T, I>::contains_key(&address, call_hash));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn remove_expired_proposal() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        setup_approval_policy::<T, I>(&address, admin_device_id)?;
+        let (_, call_hash) = do_propose::<T, I>(&address, admin_device_id)?;
+        T::BlockNumberProvider::set_block_number(
+            T::BlockNumberProvider::current_block_number().saturating_add(1u32.into()),
+        );
+        let caller: T::AccountId = whitelisted_caller();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            T::Lookup::unlookup(address.clone()),
+            call_hash,
+        );
+
+        // Verification code
+        assert!(!Proposals::<T, I>::contains_key(&address, call_hash));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn update_device_filter() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let new_device_id = do_add_device::<T, I>(&address, admin_device_id)?;
+        let filter = DeviceFilter::Pallets(Default::default());
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            new_device_id,
+            filter.clone(),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::DeviceFilterUpdated {
+                who: address,
+                device_id: new_device_id,
+                filter,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn set_device_metadata() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let new_device_id = do_add_device::<T, I>(&address, admin_device_id)?;
+        let label: DeviceLabelOf<T, I> = vec![0u8; T::MaxDeviceLabelLen::get() as usize]
+            .try_into()
+            .expect("label fits the maximum length; qed");
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            new_device_id,
+            Some(label.clone()),
+            Some(label),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::DeviceMetadataSet {
+                who: address,
+                device_id: new_device_id,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn set_registrar() -> Result<(), BenchmarkError> {
+        // Setup code
+        let origin =
+            T::RegistrarsOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let registrar: T::AccountId = account("registrar", 0, 0);
+        let info = RegistrarInfo {
+            max_registrations: Some(u32::MAX),
+            status: RegistrarStatus::Active,
+            sponsor_device_deposits: true,
+        };
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            T::Lookup::unlookup(registrar.clone()),
+            Some(info.clone()),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(Event::RegistrarSet { registrar, info }.into());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn rotate_user_id() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let new_user_id = hash::<T>(b"my-new-account");
+        AuthenticatedDevice::<T, I>::put((address.clone(), admin_device_id));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(address.clone()), new_user_id);
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::UserIdRotated {
+                who: address,
+                user_id: new_user_id,
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    pub fn set_device_rate_limit() -> Result<(), BenchmarkError> {
+        // Setup code
+        let user_id = hash::<T>(b"my-account");
+        let admin_device_id = do_register::<T, I>(user_id)?;
+
+        let address = Pallet::<T, I>::address_for(user_id);
+        let new_device_id = do_add_device::<T, I>(&address, admin_device_id)?;
+        let limit = DeviceRateLimit {
+            max_transactions: 1,
+            period: 1,
+        };
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(address.clone()),
+            new_device_id,
+            Some(limit),
+        );
+
+        // Verification code
+        assert_has_event::<T, I>(
+            Event::DeviceRateLimitSet {
+                who: address,
+                device_id: new_device_id,
+                limit: Some(limit),
+            }
+            .into(),
+        );
 
         Ok(())
     }
@@ -0,0 +1,99 @@
+//! Helpers to build transactions authenticated with [`PassAuthenticate`], for tests and off-chain
+//! tools.
+//!
+//! Credentials are signed for an extrinsic context that depends on the call and on every
+//! transaction extension that follows [`PassAuthenticate`], so it's derived from the same
+//! extensions the transaction is built with.
+//!
+//! ```ignore
+//! let following = (CheckNonce::from(nonce), ChargeTransactionPayment::from(0));
+//! let counter = Pass::device_transactions_in_period(&address, &device_id);
+//! let extensions =
+//!     client::extensions::<Runtime, _, _>(0, &call, device_id, counter, following, |xtc| {
+//!         PassCredential::Keys(signer.sign(user_id, block_number, xtc))
+//!     })?;
+//! ```
+
+use crate::{AuthenticateParams, Config, CredentialOf, PassAuthenticate};
+use codec::Encode;
+use fc_traits_authn::DeviceId;
+use frame_system::pallet_prelude::RuntimeCallFor;
+use sp_core::blake2_256;
+use sp_runtime::{
+    generic::ExtensionVersion,
+    traits::{Dispatchable, TransactionExtension},
+    transaction_validity::TransactionValidityError,
+};
+
+/// The extrinsic context [`PassAuthenticate`] verifies credentials against: the hash of the
+/// implication it inherits, made of the extension version, the `call`, and the explicit and
+/// implicit data of the `following` extensions.
+pub fn extrinsic_context<Call, Following>(
+    extension_version: ExtensionVersion,
+    call: &Call,
+    following: &Following,
+) -> Result<[u8; 32], TransactionValidityError>
+where
+    Call: Encode + Dispatchable,
+    Following: TransactionExtension<Call>,
+{
+    Ok((extension_version, call, following, following.implicit()?).using_encoded(blake2_256))
+}
+
+/// Builds the [`AuthenticateParams`] for `call`, with the credential `sign` makes for its
+/// extrinsic context. `counter` is how many transactions the device authenticated before in the
+/// current rate limit period (see [`crate::Pallet::device_transactions_in_period`]).
+pub fn authenticate_params<T, I, Following>(
+    extension_version: ExtensionVersion,
+    call: &RuntimeCallFor<T>,
+    device_id: DeviceId,
+    counter: u32,
+    following: &Following,
+    sign: impl FnOnce(&[u8; 32]) -> CredentialOf<T, I>,
+) -> Result<AuthenticateParams<T, I>, TransactionValidityError>
+where
+    T: Config<I>,
+    I: 'static,
+    Following: TransactionExtension<RuntimeCallFor<T>>,
+{
+    let xtc = extrinsic_context(extension_version, call, following)?;
+    Ok(AuthenticateParams::new(device_id, counter, sign(&xtc)))
+}
+
+/// Builds the transaction extensions of a transaction authenticated by a pass device:
+/// [`PassAuthenticate`], followed by the `following` extensions.
+pub fn extensions<T, I, Following>(
+    extension_version: ExtensionVersion,
+    call: &RuntimeCallFor<T>,
+    device_id: DeviceId,
+    counter: u32,
+    following: Following,
+    sign: impl FnOnce(&[u8; 32]) -> CredentialOf<T, I>,
+) -> Result<(PassAuthenticate<T, I>, Following), TransactionValidityError>
+where
+    T: Config<I>,
+    I: 'static,
+    Following: TransactionExtension<RuntimeCallFor<T>>,
+{
+    let params = authenticate_params(
+        extension_version,
+        call,
+        device_id,
+        counter,
+        &following,
+        sign,
+    )?;
+    Ok((params.into(), following))
+}
+
+/// Builds the transaction extensions of a transaction signed by a session key, which doesn't
+/// need a credential.
+pub fn session_extensions<T, I, Following>(
+    following: Following,
+) -> (PassAuthenticate<T, I>, Following)
+where
+    T: Config<I>,
+    I: 'static,
+{
+    (PassAuthenticate::default(), following)
+}
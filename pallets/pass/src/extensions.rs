@@ -1,12 +1,14 @@
 use crate::{
-    AuthenticatedDevice, CallMatcher, Config, CredentialOf, DeviceFilters, Pallet, SpendMatcher,
-    WeightInfo,
+    AuthenticatedDevice, BudgetedSpendOf, Call, CallInspectionOf, Config, CredentialOf,
+    DeviceFilters, DevicePriority, Pallet, Spender, WeightInfo,
 };
+use alloc::vec::Vec;
 use codec::{Decode, DecodeWithMemTracking, Encode};
 use fc_traits_authn::DeviceId;
 use frame_support::{
     dispatch::RawOrigin,
     pallet_prelude::{DispatchResult, TransactionValidityError, Weight},
+    traits::IsSubType,
     CloneNoBound, DebugNoBound, DefaultNoBound, EqNoBound, PartialEqNoBound,
 };
 use frame_system::{ensure_signed, pallet_prelude::RuntimeCallFor};
@@ -14,16 +16,24 @@ use scale_info::TypeInfo;
 use sp_core::blake2_256;
 use sp_runtime::{
     traits::{
-        DispatchInfoOf, DispatchOriginOf, Implication, PostDispatchInfoOf, TransactionExtension,
-        ValidateResult,
+        BlockNumberProvider, DispatchInfoOf, DispatchOriginOf, Implication, PostDispatchInfoOf,
+        SaturatedConversion, Saturating, TransactionExtension, ValidateResult,
     },
     transaction_validity::{InvalidTransaction, TransactionSource, ValidTransaction},
 };
 
+/// The [`InvalidTransaction::Custom`] code of transactions from devices that exceeded their
+/// rate limit.
+pub const RATE_LIMIT_EXCEEDED: u8 = 1;
+
 /// Handles the authentication of a Pass account. If the authentication is successful, a signed
 /// origin associated to the device would be set.
 ///
 /// Throws an [`UnknownOrigin`][InvalidTransaction::UnknownOrigin] error otherwise.
+///
+/// Placed before a payment extension like `fc_pallet_gas_transaction_payment`'s, the fees are
+/// charged to the pass account, which might pay with the gas tank created for it on
+/// registration (see [`Config::GasTank`]).
 #[derive(
     DefaultNoBound,
     Encode,
@@ -51,6 +61,10 @@ pub struct PassAuthenticate<T: Config<I>, I: 'static = ()>(Option<AuthenticatePa
 #[scale_info(skip_type_params(T, I))]
 pub struct AuthenticateParams<T: Config<I>, I: 'static = ()> {
     device_id: DeviceId,
+    /// How many transactions the device authenticated before this one in the current rate
+    /// limit period (see [`Pallet::device_transactions_in_period`]). Like an account nonce, it
+    /// orders the transactions of a device in the pool.
+    counter: u32,
     credential: CredentialOf<T, I>,
 }
 
@@ -59,11 +73,32 @@ where
     T: Config<I>,
     I: 'static,
 {
-    pub fn from(device_id: DeviceId, credential: CredentialOf<T, I>) -> Self {
-        Self(Some(AuthenticateParams {
+    pub fn from(device_id: DeviceId, counter: u32, credential: CredentialOf<T, I>) -> Self {
+        AuthenticateParams::new(device_id, counter, credential).into()
+    }
+}
+
+impl<T, I> AuthenticateParams<T, I>
+where
+    T: Config<I>,
+    I: 'static,
+{
+    pub fn new(device_id: DeviceId, counter: u32, credential: CredentialOf<T, I>) -> Self {
+        Self {
             device_id,
+            counter,
             credential,
-        }))
+        }
+    }
+}
+
+impl<T, I> From<AuthenticateParams<T, I>> for PassAuthenticate<T, I>
+where
+    T: Config<I>,
+    I: 'static,
+{
+    fn from(params: AuthenticateParams<T, I>) -> Self {
+        Self(Some(params))
     }
 }
 
@@ -74,12 +109,18 @@ where
 {
     const IDENTIFIER: &'static str = "PassAuthenticate";
     type Implicit = ();
-    /// The authenticated (account, device_id), if any.
-    type Val = Option<(T::AccountId, DeviceId)>;
-    type Pre = Option<(T::AccountId, DeviceId)>;
+    /// The authenticated (account, device_id), if any, and the spends to charge against a
+    /// budget.
+    type Val = (Option<(T::AccountId, DeviceId)>, Vec<BudgetedSpendOf<T, I>>);
+    type Pre = (Option<(T::AccountId, DeviceId)>, Vec<BudgetedSpendOf<T, I>>);
 
-    fn weight(&self, _call: &RuntimeCallFor<T>) -> Weight {
-        T::WeightInfo::authenticate()
+    fn weight(&self, call: &RuntimeCallFor<T>) -> Weight {
+        // Every call found when inspecting `call` is encoded and checked against the filter
+        // rules, the approval policy and the spend budgets, so wrappers count as calls too.
+        T::WeightInfo::authenticate(
+            CallInspectionOf::<T, I>::count::<_, T::CallInspector>(call),
+            call.encoded_size().saturated_into(),
+        )
     }
 
     fn validate(
@@ -92,7 +133,7 @@ where
         inherited_implication: &impl Implication,
         _source: TransactionSource,
     ) -> ValidateResult<Self::Val, RuntimeCallFor<T>> {
-        let (device_id, origin) = if let Some(params) = &self.0 {
+        let (validity, val, origin) = if let Some(params) = &self.0 {
             let address = Pallet::<T, I>::authenticate(
                 &params.device_id,
                 &params.credential,
@@ -103,43 +144,119 @@ where
                 TransactionValidityError::from(InvalidTransaction::BadSigner)
             })?;
 
-            // Check the device's call filter (missing filter = denied)
-            let filter = DeviceFilters::<T, I>::get(&address, &params.device_id)
-                .ok_or(TransactionValidityError::from(InvalidTransaction::Call))?;
-            if !filter.allows(
-                T::CallMatcher::call_indices(call),
-                T::SpendMatcher::spending_amount(call),
-            ) {
-                log::error!(target: "pallet_pass", "Device filter rejected call");
-                return Err(InvalidTransaction::Call.into());
+            let transactions =
+                Pallet::<T, I>::device_transactions_in_period(&address, &params.device_id);
+            if params.counter < transactions {
+                return Err(InvalidTransaction::Stale.into());
             }
+            let period =
+                Pallet::<T, I>::check_rate_limit(&address, &params.device_id, params.counter)
+                    .map_err(|e| {
+                        log::error!(target: "pallet_pass", "Rate limit check failed: {:?}", e);
+                        TransactionValidityError::from(InvalidTransaction::Custom(
+                            RATE_LIMIT_EXCEEDED,
+                        ))
+                    })?;
+            // Transactions of a device follow each other by their counter, so the pool holds no
+            // more of them than its rate limit allows. The same credential can't be used twice,
+            // and the transaction is revalidated once the rate limit period ends.
+            let validity = ValidTransaction::with_tag_prefix(Self::IDENTIFIER)
+                .priority(T::DevicePriority::priority(&address, &params.device_id))
+                .and_provides((&address, params.device_id, period.start, params.counter))
+                .and_provides((
+                    &address,
+                    params.device_id,
+                    params.credential.using_encoded(blake2_256),
+                ))
+                .longevity(
+                    period
+                        .end
+                        .saturating_sub(T::BlockNumberProvider::current_block_number())
+                        .saturated_into::<u64>()
+                        .max(1),
+                );
+            let validity = if params.counter > transactions {
+                validity.and_requires((
+                    &address,
+                    params.device_id,
+                    period.start,
+                    params.counter - 1,
+                ))
+            } else {
+                validity
+            }
+            .into();
+
+            // Approved calls are checked against the device's filter by `approve_call` itself.
+            let spends = if matches!(call.is_sub_type(), Some(Call::approve_call { .. })) {
+                Vec::new()
+            } else {
+                // Check the device's call filter (missing filter = denied)
+                let filter = DeviceFilters::<T, I>::get(&address, params.device_id)
+                    .ok_or(TransactionValidityError::from(InvalidTransaction::Call))?;
+                let inspection = Pallet::<T, I>::check_filter(&filter, call).ok_or_else(|| {
+                    log::error!(target: "pallet_pass", "Device filter rejected call");
+                    TransactionValidityError::from(InvalidTransaction::Call)
+                })?;
+                if Pallet::<T, I>::requires_approval(&address, &inspection) {
+                    log::error!(target: "pallet_pass", "Call requires approvals");
+                    return Err(InvalidTransaction::Call.into());
+                }
+                Pallet::<T, I>::check_spend_budgets(
+                    &address,
+                    Spender::Device(params.device_id),
+                    &filter,
+                    &inspection,
+                )
+                .map_err(|e| {
+                    log::error!(target: "pallet_pass", "Spend budget check failed: {:?}", e);
+                    TransactionValidityError::from(InvalidTransaction::Call)
+                })?
+            };
 
             Ok::<_, TransactionValidityError>((
-                Some((address.clone(), params.device_id)),
+                validity,
+                (Some((address.clone(), params.device_id)), spends),
                 RawOrigin::Signed(address).into(),
             ))
         } else {
             // Check if the origin is signed by a session key.
             // Otherwise, pass the origin through unchanged.
             if let Ok(who) = ensure_signed(origin.clone()) {
-                if let Some((account, filter)) = Pallet::<T, I>::pass_account_from_session_key(&who)
+                if let Some((account, filter, creator)) =
+                    Pallet::<T, I>::pass_account_from_session_key(&who)
                 {
-                    if !filter.allows(
-                        T::CallMatcher::call_indices(call),
-                        T::SpendMatcher::spending_amount(call),
-                    ) {
-                        return Err(InvalidTransaction::Call.into());
-                    }
-                    Ok((None, RawOrigin::Signed(account).into()))
+                    let inspection = Pallet::<T, I>::check_filter(&filter, call)
+                        .filter(|inspection| {
+                            !Pallet::<T, I>::requires_approval(&account, inspection)
+                        })
+                        .ok_or(TransactionValidityError::from(InvalidTransaction::Call))?;
+                    let spends = Pallet::<T, I>::check_session_spend_budgets(
+                        &account,
+                        &who,
+                        creator,
+                        &filter,
+                        &inspection,
+                    )
+                    .map_err(|_| TransactionValidityError::from(InvalidTransaction::Call))?;
+                    Ok((
+                        ValidTransaction::default(),
+                        (None, spends),
+                        RawOrigin::Signed(account).into(),
+                    ))
                 } else {
-                    Ok((None, RawOrigin::Signed(who).into()))
+                    Ok((
+                        ValidTransaction::default(),
+                        (None, Vec::new()),
+                        RawOrigin::Signed(who).into(),
+                    ))
                 }
             } else {
-                Ok((None, origin))
+                Ok((ValidTransaction::default(), (None, Vec::new()), origin))
             }
         }?;
 
-        Ok((ValidTransaction::default(), device_id, origin))
+        Ok((validity, val, origin))
     }
 
     fn prepare(
@@ -157,8 +274,24 @@ where
 
         // Store the authenticated (account, device_id) so extrinsics can
         // read it for no-escalation checks.
-        if let Some(ref auth) = val {
-            AuthenticatedDevice::<T, I>::put(auth);
+        if let Some((ref address, ref device_id)) = val.0 {
+            // Only the next transaction of the device can be included.
+            let counter = self.0.as_ref().map(|params| params.counter);
+            if counter
+                != Some(Pallet::<T, I>::device_transactions_in_period(
+                    address, device_id,
+                ))
+            {
+                return Err(InvalidTransaction::Future.into());
+            }
+            AuthenticatedDevice::<T, I>::put((address, device_id));
+            Pallet::<T, I>::note_device_used(address, device_id);
+        }
+
+        // Charge the spends upfront, so the budgets are already reduced when
+        // the call is dispatched. They are refunded if the call fails.
+        for spend in &val.1 {
+            Pallet::<T, I>::charge_spend(spend);
         }
         Ok(val)
     }
@@ -168,12 +301,18 @@ where
         _info: &DispatchInfoOf<RuntimeCallFor<T>>,
         _post_info: &PostDispatchInfoOf<RuntimeCallFor<T>>,
         _len: usize,
-        _result: &DispatchResult,
+        result: &DispatchResult,
     ) -> Result<Weight, TransactionValidityError> {
+        let (auth, spends) = pre;
         // Clear transient storage regardless of success/failure.
-        if pre.is_some() {
+        if auth.is_some() {
             AuthenticatedDevice::<T, I>::kill();
         }
+        if result.is_err() {
+            for spend in &spends {
+                Pallet::<T, I>::refund_spend(spend);
+            }
+        }
         Ok(Weight::zero())
     }
 }
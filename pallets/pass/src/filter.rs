@@ -12,7 +12,12 @@
 //! - [`DeviceFilter::Pallets`] — whitelist by pallet index.
 //! - [`DeviceFilter::Calls`] — whitelist by `(pallet_index, call_index)`.
 //! - [`DeviceFilter::Spend`] — transfer listed assets up to per-asset limits.
-//!   Non-spending calls are rejected.
+//!   Non-spending calls are rejected. Limits can optionally carry a
+//!   [`SpendBudget`], capping the cumulative amount spent every period.
+//! - [`DeviceFilter::Scoped`] — a list of [`CallRule`]s, each allowing a
+//!   pallet or call, optionally restricted by its arguments ([`ArgsFilter`])
+//!   and by a spend limit, plus an optional [`ValidityWindow`]. E.g. a cashier
+//!   device that can only charge orders up to a given amount, during a shift.
 //!
 //! ## Delegation (no-escalation invariant)
 //!
@@ -20,36 +25,67 @@
 //! subset of its own (see [`DeviceFilter::is_superset_of`]). The hierarchy:
 //!
 //! ```text
-//! Admin  ──┬─► Pallets ──► Calls
+//! Admin  ──┬─► Pallets ──► Calls ──► Scoped
 //!          │
-//!          └─► Spend
+//!          └─► Spend ─────────────► Scoped (spend-limited rules only)
 //! ```
 //!
 //! - `Admin` can delegate to anything
 //! - `Pallets(S)` can delegate to `Pallets(S' ⊆ S)` or `Calls` within `S`
 //! - `Calls(S)` can only delegate to `Calls(S' ⊆ S)`
-//! - `Spend` can only delegate to `Spend` with lower/equal per-asset limits
+//! - `Spend` can only delegate to `Spend` with lower/equal per-asset limits,
+//!   and budgets that are lower/equal and span at least as many blocks
 //! - `Pallets`/`Calls` **cannot** delegate to `Spend` — this would let a
 //!   device with access to e.g. `system.remark` spawn spend-capable devices.
 //!   Spend filters must originate from an `Admin` device.
+//! - `Scoped` can be delegated from `Pallets`/`Calls` when all of its rules
+//!   fall within them, and from `Spend` when all of its rules carry a spend
+//!   limit within it. A `Scoped` filter can delegate to a `Scoped` filter with
+//!   a nested validity window and rules covered by its own, and to
+//!   `Pallets`/`Calls` only if it never expires and allows those without
+//!   argument or spend restrictions.
 //!
 //! ## Session keys
 //!
 //! Session keys are ephemeral and cannot use `Admin`. They still undergo the
 //! no-escalation check against the device that created them.
 //!
+//! ## Validity
+//!
+//! A `Scoped` filter with a [`ValidityWindow`] is only usable while the
+//! current block number is within the window. Outside of it, the device (or
+//! session key) can neither dispatch calls nor delegate.
+//!
 //! ## Spending inspection
 //!
 //! The runtime provides a [`SpendMatcher`] implementation that extracts
 //! `(asset_id, amount)` from runtime calls. For runtimes that don't use
 //! spend filters, `()` is a no-op implementation.
+//!
+//! ## Nested calls
+//!
+//! Calls that dispatch other calls (e.g. `utility.batch`, `proxy.proxy`) are
+//! expanded by the runtime's [`CallInspector`] into a [`CallInspection`], so
+//! filters are enforced against every call that ends up being dispatched:
+//!
+//! - `Pallets`/`Calls` must allow every call, wrappers included.
+//! - `Spend` ignores wrappers, but every leaf call must be a spend. Spends
+//!   are summed per asset before checking the limits.
+//! - `Scoped` must match every call, wrappers included, with a rule. Spends
+//!   of the calls that only match spend-limited rules are summed per asset
+//!   before checking the limits.
 
+use alloc::{collections::BTreeMap, vec, vec::Vec};
 use core::fmt::Debug;
 
 use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use frame_support::{pallet_prelude::*, CloneNoBound, DebugNoBound, EqNoBound, PartialEqNoBound};
 use scale_info::TypeInfo;
-use sp_runtime::BoundedBTreeSet;
+use sp_core::blake2_256;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, Saturating, Zero},
+    BoundedBTreeSet,
+};
 
 /// Compact per-device call filter.
 ///
@@ -84,20 +120,181 @@ pub enum DeviceFilter<
     /// Spend-only: can transfer listed assets, each up to a per-tx limit.
     /// Non-spending calls are rejected.
     Spend(BoundedVec<AssetSpendLimit<AssetId, Balance>, MaxAssets>),
+    /// Can call only what the listed rules allow, optionally within a validity window.
+    Scoped {
+        rules: BoundedVec<CallRule<AssetId, Balance>, MaxCalls>,
+        /// When the filter can be used. Never expires if `None`.
+        validity: Option<ValidityWindow>,
+    },
+}
+
+/// The maximum length of an [`ArgsFilter::Prefix`].
+pub const MAX_ARGS_PREFIX: u32 = 64;
+
+/// A rule of a [`DeviceFilter::Scoped`] filter, allowing a pallet or a single call.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub struct CallRule<AssetId: Clone + Debug + Eq, Balance: Clone + Debug + Eq> {
+    pub pallet: u8,
+    /// The allowed call. Any call in the pallet if `None`.
+    pub call: Option<u8>,
+    pub args: ArgsFilter,
+    /// If set, the call must be a spend of the given asset, within the limit.
+    pub spend: Option<AssetSpendLimit<AssetId, Balance>>,
+}
+
+/// Restricts the arguments of a call, i.e. its SCALE encoding after the call indices.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub enum ArgsFilter {
+    Any,
+    /// The `blake2_256` hash of the arguments must match.
+    Hash([u8; 32]),
+    /// The arguments must start with the given bytes.
+    Prefix(BoundedVec<u8, ConstU32<MAX_ARGS_PREFIX>>),
+}
+
+/// A range of blocks, from `from` (inclusive) until `until` (exclusive).
+#[derive(
+    Clone,
+    Copy,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ValidityWindow {
+    pub from: u32,
+    pub until: u32,
+}
+
+impl ValidityWindow {
+    pub fn contains(&self, now: u32) -> bool {
+        self.from <= now && now < self.until
+    }
+
+    pub fn is_superset_of(&self, other: &Self) -> bool {
+        self.from <= other.from && other.until <= self.until
+    }
+}
+
+impl ArgsFilter {
+    pub fn matches(&self, call: &InspectedCall<impl Sized, impl Sized>) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Hash(hash) => &call.args_hash == hash,
+            Self::Prefix(prefix) => call.args_prefix.starts_with(prefix),
+        }
+    }
+
+    /// Whether every argument matched by `other` is matched by `self`.
+    pub fn is_superset_of(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Any, _) => true,
+            (Self::Prefix(a), Self::Prefix(b)) => b.starts_with(a),
+            (Self::Hash(a), Self::Hash(b)) => a == b,
+            _ => false,
+        }
+    }
 }
 
 /// A single asset spend limit.
 #[derive(
     Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
 )]
-pub struct AssetSpendLimit<AssetId: Clone + Debug + Eq, Balance: Clone + Debug + Eq> {
+pub struct AssetSpendLimit<AssetId, Balance>
+where
+    AssetId: Clone + Debug + Eq,
+    Balance: Clone + Debug + Eq,
+{
     pub asset: AssetId,
+    /// The maximum amount per transaction.
     pub max_amount: Balance,
+    /// The maximum cumulative amount per period. Unlimited if `None`.
+    pub budget: Option<SpendBudget<Balance>>,
+}
+
+/// A cumulative spend limit, reset every `period` blocks.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub struct SpendBudget<Balance> {
+    pub amount: Balance,
+    /// The length of a period, in blocks. Periods start at multiples of this number.
+    pub period: u32,
+}
+
+impl<Balance: Ord> SpendBudget<Balance> {
+    /// Whether `self` allows spending at least as much as `other`, over any span of blocks.
+    pub fn is_superset_of(&self, other: &Self) -> bool {
+        self.amount >= other.amount && self.period <= other.period
+    }
+}
+
+impl<AssetId: Clone + Debug + Eq, Balance: Ord + Clone + Debug + Eq>
+    AssetSpendLimit<AssetId, Balance>
+{
+    /// Whether spending `amount` of `asset` in a single transaction is within this limit.
+    pub fn allows(&self, asset: &AssetId, amount: &Balance) -> bool {
+        &self.asset == asset && amount <= &self.max_amount
+    }
+
+    /// Whether `self` allows spending at least as much as `other`.
+    pub fn is_superset_of(&self, other: &Self) -> bool {
+        self.asset == other.asset
+            && self.max_amount >= other.max_amount
+            && match (&self.budget, &other.budget) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => a.is_superset_of(b),
+            }
+    }
+}
+
+impl<AssetId: Clone + Debug + Eq, Balance: Ord + Clone + Debug + Eq> CallRule<AssetId, Balance> {
+    /// Whether the call is allowed by this rule, including its spend limit.
+    pub fn allows(&self, call: &InspectedCall<AssetId, Balance>) -> bool {
+        self.pallet == call.index.0
+            && self.call.is_none_or(|c| c == call.index.1)
+            && self.args.matches(call)
+            && self.spend.as_ref().is_none_or(|limit| {
+                call.spend
+                    .as_ref()
+                    .is_some_and(|(asset, amount)| limit.allows(asset, amount))
+            })
+    }
+
+    /// Whether every call allowed by `other` is allowed by `self`.
+    pub fn is_superset_of(&self, other: &Self) -> bool {
+        self.pallet == other.pallet
+            && (self.call.is_none() || self.call == other.call)
+            && self.args.is_superset_of(&other.args)
+            && match (&self.spend, &other.spend) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => a.is_superset_of(b),
+            }
+    }
+
+    /// Whether this rule allows any call in `pallet` (or only `call`, if given) without
+    /// restrictions.
+    fn allows_unrestricted(&self, pallet: u8, call: Option<u8>) -> bool {
+        self.pallet == pallet
+            && (self.call.is_none() || self.call == call)
+            && self.args == ArgsFilter::Any
+            && self.spend.is_none()
+    }
 }
 
 impl<
         AssetId: Ord + Clone + Debug + Eq,
-        Balance: Ord + Clone + Debug + Eq,
+        Balance: Ord + Clone + Debug + Eq + Saturating + Zero,
         MaxCalls: Get<u32>,
         MaxAssets: Get<u32>,
     > DeviceFilter<AssetId, Balance, MaxCalls, MaxAssets>
@@ -112,19 +309,80 @@ impl<
             (Self::Pallets(a), Self::Pallets(b)) => b.iter().all(|p| a.contains(p)),
             (Self::Pallets(a), Self::Calls(b)) => b.iter().all(|(p, _)| a.contains(p)),
             (Self::Calls(a), Self::Calls(b)) => b.iter().all(|c| a.contains(c)),
-            (Self::Spend(a), Self::Spend(b)) => b.iter().all(|bl| {
-                a.iter()
-                    .any(|al| al.asset == bl.asset && al.max_amount >= bl.max_amount)
-            }),
+            (Self::Spend(a), Self::Spend(b)) => {
+                b.iter().all(|bl| a.iter().any(|al| al.is_superset_of(bl)))
+            }
             // Pallets/Calls CANNOT delegate to Spend: a Calls device with
             // access to `system.remark` must not be able to spawn a Spend
             // device that can transfer arbitrary assets. Spend must come
             // directly from an Admin device.
             (Self::Pallets(_) | Self::Calls(_), Self::Spend(_)) => false,
+            // Scoped rules only narrow down the calls they allow
+            (Self::Pallets(a), Self::Scoped { rules, .. }) => {
+                rules.iter().all(|r| a.contains(&r.pallet))
+            }
+            (Self::Calls(a), Self::Scoped { rules, .. }) => rules
+                .iter()
+                .all(|r| r.call.is_some_and(|c| a.contains(&(r.pallet, c)))),
+            // A spend-limited rule only allows a single spend within its limit
+            (Self::Spend(a), Self::Scoped { rules, .. }) => rules.iter().all(|r| {
+                r.spend
+                    .as_ref()
+                    .is_some_and(|bl| a.iter().any(|al| al.is_superset_of(bl)))
+            }),
             // Spend can't grant call/pallet access
             (Self::Spend(_), _) => false,
             // Calls can't grant pallet-wide access
             (Self::Calls(_), Self::Pallets(_)) => false,
+            (
+                Self::Scoped {
+                    rules: a,
+                    validity: va,
+                },
+                Self::Scoped {
+                    rules: b,
+                    validity: vb,
+                },
+            ) => {
+                let within_window = match (va, vb) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some(va), Some(vb)) => va.is_superset_of(vb),
+                };
+                within_window && b.iter().all(|br| a.iter().any(|ar| ar.is_superset_of(br)))
+            }
+            (
+                Self::Scoped {
+                    rules,
+                    validity: None,
+                },
+                Self::Pallets(b),
+            ) => b
+                .iter()
+                .all(|p| rules.iter().any(|r| r.allows_unrestricted(*p, None))),
+            (
+                Self::Scoped {
+                    rules,
+                    validity: None,
+                },
+                Self::Calls(b),
+            ) => b
+                .iter()
+                .all(|(p, c)| rules.iter().any(|r| r.allows_unrestricted(*p, Some(*c)))),
+            // Scoped can't grant access beyond its validity window, nor spend
+            // access to any call
+            (Self::Scoped { .. }, _) => false,
+        }
+    }
+
+    /// Whether the filter can be used at block `now`.
+    pub fn is_active(&self, now: u32) -> bool {
+        match self {
+            Self::Scoped {
+                validity: Some(window),
+                ..
+            } => window.contains(now),
+            _ => true,
         }
     }
 
@@ -135,6 +393,10 @@ impl<
     ///
     /// `spend_amount` is provided by the runtime's `SpendMatcher` and returns
     /// `Some((asset, amount))` for spending calls.
+    ///
+    /// Call arguments and validity windows are not known here, so `Scoped`
+    /// rules with an [`ArgsFilter`] other than `Any` never match. Use
+    /// [`Self::allows_all`] instead.
     pub fn allows(&self, call_index: (u8, u8), spend_amount: Option<(AssetId, Balance)>) -> bool {
         match self {
             Self::Admin => true,
@@ -144,10 +406,114 @@ impl<
                 let Some((asset, amount)) = spend_amount else {
                     return false;
                 };
-                limits
-                    .iter()
-                    .any(|l| l.asset == asset && amount <= l.max_amount)
+                limits.iter().any(|l| l.allows(&asset, &amount))
+            }
+            Self::Scoped { rules, .. } => rules.iter().any(|r| {
+                r.pallet == call_index.0
+                    && r.call.is_none_or(|c| c == call_index.1)
+                    && r.args == ArgsFilter::Any
+                    && r.spend.as_ref().is_none_or(|l| {
+                        spend_amount
+                            .as_ref()
+                            .is_some_and(|(asset, amount)| l.allows(asset, amount))
+                    })
+            }),
+        }
+    }
+
+    /// Check if a (possibly nested) call is allowed by this filter.
+    pub fn allows_all(&self, inspection: &CallInspection<AssetId, Balance>) -> bool {
+        match self {
+            Self::Admin => true,
+            Self::Pallets(set) => inspection.calls.iter().all(|c| set.contains(&c.index.0)),
+            Self::Calls(set) => inspection.calls.iter().all(|c| set.contains(&c.index)),
+            Self::Spend(limits) => {
+                inspection.only_spends
+                    && !inspection.spends.is_empty()
+                    && inspection
+                        .spends
+                        .iter()
+                        .all(|(asset, amount)| limits.iter().any(|l| l.allows(asset, amount)))
+            }
+            Self::Scoped { rules, .. } => {
+                let mut limited = BTreeMap::<&AssetId, Balance>::new();
+                for call in &inspection.calls {
+                    if rules.iter().any(|r| r.spend.is_none() && r.allows(call)) {
+                        continue;
+                    }
+                    if !rules.iter().any(|r| r.allows(call)) {
+                        return false;
+                    }
+                    // Only allowed by spend-limited rules, so it's a spend
+                    if let Some((asset, amount)) = &call.spend {
+                        let spent = limited.entry(asset).or_insert_with(Zero::zero);
+                        *spent = spent.clone().saturating_add(amount.clone());
+                    }
+                }
+                limited.into_iter().all(|(asset, amount)| {
+                    rules
+                        .iter()
+                        .filter_map(|r| r.spend.as_ref())
+                        .any(|l| l.allows(asset, &amount))
+                })
+            }
+        }
+    }
+
+    /// The spends of `inspection` to charge against a budget, summed per asset, along with the
+    /// budget of the limit that allowed them.
+    ///
+    /// A spend is allowed by the first limit (or `Scoped` rule) that allows it, and `Scoped`
+    /// rules without a spend limit take precedence. Spends allowed by a limit without a budget,
+    /// or by a rule without a spend limit, are not charged. If spends of the same asset are
+    /// allowed by rules with different budgets, the smallest one applies.
+    pub fn budgeted_spends(
+        &self,
+        inspection: &CallInspection<AssetId, Balance>,
+    ) -> Vec<(AssetId, Balance, SpendBudget<Balance>)> {
+        match self {
+            Self::Spend(limits) => inspection
+                .spends
+                .iter()
+                .filter_map(|(asset, amount)| {
+                    let limit = limits.iter().find(|l| l.allows(asset, amount))?;
+                    Some((asset.clone(), amount.clone(), limit.budget.clone()?))
+                })
+                .collect(),
+            Self::Scoped { rules, .. } => {
+                let mut budgeted = BTreeMap::<&AssetId, (Balance, SpendBudget<Balance>)>::new();
+                for call in &inspection.calls {
+                    let Some((asset, amount)) = &call.spend else {
+                        continue;
+                    };
+                    if rules.iter().any(|r| r.spend.is_none() && r.allows(call)) {
+                        continue;
+                    }
+                    let Some(budget) = rules
+                        .iter()
+                        .find(|r| r.allows(call))
+                        .and_then(|r| r.spend.as_ref()?.budget.clone())
+                    else {
+                        continue;
+                    };
+                    match budgeted.get_mut(asset) {
+                        Some((spent, smallest)) => {
+                            *spent = spent.clone().saturating_add(amount.clone());
+                            if budget.amount < smallest.amount {
+                                *smallest = budget;
+                            }
+                        }
+                        None => {
+                            budgeted.insert(asset, (amount.clone(), budget));
+                        }
+                    }
+                }
+                budgeted
+                    .into_iter()
+                    .map(|(asset, (amount, budget))| (asset.clone(), amount, budget))
+                    .collect()
             }
+            _ => Vec::new(),
         }
     }
 }
@@ -161,7 +527,7 @@ pub trait SpendMatcher<Call> {
     /// The asset identifier used in `Spend` filter entries.
     type AssetId: Parameter + MaxEncodedLen + Ord + Copy;
     /// The balance type used for per-asset spend limits.
-    type Balance: Parameter + MaxEncodedLen + Ord + Copy;
+    type Balance: Parameter + MaxEncodedLen + AtLeast32BitUnsigned + Copy;
 
     /// If the call is a spending operation (transfer, payment, etc.),
     /// return `(asset_id, amount)`. Otherwise return `None`.
@@ -177,6 +543,126 @@ impl<C> SpendMatcher<C> for () {
     }
 }
 
+/// Expands runtime calls that dispatch other calls, like batches or proxies.
+///
+/// If nested calls are not expected (or not allowed for pass accounts), use `()`,
+/// which treats every call as a leaf.
+pub trait CallInspector<Call> {
+    /// Calls nested deeper than this are rejected.
+    const MAX_DEPTH: u32 = 4;
+
+    /// Returns the calls directly dispatched by `call`, or `None` if it's a leaf call.
+    fn inner_calls(call: &Call) -> Option<Vec<&Call>>;
+}
+
+impl<C> CallInspector<C> for () {
+    fn inner_calls(_: &C) -> Option<Vec<&C>> {
+        None
+    }
+}
+
+/// A single call found when inspecting a runtime call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InspectedCall<AssetId, Balance> {
+    /// The `(pallet_index, call_index)` of the call.
+    pub index: (u8, u8),
+    /// The `blake2_256` hash of the encoded call arguments.
+    pub args_hash: [u8; 32],
+    /// The first [`MAX_ARGS_PREFIX`] bytes of the encoded call arguments.
+    pub args_prefix: Vec<u8>,
+    /// What the call spends, if it's a leaf call and a spend.
+    pub spend: Option<(AssetId, Balance)>,
+}
+
+impl<AssetId, Balance> InspectedCall<AssetId, Balance> {
+    pub fn new(index: (u8, u8), args: &[u8], spend: Option<(AssetId, Balance)>) -> Self {
+        Self {
+            index,
+            args_hash: blake2_256(args),
+            args_prefix: args[..args.len().min(MAX_ARGS_PREFIX as usize)].to_vec(),
+            spend,
+        }
+    }
+}
+
+/// The calls and spends of a (possibly nested) runtime call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallInspection<AssetId, Balance> {
+    /// Every call, wrappers included.
+    pub calls: Vec<InspectedCall<AssetId, Balance>>,
+    /// Whether every leaf call is a spend.
+    pub only_spends: bool,
+    /// The amounts spent by the leaf calls, summed per asset.
+    pub spends: Vec<(AssetId, Balance)>,
+}
+
+impl<AssetId: Ord + Copy, Balance: AtLeast32BitUnsigned + Copy> CallInspection<AssetId, Balance> {
+    /// Inspects `call`, expanding it with `I`. Returns `None` if the call is nested deeper than
+    /// [`CallInspector::MAX_DEPTH`].
+    pub fn inspect<Call, I, C, S>(call: &Call) -> Option<Self>
+    where
+        I: CallInspector<Call>,
+        C: CallMatcher<Call>,
+        S: SpendMatcher<Call, AssetId = AssetId, Balance = Balance>,
+    {
+        let mut calls = Vec::new();
+        let mut only_spends = true;
+        let mut spends = BTreeMap::<AssetId, Balance>::new();
+
+        let mut pending = vec![(call, 0u32)];
+        while let Some((call, depth)) = pending.pop() {
+            if depth > I::MAX_DEPTH {
+                return None;
+            }
+            let spend = match I::inner_calls(call) {
+                Some(inner) => {
+                    pending.extend(inner.into_iter().map(|c| (c, depth + 1)));
+                    None
+                }
+                None => {
+                    let spend = S::spending_amount(call);
+                    match spend {
+                        Some((asset, amount)) => {
+                            let spent = spends.entry(asset).or_insert_with(Zero::zero);
+                            *spent = spent.saturating_add(amount);
+                        }
+                        None => only_spends = false,
+                    }
+                    spend
+                }
+            };
+            calls.push(InspectedCall::new(
+                C::call_indices(call),
+                &C::call_args(call),
+                spend,
+            ));
+        }
+
+        Some(Self {
+            calls,
+            only_spends,
+            spends: spends.into_iter().collect(),
+        })
+    }
+
+    /// How many calls [`Self::inspect`] goes through when inspecting `call`, wrappers included,
+    /// without encoding them. Calls nested deeper than [`CallInspector::MAX_DEPTH`] are not
+    /// counted, since inspecting them fails.
+    pub fn count<Call, I: CallInspector<Call>>(call: &Call) -> u32 {
+        let mut count = 0u32;
+        let mut pending = vec![(call, 0u32)];
+        while let Some((call, depth)) = pending.pop() {
+            count.saturating_inc();
+            if depth < I::MAX_DEPTH {
+                if let Some(inner) = I::inner_calls(call) {
+                    pending.extend(inner.into_iter().map(|c| (c, depth + 1)));
+                }
+            }
+        }
+        count
+    }
+}
+
 /// Identifies a runtime call as a `(pallet_index, call_index)` pair for
 /// filter matching.
 ///
@@ -185,6 +671,9 @@ impl<C> SpendMatcher<C> for () {
 /// encoding (e.g. XCM-wrapped calls) can provide a custom implementation.
 pub trait CallMatcher<Call> {
     fn call_indices(call: &Call) -> (u8, u8);
+
+    /// The encoded arguments of the call, matched against [`ArgsFilter`]s.
+    fn call_args(call: &Call) -> Vec<u8>;
 }
 
 /// Default matcher: reads `(pallet_index, call_index)` from the first two
@@ -200,4 +689,8 @@ impl<Call: Encode> CallMatcher<Call> for ScaleCallMatcher {
             }
         })
     }
+
+    fn call_args(call: &Call) -> Vec<u8> {
+        call.using_encoded(|bytes| bytes.get(2..).unwrap_or_default().to_vec())
+    }
 }
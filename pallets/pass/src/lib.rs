@@ -7,6 +7,7 @@
 extern crate alloc;
 extern crate core;
 
+use alloc::{boxed::Box, vec::Vec};
 use core::fmt::Debug;
 use fc_traits_authn::*;
 use frame_support::{
@@ -18,13 +19,16 @@ use frame_support::{
             v3::{Named, TaskName},
             DispatchTime,
         },
-        Bounded, Consideration, EnsureOriginWithArg, Footprint,
+        tokens::{Fortitude, Preservation},
+        Bounded, Consideration, EnsureOriginWithArg, Footprint, IsSubType,
     },
     PalletId,
 };
 use frame_system::pallet_prelude::*;
 use sp_runtime::{
-    traits::{BlockNumberProvider, Hash, StaticLookup},
+    traits::{
+        BlockNumberProvider, Dispatchable, Hash, SaturatedConversion, Saturating, StaticLookup,
+    },
     DispatchResult,
 };
 
@@ -36,14 +40,20 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "std")]
+pub mod client;
 mod extensions;
 pub mod filter;
+pub mod migration;
+pub mod origin;
+pub mod runtime_api;
 mod types;
 
 pub mod weights;
 pub use extensions::*;
 pub use filter::*;
 pub use pallet::*;
+pub use runtime_api::*;
 pub use types::*;
 pub use weights::*;
 
@@ -54,7 +64,10 @@ pub mod pallet {
 
     #[pallet::config]
     pub trait Config<I: 'static = ()>:
-        frame_system::Config<RuntimeCall: From<Call<Self, I>>, RuntimeEvent: From<Event<Self, I>>>
+        frame_system::Config<
+        RuntimeCall: From<Call<Self, I>> + IsSubType<Call<Self, I>>,
+        RuntimeEvent: From<Event<Self, I>>,
+    >
     {
         // Primitives: Some overarching types that come from the system (or the system depends on).
 
@@ -73,6 +86,13 @@ pub mod pallet {
             HashedUserId,
             Success = Self::AccountId,
         >;
+        /// The origin of a guardian taking part in the recovery of a pass account. Returns the
+        /// [`AccountId`] that identifies the guardian, so it can be a signed account (including
+        /// other pass accounts), or a community origin mapped to an account.
+        type GuardianOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+        /// The origin that manages the terms registrars register accounts under (e.g.
+        /// governance).
+        type RegistrarsOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
         // Dependencies: The external components this pallet depends on.
 
@@ -86,6 +106,12 @@ pub mod pallet {
         /// The `Scheduler` system.
         type Scheduler: Named<BlockNumberFor<Self, I>, Self::RuntimeCall, Self::PalletsOrigin>;
         type BlockNumberProvider: BlockNumberProvider;
+        /// Sets the priority of transactions authenticated by a device. Use `()` for no
+        /// priority.
+        type DevicePriority: DevicePriority<Self, I>;
+        /// Creates the gas tank new pass accounts pay for their transactions with. Use `()` to not
+        /// create gas tanks.
+        type GasTank: AccountGasTank<Self, I>;
 
         // Device filters: types for per-device call authorization.
 
@@ -95,6 +121,9 @@ pub mod pallet {
         /// Identifies calls as `(pallet_index, call_index)` for filter
         /// matching. Defaults can use `ScaleCallMatcher` from this pallet.
         type CallMatcher: CallMatcher<Self::RuntimeCall>;
+        /// Expands calls that dispatch other calls (e.g. batches), so device filters are
+        /// enforced against every nested call. Use `()` if nested calls aren't expected.
+        type CallInspector: CallInspector<Self::RuntimeCall>;
 
         // Considerations: Costs that are "taken from [the caller's] account temporarily in order to
         // offset the cost to the chain of holding some data Footprint in state".
@@ -102,7 +131,9 @@ pub mod pallet {
         /// A `Consideration` helper to handle the deposits for registering an account. The account
         /// registrar would cover for the consideration.
         type RegistrarConsideration: Consideration<Self::AccountId, Footprint>;
-        /// A `Consideration` helper to handle the deposits for storing devices.
+        /// A `Consideration` helper to handle the deposits for storing devices. Also covers the
+        /// guardian set, pending recovery, approval policy and calls pending approval of an
+        /// account.
         type DeviceConsideration: Consideration<Self::AccountId, Footprint>;
         /// A `Consideration` helper to handle the deposits for storing session keys.
         type SessionKeyConsideration: Consideration<Self::AccountId, Footprint>;
@@ -115,6 +146,9 @@ pub mod pallet {
         /// The maximum amount of devices an account might have
         #[pallet::constant]
         type MaxDevicesPerAccount: Get<u32>;
+        /// The maximum length of a device's label and platform.
+        #[pallet::constant]
+        type MaxDeviceLabelLen: Get<u32>;
         /// The maximum amount of sessions an account might have
         #[pallet::constant]
         type MaxSessionsPerAccount: Get<u32>;
@@ -127,6 +161,23 @@ pub mod pallet {
         /// Maximum assets in a device's spend filter.
         #[pallet::constant]
         type MaxFilteredAssets: Get<u32>;
+        /// The maximum amount of guardians an account might have.
+        #[pallet::constant]
+        type MaxGuardians: Get<u32>;
+        /// The minimum time an owner has to cancel an approved recovery.
+        #[pallet::constant]
+        type MinRecoveryDelay: Get<BlockNumberFor<Self, I>>;
+        /// The maximum amount of calls pending approval an account might have.
+        #[pallet::constant]
+        type MaxPendingProposals: Get<u32>;
+        /// The maximum amount of transactions a device without a rate limit of its own (see
+        /// [`DeviceRateLimits`]) might authenticate per `DeviceRateLimitPeriod`.
+        #[pallet::constant]
+        type MaxDeviceTransactions: Get<u32>;
+        /// The length, in blocks, of the periods the transactions of devices without a rate
+        /// limit of their own are counted in.
+        #[pallet::constant]
+        type DeviceRateLimitPeriod: Get<u32>;
 
         // Benchmarking: Types to handle benchmarks.
 
@@ -146,7 +197,10 @@ pub mod pallet {
         SessionKeys,
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T, I = ()>(_);
 
     /// A map of the pass accounts registered by a system account, with a mapping of the
@@ -155,12 +209,42 @@ pub mod pallet {
     pub type RegisteredAccounts<T: Config<I>, I: 'static = ()> =
         StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, HashedUserId, ()>;
 
+    /// The registrar of a pass account, and the [`HashedUserId`] used to register it.
+    #[pallet::storage]
+    pub type AccountRegistrars<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, HashedUserId)>;
+
+    /// The pass account a [`HashedUserId`] is bound to. Accounts are bound when registered, so
+    /// they can still be found if the `AddressGenerator` changes, or after being rebound to
+    /// another [`HashedUserId`]. Accounts registered before bindings existed are found through
+    /// version `0` of the `AddressGenerator` until migrated.
+    #[pallet::storage]
+    pub type UserIdAddresses<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, HashedUserId, T::AccountId>;
+
+    /// The [`HashedUserId`] a pass account is bound to.
+    #[pallet::storage]
+    pub type AccountUserIds<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, HashedUserId>;
+
     /// Counts how many pass accounts a system account has registered and holds an amount as
     /// registrar.
     #[pallet::storage]
     pub type RegistrarConsiderations<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Blake2_128Concat, T::AccountId, (T::RegistrarConsideration, u32)>;
 
+    /// The terms a registrar registers accounts under. Registrars without an entry here
+    /// register accounts without limits.
+    #[pallet::storage]
+    pub type Registrars<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, RegistrarInfo>;
+
+    /// The registrar that pays for the device deposits of a pass account, when the account
+    /// doesn't pay for them itself.
+    #[pallet::storage]
+    pub type DeviceDepositSponsors<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+
     /// A map of the devices registered on behalf of an account.
     #[pallet::storage]
     pub type Devices<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
@@ -172,6 +256,17 @@ pub mod pallet {
         DeviceOf<T, I>,
     >;
 
+    /// Descriptive information of the devices registered on behalf of an account.
+    #[pallet::storage]
+    pub type DeviceInfos<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        DeviceId,
+        DeviceInfoOf<T, I>,
+    >;
+
     /// A list the ids of the devices registered in the pallet.
     #[pallet::storage]
     pub type DeviceIds<T: Config<I>, I: 'static = ()> =
@@ -194,11 +289,55 @@ pub mod pallet {
         StorageMap<_, Blake2_128Concat, T::AccountId, (T::DeviceConsideration, u32)>;
 
     #[pallet::storage]
-    pub type SessionKeys<T: Config<I>, I: 'static = ()> = CountedStorageMap<
+    pub type SessionKeys<T: Config<I>, I: 'static = ()> =
+        CountedStorageMap<_, Blake2_128Concat, T::AccountId, SessionOf<T, I>>;
+
+    /// A list of the session keys registered by a pass account.
+    #[pallet::storage]
+    pub type AccountSessionKeys<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+    /// Counts how many session keys a pass account has registered and holds an amount to the
+    /// account.
+    #[pallet::storage]
+    pub type SessionKeyConsiderations<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::SessionKeyConsideration, u32)>;
+
+    /// How many transactions a device authenticated in the current rate limit period, along
+    /// with the block the period started.
+    #[pallet::storage]
+    pub type DeviceTransactions<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
         _,
         Blake2_128Concat,
         T::AccountId,
-        (T::AccountId, BlockNumberFor<T, I>, DeviceFilterOf<T, I>),
+        Blake2_128Concat,
+        DeviceId,
+        (BlockNumberFor<T, I>, u32),
+    >;
+
+    /// The rate limits set for devices. Devices without an entry might authenticate up to
+    /// `MaxDeviceTransactions` per `DeviceRateLimitPeriod`.
+    #[pallet::storage]
+    pub type DeviceRateLimits<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        DeviceId,
+        DeviceRateLimit,
+    >;
+
+    /// How much a device or session key has spent of an asset in the current period of its
+    /// [`SpendBudget`], along with the block the period started.
+    #[pallet::storage]
+    pub type PeriodSpends<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, Spender<T::AccountId>>,
+            NMapKey<Blake2_128Concat, SpendAssetOf<T, I>>,
+        ),
+        (BlockNumberFor<T, I>, SpendBalanceOf<T, I>),
     >;
 
     /// The `(pass_account, device_id)` that authenticated the current
@@ -209,7 +348,8 @@ pub mod pallet {
     /// - **Set**: by `PassAuthenticate::prepare` when the tx was
     ///   authenticated with a device credential.
     /// - **Read**: by `check_no_escalation` during `add_device` /
-    ///   `add_session_key`.
+    ///   `add_session_key`, and by `approve_call` / `cancel_proposal` to
+    ///   identify the approving device.
     /// - **Cleared**: at the start of every `PassAuthenticate::prepare`
     ///   (defense-in-depth) AND in `post_dispatch_details`.
     ///
@@ -235,6 +375,46 @@ pub mod pallet {
     pub type AccountSessionsCount<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
+    /// The guardians that can recover a pass account.
+    #[pallet::storage]
+    pub type Guardians<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, GuardianSetOf<T, I>>;
+
+    /// The recovery in progress for a pass account, if any.
+    #[pallet::storage]
+    pub type Recoveries<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, RecoveryOf<T, I>>;
+
+    /// Counts the guardian set and pending recovery of a pass account, and holds an amount to
+    /// the account.
+    #[pallet::storage]
+    pub type RecoveryConsiderations<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::DeviceConsideration, u32)>;
+
+    /// The calls of a pass account that require approvals from several of its devices.
+    #[pallet::storage]
+    pub type ApprovalPolicies<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ApprovalPolicyOf<T, I>>;
+
+    /// The calls of a pass account waiting for approvals, by call hash.
+    #[pallet::storage]
+    pub type Proposals<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Identity, T::Hash, ProposalOf<T, I>>;
+
+    /// Counts the approval policy and calls pending approval of a pass account, and holds an
+    /// amount to the account.
+    #[pallet::storage]
+    pub type ApprovalConsiderations<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::DeviceConsideration, u32)>;
+
+    /// The pass account whose approved call is being dispatched.
+    ///
+    /// Set by `approve_call` only while dispatching a call that got enough approvals, so
+    /// `set_approval_policy` and `kill_account` can tell whether they were approved.
+    #[pallet::storage]
+    pub(crate) type ExecutingProposal<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::AccountId>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config<I>, I: 'static = ()> {
@@ -249,12 +429,106 @@ pub mod pallet {
             who: T::AccountId,
             device_id: DeviceId,
         },
+        DeviceFilterUpdated {
+            who: T::AccountId,
+            device_id: DeviceId,
+            filter: DeviceFilterOf<T, I>,
+        },
+        DeviceMetadataSet {
+            who: T::AccountId,
+            device_id: DeviceId,
+        },
         SessionCreated {
             session_key_hash: T::Hash,
             until: BlockNumberFor<T, I>,
+            filter: DeviceFilterOf<T, I>,
+        },
+        SessionRenewed {
+            session_key_hash: T::Hash,
+            until: BlockNumberFor<T, I>,
+            filter: DeviceFilterOf<T, I>,
         },
         SessionRemoved {
             session_key: T::AccountId,
+            filter: DeviceFilterOf<T, I>,
+        },
+        GuardiansSet {
+            who: T::AccountId,
+            threshold: u32,
+        },
+        GuardiansRemoved {
+            who: T::AccountId,
+        },
+        RecoveryInitiated {
+            who: T::AccountId,
+            guardian: T::AccountId,
+            device_id: DeviceId,
+        },
+        RecoveryApproved {
+            who: T::AccountId,
+            guardian: T::AccountId,
+        },
+        /// The recovery got enough approvals, and can be finished once the delay is over.
+        RecoveryDelayStarted {
+            who: T::AccountId,
+            executable_at: BlockNumberFor<T, I>,
+        },
+        RecoveryCancelled {
+            who: T::AccountId,
+        },
+        RecoveryFinished {
+            who: T::AccountId,
+            device_id: DeviceId,
+        },
+        /// The account got killed, releasing all its deposits.
+        AccountKilled {
+            who: T::AccountId,
+        },
+        ApprovalPolicySet {
+            who: T::AccountId,
+            threshold: u32,
+        },
+        ApprovalPolicyRemoved {
+            who: T::AccountId,
+        },
+        /// A device proposed a call that requires approvals.
+        CallProposed {
+            who: T::AccountId,
+            call_hash: T::Hash,
+            expires_at: BlockNumberFor<T, I>,
+        },
+        CallApproved {
+            who: T::AccountId,
+            call_hash: T::Hash,
+            device_id: DeviceId,
+        },
+        /// A call got enough approvals and was dispatched.
+        ProposalExecuted {
+            who: T::AccountId,
+            call_hash: T::Hash,
+            result: DispatchResult,
+        },
+        ProposalCancelled {
+            who: T::AccountId,
+            call_hash: T::Hash,
+        },
+        RegistrarSet {
+            registrar: T::AccountId,
+            info: RegistrarInfo,
+        },
+        RegistrarRemoved {
+            registrar: T::AccountId,
+        },
+        /// The account is now bound to another [`HashedUserId`].
+        UserIdRotated {
+            who: T::AccountId,
+            user_id: HashedUserId,
+        },
+        /// The device has a rate limit of its own, or uses the default one again if `None`.
+        DeviceRateLimitSet {
+            who: T::AccountId,
+            device_id: DeviceId,
+            limit: Option<DeviceRateLimit>,
         },
     }
 
@@ -284,6 +558,44 @@ pub mod pallet {
         /// The transaction did not carry a device authentication, so no
         /// device filter context is available for this operation.
         NotAuthenticatedByDevice,
+        /// The guardians are empty, repeated, include the account itself, or the threshold or
+        /// delay are out of bounds.
+        InvalidGuardians,
+        /// The caller is not a guardian of the account.
+        NotGuardian,
+        /// The account has no guardians set.
+        GuardiansNotFound,
+        /// There is already a recovery in progress for the account.
+        RecoveryInProgress,
+        RecoveryNotFound,
+        /// The guardian already approved the recovery, or the device already approved the call.
+        AlreadyApproved,
+        /// The recovery lacks approvals, or its delay is not over yet.
+        RecoveryNotReady,
+        /// The spend would exceed the budget of the device for the current period.
+        SpendBudgetExceeded,
+        /// The threshold or timeout of the approval policy are out of bounds.
+        InvalidApprovalPolicy,
+        /// The account has no approval policy set.
+        ApprovalPolicyNotFound,
+        /// The call requires approvals from several devices.
+        ApprovalRequired,
+        ProposalNotFound,
+        /// The device did not approve the call.
+        NotApprover,
+        /// The proposal can still be approved.
+        ProposalNotExpired,
+        MaxProposalsExceeded,
+        /// The registrar is suspended, and can't register new accounts.
+        RegistrarSuspended,
+        /// The registrar reached the maximum amount of accounts it might register.
+        MaxRegistrationsExceeded,
+        /// The [`HashedUserId`] is already bound to a pass account.
+        UserIdInUse,
+        /// The device authenticated too many transactions in the current rate limit period.
+        RateLimitExceeded,
+        /// The rate limit allows no transactions, or its period is empty.
+        InvalidRateLimit,
     }
 
     #[pallet::call(weight(<T as Config<I>>::WeightInfo))]
@@ -297,6 +609,26 @@ pub mod pallet {
         ) -> DispatchResult {
             let registrar = &T::RegisterOrigin::ensure_origin(origin, &user)?;
             let address = &T::AddressGenerator::generate_address(user);
+            ensure!(
+                !UserIdAddresses::<T, I>::contains_key(user)
+                    && !Devices::<T, I>::contains_prefix(Self::address_for(user)),
+                Error::<T, I>::AccountAlreadyRegistered
+            );
+
+            let registrar_info = Registrars::<T, I>::get(registrar);
+            if let Some(info) = &registrar_info {
+                ensure!(
+                    info.status == RegistrarStatus::Active,
+                    Error::<T, I>::RegistrarSuspended
+                );
+                let registrations = RegistrarConsiderations::<T, I>::get(registrar)
+                    .map(|(_, count)| count)
+                    .unwrap_or(0);
+                ensure!(
+                    info.max_registrations.is_none_or(|max| registrations < max),
+                    Error::<T, I>::MaxRegistrationsExceeded
+                );
+            }
 
             // Handles the deposit of storage for the account
             ConsiderationHandler::<
@@ -306,7 +638,16 @@ pub mod pallet {
                 HashedUserId,
             >::increment(registrar)?;
 
+            RegisteredAccounts::<T, I>::insert(registrar, user, ());
+            AccountRegistrars::<T, I>::insert(address, (registrar.clone(), user));
+            UserIdAddresses::<T, I>::insert(user, address);
+            AccountUserIds::<T, I>::insert(address, user);
+
             Self::create_account(address)?;
+            T::GasTank::make_tank(address)?;
+            if registrar_info.is_some_and(|info| info.sponsor_device_deposits) {
+                DeviceDepositSponsors::<T, I>::insert(address, registrar);
+            }
             Self::try_add_device(address, attestation, DeviceFilter::Admin)
         }
 
@@ -358,7 +699,7 @@ pub mod pallet {
                 Error::<T, I>::AccountForSessionKeyAlreadyExists
             );
 
-            if let Some((account, _, _)) = SessionKeys::<T, I>::get(session_key) {
+            if let Some((account, ..)) = SessionKeys::<T, I>::get(session_key) {
                 // Ensure another user is not using this session key.
                 ensure!(&account == address, Error::<T, I>::SessionKeyInUse);
 
@@ -375,43 +716,679 @@ pub mod pallet {
                 address,
                 AccountSessionsCount::<T, I>::get(address).saturating_add(1),
             );
-
-            let until = duration
-                .unwrap_or(T::MaxSessionDuration::get())
-                .min(T::MaxSessionDuration::get());
-
-            SessionKeys::<T, I>::insert(session_key.clone(), (address.clone(), until, filter));
-            Self::schedule_next_removal(session_key, duration)?;
+            ConsiderationHandler::<
+                T::AccountId,
+                SessionKeyConsiderations<T, I>,
+                T::SessionKeyConsideration,
+                SessionOf<T, I>,
+            >::increment(address)?;
+
+            let creator = Self::authenticated_device(address)?;
+            let until = Self::schedule_next_removal(session_key, duration)?;
+            SessionKeys::<T, I>::insert(
+                session_key.clone(),
+                (address.clone(), until, filter.clone(), creator),
+            );
+            AccountSessionKeys::<T, I>::insert(address, session_key, ());
 
             Self::deposit_event(Event::<T, I>::SessionCreated {
                 session_key_hash: T::Hashing::hash(&session_key.encode()),
                 until,
+                filter,
             });
 
             Ok(())
         }
 
+        /// Removes a session key, releasing its deposit. Besides `Root` (used when the session
+        /// expires), the pass account can revoke its own session keys, as long as the
+        /// authenticated device either created the session or is at least as permissive as
+        /// the session's filter.
         #[pallet::call_index(4)]
         pub fn remove_session_key(
             origin: OriginFor<T>,
             session_key: T::AccountId,
         ) -> DispatchResult {
-            ensure_root(origin)?;
+            if ensure_root(origin.clone()).is_err() {
+                let address = &Self::ensure_signer_is_pass_account(origin)?;
+                Self::check_session_access(address, &session_key)?;
+            }
             Self::try_remove_session_key(&session_key)
         }
+
+        /// Sets the guardians that can recover the account, replacing the existing ones. Any
+        /// recovery in progress is cancelled. Requires an `Admin` device.
+        #[pallet::call_index(5)]
+        pub fn set_guardians(
+            origin: OriginFor<T>,
+            guardians: BoundedVec<T::AccountId, T::MaxGuardians>,
+            threshold: u32,
+            delay: BlockNumberFor<T, I>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+
+            let mut unique = guardians.to_vec();
+            unique.sort();
+            unique.dedup();
+            ensure!(
+                unique.len() == guardians.len()
+                    && !guardians.contains(address)
+                    && (1..=guardians.len() as u32).contains(&threshold)
+                    && delay >= T::MinRecoveryDelay::get(),
+                Error::<T, I>::InvalidGuardians
+            );
+
+            Self::try_cancel_recovery(address)?;
+            if !Guardians::<T, I>::contains_key(address) {
+                Self::recovery_consideration_increment(address)?;
+            }
+            Guardians::<T, I>::insert(
+                address,
+                GuardianSet {
+                    guardians,
+                    threshold,
+                    delay,
+                },
+            );
+
+            Self::deposit_event(Event::<T, I>::GuardiansSet {
+                who: address.clone(),
+                threshold,
+            });
+            Ok(())
+        }
+
+        /// Removes the guardians of the account, cancelling any recovery in progress. Requires
+        /// an `Admin` device.
+        #[pallet::call_index(6)]
+        pub fn remove_guardians(origin: OriginFor<T>) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+            ensure!(
+                Guardians::<T, I>::contains_key(address),
+                Error::<T, I>::GuardiansNotFound
+            );
+
+            Self::try_cancel_recovery(address)?;
+            Guardians::<T, I>::remove(address);
+            Self::recovery_consideration_decrement(address)?;
+
+            Self::deposit_event(Event::<T, I>::GuardiansRemoved {
+                who: address.clone(),
+            });
+            Ok(())
+        }
+
+        /// A guardian initiates the recovery of an account, proposing a new `Admin` device, and
+        /// optionally a device to revoke. Counts as the guardian's approval.
+        #[pallet::call_index(7)]
+        pub fn initiate_recovery(
+            origin: OriginFor<T>,
+            account: AccountIdLookupOf<T>,
+            attestation: DeviceAttestationOf<T, I>,
+            revoke: Option<DeviceId>,
+        ) -> DispatchResult {
+            let guardian = T::GuardianOrigin::ensure_origin(origin)?;
+            let address = &T::Lookup::lookup(account)?;
+            Self::ensure_guardian(address, &guardian)?;
+            ensure!(
+                !Recoveries::<T, I>::contains_key(address),
+                Error::<T, I>::RecoveryInProgress
+            );
+
+            let device_id = *attestation.device_id();
+            let device = Self::verify_new_device(address, attestation)?;
+
+            Self::recovery_consideration_increment(address)?;
+            Recoveries::<T, I>::insert(
+                address,
+                Recovery {
+                    device,
+                    revoke,
+                    approvals: BoundedVec::new(),
+                    executable_at: None,
+                },
+            );
+
+            Self::deposit_event(Event::<T, I>::RecoveryInitiated {
+                who: address.clone(),
+                guardian: guardian.clone(),
+                device_id,
+            });
+            Self::try_approve_recovery(address, guardian)
+        }
+
+        /// A guardian approves the recovery in progress for an account. Once the threshold of
+        /// approvals is met, the recovery can be finished after the delay set by the account.
+        #[pallet::call_index(8)]
+        pub fn approve_recovery(
+            origin: OriginFor<T>,
+            account: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            let guardian = T::GuardianOrigin::ensure_origin(origin)?;
+            let address = &T::Lookup::lookup(account)?;
+            Self::ensure_guardian(address, &guardian)?;
+            Self::try_approve_recovery(address, guardian)
+        }
+
+        /// The owner of the account cancels the recovery in progress.
+        #[pallet::call_index(9)]
+        pub fn cancel_recovery(origin: OriginFor<T>) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            ensure!(
+                Recoveries::<T, I>::contains_key(address),
+                Error::<T, I>::RecoveryNotFound
+            );
+            Self::try_cancel_recovery(address)
+        }
+
+        /// Finishes an approved recovery once its delay is over, registering the new `Admin`
+        /// device and revoking the old one, if requested. Can be called by anyone.
+        #[pallet::call_index(10)]
+        pub fn finish_recovery(
+            origin: OriginFor<T>,
+            account: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let address = &T::Lookup::lookup(account)?;
+            let recovery =
+                Recoveries::<T, I>::get(address).ok_or(Error::<T, I>::RecoveryNotFound)?;
+            ensure!(
+                recovery
+                    .executable_at
+                    .is_some_and(|at| T::BlockNumberProvider::current_block_number() >= at),
+                Error::<T, I>::RecoveryNotReady
+            );
+
+            Recoveries::<T, I>::remove(address);
+            Self::recovery_consideration_decrement(address)?;
+
+            if let Some(revoke) = recovery.revoke {
+                if Devices::<T, I>::contains_key(address, revoke) {
+                    Self::try_remove_device(address, &revoke)?;
+                }
+            }
+            let device_id = *recovery.device.device_id();
+            Self::insert_device(address, recovery.device, DeviceFilter::Admin)?;
+
+            Self::deposit_event(Event::<T, I>::RecoveryFinished {
+                who: address.clone(),
+                device_id,
+            });
+            Ok(())
+        }
+
+        /// Kills the account, removing its devices, session keys, guardians and recovery in
+        /// progress, and releasing all the deposits taken for them, including the registrar's.
+        /// The remaining balance of the account is transferred to `beneficiary`. Requires an
+        /// `Admin` device. Accounts with an approval policy can only be killed through
+        /// [`Pallet::approve_call`].
+        #[pallet::call_index(11)]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::kill_account(
+            T::MaxDevicesPerAccount::get(),
+            T::MaxSessionsPerAccount::get(),
+        ))]
+        pub fn kill_account(
+            origin: OriginFor<T>,
+            beneficiary: AccountIdLookupOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+            ensure!(
+                !ApprovalPolicies::<T, I>::contains_key(address)
+                    || ExecutingProposal::<T, I>::get().as_ref() == Some(address),
+                Error::<T, I>::ApprovalRequired
+            );
+            let beneficiary = &T::Lookup::lookup(beneficiary)?;
+
+            let (devices, sessions) = Self::try_kill_account(address)?;
+
+            let amount = T::Balances::reducible_balance(
+                address,
+                Preservation::Expendable,
+                Fortitude::Polite,
+            );
+            if address != beneficiary && !amount.is_zero() {
+                T::Balances::transfer(address, beneficiary, amount, Preservation::Expendable)?;
+            }
+            frame_system::Pallet::<T>::dec_providers(address)?;
+
+            Self::deposit_event(Event::<T, I>::AccountKilled {
+                who: address.clone(),
+            });
+            Ok(Some(<T as Config<I>>::WeightInfo::kill_account(
+                devices, sessions,
+            ))
+            .into())
+        }
+
+        /// Sets the calls that require approvals from several devices of the account, or
+        /// removes them, cancelling the calls pending approval. Requires an `Admin` device. An
+        /// existing policy can only be changed through [`Pallet::approve_call`].
+        #[pallet::call_index(12)]
+        pub fn set_approval_policy(
+            origin: OriginFor<T>,
+            policy: Option<ApprovalPolicyOf<T, I>>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+            let exists = ApprovalPolicies::<T, I>::contains_key(address);
+            ensure!(
+                !exists || ExecutingProposal::<T, I>::get().as_ref() == Some(address),
+                Error::<T, I>::ApprovalRequired
+            );
+
+            match policy {
+                Some(policy) => {
+                    ensure!(
+                        (2..=T::MaxDevicesPerAccount::get()).contains(&policy.threshold)
+                            && policy.timeout > 0,
+                        Error::<T, I>::InvalidApprovalPolicy
+                    );
+                    if !exists {
+                        Self::approval_consideration_increment(address)?;
+                    }
+                    let threshold = policy.threshold;
+                    ApprovalPolicies::<T, I>::insert(address, policy);
+                    Self::deposit_event(Event::<T, I>::ApprovalPolicySet {
+                        who: address.clone(),
+                        threshold,
+                    });
+                }
+                None => {
+                    ensure!(exists, Error::<T, I>::ApprovalPolicyNotFound);
+                    Self::try_remove_approval_policy(address)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Approves a call on behalf of the authenticated device, proposing it if it's not
+        /// pending approval already (taking a deposit). Once enough devices approve it, the call
+        /// is dispatched as the account. Every approving device must be allowed to dispatch the
+        /// call, and have enough budget left for its spends. The spends are only charged once the
+        /// call is dispatched, against the budget of the device that proposed it (or the one
+        /// approving it last, if that device was removed).
+        #[pallet::call_index(13)]
+        #[pallet::weight({
+            let info = call.get_dispatch_info();
+            <T as Config<I>>::WeightInfo::approve_call().saturating_add(info.call_weight)
+        })]
+        pub fn approve_call(
+            origin: OriginFor<T>,
+            call: Box<<T as frame_system::Config>::RuntimeCall>,
+        ) -> DispatchResultWithPostInfo {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            let device_id = Self::authenticated_device(address)?;
+            let policy = ApprovalPolicies::<T, I>::get(address)
+                .ok_or(Error::<T, I>::ApprovalPolicyNotFound)?;
+
+            // The device must be allowed to dispatch the call by itself
+            let filter = DeviceFilters::<T, I>::get(address, device_id)
+                .ok_or(Error::<T, I>::NotAuthenticatedByDevice)?;
+            let inspection =
+                Self::check_filter(&filter, &call).ok_or(Error::<T, I>::CallNotAllowed)?;
+            let spends = Self::check_spend_budgets(
+                address,
+                Spender::Device(device_id),
+                &filter,
+                &inspection,
+            )?;
+
+            let call_hash = T::Hashing::hash_of(&call);
+            let now = T::BlockNumberProvider::current_block_number();
+            let mut proposal = match Proposals::<T, I>::get(address, call_hash) {
+                Some(proposal) if proposal.expires_at > now => proposal,
+                expired => {
+                    if expired.is_none() {
+                        let pending = ApprovalConsiderations::<T, I>::get(address)
+                            .map(|(_, count)| count.saturating_sub(1))
+                            .unwrap_or_default();
+                        ensure!(
+                            pending < T::MaxPendingProposals::get(),
+                            Error::<T, I>::MaxProposalsExceeded
+                        );
+                        Self::approval_consideration_increment(address)?;
+                    }
+                    let expires_at = now.saturating_add(policy.timeout.into());
+                    Self::deposit_event(Event::<T, I>::CallProposed {
+                        who: address.clone(),
+                        call_hash,
+                        expires_at,
+                    });
+                    Proposal {
+                        approvals: BoundedVec::new(),
+                        expires_at,
+                    }
+                }
+            };
+
+            ensure!(
+                !proposal.approvals.contains(&device_id),
+                Error::<T, I>::AlreadyApproved
+            );
+            proposal
+                .approvals
+                .try_push(device_id)
+                .map_err(|_| Error::<T, I>::MaxDevicesExceeded)?;
+            Self::deposit_event(Event::<T, I>::CallApproved {
+                who: address.clone(),
+                call_hash,
+                device_id,
+            });
+
+            if (proposal.approvals.len() as u32) < policy.threshold {
+                Proposals::<T, I>::insert(address, call_hash, proposal);
+                return Ok(Some(<T as Config<I>>::WeightInfo::approve_call()).into());
+            }
+
+            Proposals::<T, I>::remove(address, call_hash);
+            Self::approval_consideration_decrement(address)?;
+
+            let spends = match proposal.approvals.first() {
+                Some(proposer) if *proposer != device_id => {
+                    match DeviceFilters::<T, I>::get(address, proposer) {
+                        Some(proposer_filter) => Self::check_spend_budgets(
+                            address,
+                            Spender::Device(*proposer),
+                            &proposer_filter,
+                            &inspection,
+                        )?,
+                        None => spends,
+                    }
+                }
+                _ => spends,
+            };
+            for spend in &spends {
+                Self::charge_spend(spend);
+            }
+
+            let executing = ExecutingProposal::<T, I>::take();
+            ExecutingProposal::<T, I>::put(address);
+            let result = call
+                .dispatch(frame_system::RawOrigin::Signed(address.clone()).into())
+                .map(|_| ())
+                .map_err(|e| e.error);
+            ExecutingProposal::<T, I>::set(executing);
+            if result.is_err() {
+                for spend in &spends {
+                    Self::refund_spend(spend);
+                }
+            }
+
+            Self::deposit_event(Event::<T, I>::ProposalExecuted {
+                who: address.clone(),
+                call_hash,
+                result,
+            });
+            Ok(().into())
+        }
+
+        /// Cancels a call pending approval, releasing its deposit. Requires a device that
+        /// approved the call.
+        #[pallet::call_index(14)]
+        pub fn cancel_proposal(origin: OriginFor<T>, call_hash: T::Hash) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            let device_id = Self::authenticated_device(address)?;
+            let proposal = Proposals::<T, I>::get(address, call_hash)
+                .ok_or(Error::<T, I>::ProposalNotFound)?;
+            ensure!(
+                proposal.approvals.contains(&device_id),
+                Error::<T, I>::NotApprover
+            );
+            Self::try_remove_proposal(address, call_hash)
+        }
+
+        /// Removes an expired call pending approval, releasing its deposit. Can be called by
+        /// anyone.
+        #[pallet::call_index(15)]
+        pub fn remove_expired_proposal(
+            origin: OriginFor<T>,
+            account: AccountIdLookupOf<T>,
+            call_hash: T::Hash,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let address = &T::Lookup::lookup(account)?;
+            let proposal = Proposals::<T, I>::get(address, call_hash)
+                .ok_or(Error::<T, I>::ProposalNotFound)?;
+            ensure!(
+                T::BlockNumberProvider::current_block_number() >= proposal.expires_at,
+                Error::<T, I>::ProposalNotExpired
+            );
+            Self::try_remove_proposal(address, call_hash)
+        }
+
+        /// Changes the filter of a device. The authenticated device must be at least as
+        /// permissive as both the current and the new filter.
+        #[pallet::call_index(16)]
+        pub fn update_device_filter(
+            origin: OriginFor<T>,
+            device_id: DeviceId,
+            filter: DeviceFilterOf<T, I>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_device_access(address, &device_id)?;
+            Self::check_no_escalation(address, &filter)?;
+
+            DeviceFilters::<T, I>::insert(address, device_id, filter.clone());
+            Self::deposit_event(Event::<T, I>::DeviceFilterUpdated {
+                who: address.clone(),
+                device_id,
+                filter,
+            });
+            Ok(())
+        }
+
+        /// Sets the label and platform of a device. The authenticated device must be at least
+        /// as permissive as the filter of the device.
+        #[pallet::call_index(17)]
+        pub fn set_device_metadata(
+            origin: OriginFor<T>,
+            device_id: DeviceId,
+            label: Option<DeviceLabelOf<T, I>>,
+            platform: Option<DeviceLabelOf<T, I>>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_device_access(address, &device_id)?;
+
+            DeviceInfos::<T, I>::mutate(address, device_id, |info| {
+                let info = info.get_or_insert_with(|| DeviceInfo::new(Zero::zero()));
+                info.label = label;
+                info.platform = platform;
+            });
+            Self::deposit_event(Event::<T, I>::DeviceMetadataSet {
+                who: address.clone(),
+                device_id,
+            });
+            Ok(())
+        }
+
+        /// Extends a session key so it expires after `duration` blocks from now, capped at
+        /// `MaxSessionDuration`. The same devices that can revoke a session can renew it.
+        #[pallet::call_index(18)]
+        pub fn renew_session_key(
+            origin: OriginFor<T>,
+            session_key: T::AccountId,
+            duration: Option<BlockNumberFor<T, I>>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            let (_, _, filter, creator) = Self::check_session_access(address, &session_key)?;
+
+            let until = Self::schedule_next_removal(&session_key, duration)?;
+            SessionKeys::<T, I>::insert(
+                session_key.clone(),
+                (address.clone(), until, filter.clone(), creator),
+            );
+
+            Self::deposit_event(Event::<T, I>::SessionRenewed {
+                session_key_hash: T::Hashing::hash(&session_key.encode()),
+                until,
+                filter,
+            });
+            Ok(())
+        }
+
+        /// Sets the terms a registrar registers accounts under, or removes them, so the
+        /// registrar registers accounts without limits. Suspending a registrar doesn't affect the
+        /// accounts it already registered, and whether it sponsors device deposits only applies
+        /// to accounts registered afterwards.
+        #[pallet::call_index(19)]
+        pub fn set_registrar(
+            origin: OriginFor<T>,
+            registrar: AccountIdLookupOf<T>,
+            info: Option<RegistrarInfo>,
+        ) -> DispatchResult {
+            T::RegistrarsOrigin::ensure_origin(origin)?;
+            let registrar = T::Lookup::lookup(registrar)?;
+
+            if let Some(info) = info {
+                Registrars::<T, I>::insert(&registrar, info.clone());
+                Self::deposit_event(Event::<T, I>::RegistrarSet { registrar, info });
+            } else {
+                Registrars::<T, I>::remove(&registrar);
+                Self::deposit_event(Event::<T, I>::RegistrarRemoved { registrar });
+            }
+            Ok(())
+        }
+
+        /// Binds the account to another [`HashedUserId`] (e.g. after the user changed their email
+        /// with the identity provider), keeping its address. Credentials for the previous
+        /// [`HashedUserId`] are no longer accepted. Requires an `Admin` device.
+        #[pallet::call_index(20)]
+        pub fn rotate_user_id(origin: OriginFor<T>, user_id: HashedUserId) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+            ensure!(
+                !UserIdAddresses::<T, I>::contains_key(user_id)
+                    && !Devices::<T, I>::contains_prefix(Self::address_for(user_id)),
+                Error::<T, I>::UserIdInUse
+            );
+
+            if let Some(previous) = AccountUserIds::<T, I>::get(address) {
+                UserIdAddresses::<T, I>::remove(previous);
+            }
+            UserIdAddresses::<T, I>::insert(user_id, address);
+            AccountUserIds::<T, I>::insert(address, user_id);
+
+            if let Some((registrar, previous)) = AccountRegistrars::<T, I>::get(address) {
+                RegisteredAccounts::<T, I>::remove(&registrar, previous);
+                RegisteredAccounts::<T, I>::insert(&registrar, user_id, ());
+                AccountRegistrars::<T, I>::insert(address, (registrar, user_id));
+            }
+
+            Self::deposit_event(Event::<T, I>::UserIdRotated {
+                who: address.clone(),
+                user_id,
+            });
+            Ok(())
+        }
+
+        /// Sets how many transactions a device might authenticate per period, or removes its
+        /// rate limit so the default one applies. Requires an `Admin` device, so restricted
+        /// devices can't lift their own limits. Limits must allow at least one transaction per
+        /// period, so devices are removed rather than locked out.
+        #[pallet::call_index(21)]
+        pub fn set_device_rate_limit(
+            origin: OriginFor<T>,
+            device_id: DeviceId,
+            limit: Option<DeviceRateLimit>,
+        ) -> DispatchResult {
+            let address = &Self::ensure_signer_is_pass_account(origin)?;
+            Self::check_no_escalation(address, &DeviceFilter::Admin)?;
+            ensure!(
+                Devices::<T, I>::contains_key(address, device_id),
+                Error::<T, I>::DeviceNotFound
+            );
+            if let Some(limit) = &limit {
+                ensure!(
+                    limit.max_transactions > 0 && limit.period > 0,
+                    Error::<T, I>::InvalidRateLimit
+                );
+            }
+
+            DeviceRateLimits::<T, I>::set(address, device_id, limit);
+            Self::deposit_event(Event::<T, I>::DeviceRateLimitSet {
+                who: address.clone(),
+                device_id,
+                limit,
+            });
+            Ok(())
+        }
     }
 }
 
 impl<T: Config<I>, I: 'static> Pallet<T, I> {
+    /// The address of the pass account bound to `user`. Accounts not bound to any are the ones
+    /// registered before bindings existed, so their address is derived with the scheme of
+    /// version `0` of the `AddressGenerator`.
     pub fn address_for(user: HashedUserId) -> T::AccountId {
-        T::AddressGenerator::generate_address(user)
+        UserIdAddresses::<T, I>::get(user)
+            .unwrap_or_else(|| T::AddressGenerator::generate_address_at(0, user))
     }
 
-    /// Extracts the pass account and filter from a session key.
+    /// The devices registered for `who`, with their filters and metadata. Backs the
+    /// [`PassApi`] runtime API.
+    pub fn devices(who: T::AccountId) -> Vec<DeviceDetailsOf<T, I>> {
+        Devices::<T, I>::iter_key_prefix(&who)
+            .map(|device_id| DeviceDetails {
+                device_id,
+                filter: DeviceFilters::<T, I>::get(&who, device_id),
+                info: DeviceInfos::<T, I>::get(&who, device_id),
+            })
+            .collect()
+    }
+
+    /// The session keys of `who` that haven't expired yet. Backs the [`PassApi`] runtime API.
+    pub fn sessions(who: T::AccountId) -> Vec<SessionDetailsOf<T, I>> {
+        let now = T::BlockNumberProvider::current_block_number();
+        AccountSessionKeys::<T, I>::iter_key_prefix(&who)
+            .filter_map(|session_key| {
+                let (_, until, filter, _) = SessionKeys::<T, I>::get(&session_key)?;
+                (until >= now).then_some(SessionDetails {
+                    session_key,
+                    until,
+                    filter,
+                })
+            })
+            .collect()
+    }
+
+    /// Dry-runs the checks a transaction authenticated by `device_id` would go through to
+    /// dispatch `call` on behalf of `who`. Backs the [`PassApi`] runtime API.
+    pub fn can_dispatch(
+        who: T::AccountId,
+        device_id: DeviceId,
+        call: <T as frame_system::Config>::RuntimeCall,
+    ) -> DispatchResult {
+        let filter =
+            DeviceFilters::<T, I>::get(&who, device_id).ok_or(Error::<T, I>::DeviceNotFound)?;
+
+        // Like in `PassAuthenticate`, approved calls are checked by `approve_call` itself,
+        // which doesn't require the approval of the call it dispatches.
+        let (call, approved) = match call.is_sub_type() {
+            Some(Call::approve_call { call }) => ((**call).clone(), true),
+            _ => (call, false),
+        };
+        ensure!(
+            !approved || ApprovalPolicies::<T, I>::contains_key(&who),
+            Error::<T, I>::ApprovalPolicyNotFound
+        );
+        let inspection = Self::check_filter(&filter, &call).ok_or(Error::<T, I>::CallNotAllowed)?;
+        ensure!(
+            approved || !Self::requires_approval(&who, &inspection),
+            Error::<T, I>::ApprovalRequired
+        );
+        Self::check_spend_budgets(&who, Spender::Device(device_id), &filter, &inspection)?;
+        Ok(())
+    }
+
+    /// Extracts the pass account, filter and the device that created it from a session key.
     pub(crate) fn pass_account_from_session_key(
         who: &T::AccountId,
-    ) -> Option<(T::AccountId, DeviceFilterOf<T, I>)> {
-        SessionKeys::<T, I>::get(who).map(|(account, _, filter)| (account, filter))
+    ) -> Option<(T::AccountId, DeviceFilterOf<T, I>, DeviceId)> {
+        SessionKeys::<T, I>::get(who)
+            .map(|(account, _, filter, creator)| (account, filter, creator))
     }
 
     /// Verify the no-escalation invariant: the authenticated device's filter must
@@ -420,7 +1397,91 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         address: &T::AccountId,
         requested: &DeviceFilterOf<T, I>,
     ) -> DispatchResult {
-        let (auth_account, caller_device_id) =
+        let caller_device_id = Self::authenticated_device(address)?;
+        let caller_filter = DeviceFilters::<T, I>::get(address, caller_device_id)
+            .ok_or(Error::<T, I>::NotAuthenticatedByDevice)?;
+        ensure!(
+            caller_filter.is_active(Self::filter_block_number())
+                && caller_filter.is_superset_of(requested),
+            Error::<T, I>::PermissionEscalation
+        );
+        Ok(())
+    }
+
+    /// Ensures the authenticated device can manage `device_id`, that is, it's at least as
+    /// permissive as its filter. Devices can't manage devices with more permissions.
+    fn check_device_access(address: &T::AccountId, device_id: &DeviceId) -> DispatchResult {
+        let filter =
+            DeviceFilters::<T, I>::get(address, device_id).ok_or(Error::<T, I>::DeviceNotFound)?;
+        Self::check_no_escalation(address, &filter)
+    }
+
+    /// Ensures the authenticated device can manage `session_key` on behalf of `address`: it
+    /// either created the session, or is at least as permissive as the session's filter.
+    fn check_session_access(
+        address: &T::AccountId,
+        session_key: &T::AccountId,
+    ) -> Result<SessionOf<T, I>, DispatchError> {
+        let session = SessionKeys::<T, I>::get(session_key)
+            .filter(|(account, ..)| account == address)
+            .ok_or(Error::<T, I>::SessionNotFound)?;
+        if Self::authenticated_device(address)? != session.3 {
+            Self::check_no_escalation(address, &session.2)?;
+        }
+        Ok(session)
+    }
+
+    /// The rate limit of a device: its own, or `MaxDeviceTransactions` per
+    /// `DeviceRateLimitPeriod` if it has none.
+    pub fn device_rate_limit(address: &T::AccountId, device_id: &DeviceId) -> DeviceRateLimit {
+        DeviceRateLimits::<T, I>::get(address, device_id).unwrap_or(DeviceRateLimit {
+            max_transactions: T::MaxDeviceTransactions::get(),
+            period: T::DeviceRateLimitPeriod::get(),
+        })
+    }
+
+    /// Records that a device authenticated a transaction at the current block, counting it
+    /// towards its rate limit.
+    pub(crate) fn note_device_used(address: &T::AccountId, device_id: &DeviceId) {
+        DeviceInfos::<T, I>::mutate(address, device_id, |info| {
+            info.get_or_insert_with(|| DeviceInfo::new(Zero::zero()))
+                .last_used = Some(T::BlockNumberProvider::current_block_number());
+        });
+        let period_start = Self::period_start(Self::device_rate_limit(address, device_id).period);
+        let count = Self::device_transactions_in_period(address, device_id);
+        DeviceTransactions::<T, I>::insert(address, device_id, (period_start, count + 1));
+    }
+
+    /// How many transactions a device authenticated in the current rate limit period. It's the
+    /// counter the next transaction of the device must be authenticated with.
+    pub fn device_transactions_in_period(address: &T::AccountId, device_id: &DeviceId) -> u32 {
+        let period_start = Self::period_start(Self::device_rate_limit(address, device_id).period);
+        DeviceTransactions::<T, I>::get(address, device_id)
+            .filter(|(start, _)| *start == period_start)
+            .map(|(_, count)| count)
+            .unwrap_or(0)
+    }
+
+    /// Ensures the transaction of a device with the given `counter` is within its rate limit.
+    /// Returns the blocks of the current rate limit period.
+    pub(crate) fn check_rate_limit(
+        address: &T::AccountId,
+        device_id: &DeviceId,
+        counter: u32,
+    ) -> Result<core::ops::Range<types::BlockNumberFor<T, I>>, DispatchError> {
+        let limit = Self::device_rate_limit(address, device_id);
+        ensure!(
+            counter < limit.max_transactions,
+            Error::<T, I>::RateLimitExceeded
+        );
+        let period = limit.period.max(1);
+        let period_start = Self::period_start(period);
+        Ok(period_start..period_start.saturating_add(period.into()))
+    }
+
+    /// Returns the device that authenticated the current transaction on behalf of `address`.
+    fn authenticated_device(address: &T::AccountId) -> Result<DeviceId, DispatchError> {
+        let (auth_account, device_id) =
             AuthenticatedDevice::<T, I>::get().ok_or(Error::<T, I>::NotAuthenticatedByDevice)?;
         // Stale authentication context from a different account is ignored.
         ensure!(
@@ -428,16 +1489,10 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             Error::<T, I>::NotAuthenticatedByDevice
         );
         ensure!(
-            Devices::<T, I>::contains_key(address, &caller_device_id),
+            Devices::<T, I>::contains_key(address, device_id),
             Error::<T, I>::DeviceNotFound
         );
-        let caller_filter = DeviceFilters::<T, I>::get(address, caller_device_id)
-            .ok_or(Error::<T, I>::NotAuthenticatedByDevice)?;
-        ensure!(
-            caller_filter.is_superset_of(requested),
-            Error::<T, I>::PermissionEscalation
-        );
-        Ok(())
+        Ok(device_id)
     }
 
     /// Ensure that the signed origin maps onto an already existing pass account.
@@ -474,9 +1529,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         credential: &CredentialOf<T, I>,
         extrinsic_context: &impl ExtrinsicContext,
     ) -> Result<T::AccountId, DispatchError> {
-        let address = T::AddressGenerator::generate_address(credential.user_id());
+        let user_id = credential.user_id();
+        let address = Self::address_for(user_id);
+        // Accounts rebound to another user id can't be accessed with the previous one.
         ensure!(
-            Devices::<T, I>::contains_prefix(&address),
+            Devices::<T, I>::contains_prefix(&address)
+                && AccountUserIds::<T, I>::get(&address).is_none_or(|bound| bound == user_id),
             Error::<T, I>::AccountNotFound
         );
 
@@ -497,15 +1555,35 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         attestation: DeviceAttestationOf<T, I>,
         filter: DeviceFilterOf<T, I>,
     ) -> DispatchResult {
-        let device_id = attestation.device_id();
+        let device = Self::verify_new_device(address, attestation)?;
+        Self::insert_device(address, device, filter)
+    }
+
+    /// Verifies the attestation of a device to be registered on behalf of an account.
+    fn verify_new_device(
+        address: &T::AccountId,
+        attestation: DeviceAttestationOf<T, I>,
+    ) -> Result<DeviceOf<T, I>, DispatchError> {
         ensure!(
-            !DeviceIds::<T, I>::contains_key(device_id),
+            !DeviceIds::<T, I>::contains_key(attestation.device_id()),
             Error::<T, I>::DeviceAlreadyExists
         );
 
         // Devices are expected to be registered on behalf of an user
-        let device = T::Authenticator::verify_device(attestation.clone(), &address.encode())
-            .ok_or(Error::<T, I>::DeviceAttestationInvalid)?;
+        T::Authenticator::verify_device(attestation, &address.encode())
+            .ok_or(Error::<T, I>::DeviceAttestationInvalid.into())
+    }
+
+    fn insert_device(
+        address: &T::AccountId,
+        device: DeviceOf<T, I>,
+        filter: DeviceFilterOf<T, I>,
+    ) -> DispatchResult {
+        let device_id = *device.device_id();
+        ensure!(
+            !DeviceIds::<T, I>::contains_key(device_id),
+            Error::<T, I>::DeviceAlreadyExists
+        );
 
         let devices_count = DeviceConsiderations::<T, I>::get(address)
             .map(|(_, c)| c)
@@ -520,15 +1598,20 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             DeviceConsiderations<T, I>,
             T::DeviceConsideration,
             DeviceOf<T, I>,
-        >::increment(address)?;
+        >::increment_paid_by(address, &Self::device_deposit_payer(address))?;
 
         Devices::<T, I>::insert(address, device_id, device);
         DeviceIds::<T, I>::insert(device_id, ());
         DeviceFilters::<T, I>::insert(address, device_id, filter);
+        DeviceInfos::<T, I>::insert(
+            address,
+            device_id,
+            DeviceInfo::new(T::BlockNumberProvider::current_block_number()),
+        );
 
         Self::deposit_event(Event::<T, I>::DeviceAdded {
             who: address.clone(),
-            device_id: *device_id,
+            device_id,
         });
 
         Ok(())
@@ -545,11 +1628,19 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             DeviceConsiderations<T, I>,
             T::DeviceConsideration,
             DeviceOf<T, I>,
-        >::decrement(address)?;
+        >::decrement_paid_by(address, &Self::device_deposit_payer(address))?;
 
         Devices::<T, I>::remove(address, id);
         DeviceIds::<T, I>::remove(id);
         DeviceFilters::<T, I>::remove(address, id);
+        DeviceInfos::<T, I>::remove(address, id);
+        DeviceTransactions::<T, I>::remove(address, id);
+        DeviceRateLimits::<T, I>::remove(address, id);
+        let _ = PeriodSpends::<T, I>::clear_prefix(
+            (address, Spender::Device(*id)),
+            T::MaxFilteredAssets::get(),
+            None,
+        );
 
         Self::deposit_event(Event::<T, I>::DeviceRemoved {
             who: address.clone(),
@@ -559,19 +1650,373 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         Ok(())
     }
 
-    /// Removes a previously existing session. This is infallible.
+    /// The account that pays for the device deposits of `address`: its sponsor, if any, or the
+    /// account itself.
+    fn device_deposit_payer(address: &T::AccountId) -> T::AccountId {
+        DeviceDepositSponsors::<T, I>::get(address).unwrap_or_else(|| address.clone())
+    }
+
+    /// Removes all the storage of an account, and drops the considerations taken for it.
+    /// Returns how many devices and session keys were removed.
+    fn try_kill_account(address: &T::AccountId) -> Result<(u32, u32), DispatchError> {
+        let session_keys = AccountSessionKeys::<T, I>::iter_key_prefix(address).collect::<Vec<_>>();
+        for session_key in &session_keys {
+            Self::try_remove_session_key(session_key)?;
+        }
+        AccountSessionsCount::<T, I>::remove(address);
+
+        let devices = Devices::<T, I>::drain_prefix(address).collect::<Vec<_>>();
+        for (device_id, _) in &devices {
+            DeviceIds::<T, I>::remove(device_id);
+            DeviceFilters::<T, I>::remove(address, device_id);
+            DeviceInfos::<T, I>::remove(address, device_id);
+            DeviceTransactions::<T, I>::remove(address, device_id);
+            DeviceRateLimits::<T, I>::remove(address, device_id);
+            let _ = PeriodSpends::<T, I>::clear_prefix(
+                (address, Spender::Device(*device_id)),
+                T::MaxFilteredAssets::get(),
+                None,
+            );
+            Self::deposit_event(Event::<T, I>::DeviceRemoved {
+                who: address.clone(),
+                device_id: *device_id,
+            });
+        }
+
+        Self::try_cancel_recovery(address)?;
+        if Guardians::<T, I>::take(address).is_some() {
+            Self::deposit_event(Event::<T, I>::GuardiansRemoved {
+                who: address.clone(),
+            });
+        }
+
+        if let Some((consideration, _)) = DeviceConsiderations::<T, I>::take(address) {
+            consideration.drop(&Self::device_deposit_payer(address))?;
+        }
+        DeviceDepositSponsors::<T, I>::remove(address);
+        if let Some((consideration, _)) = SessionKeyConsiderations::<T, I>::take(address) {
+            consideration.drop(address)?;
+        }
+        if let Some((consideration, _)) = RecoveryConsiderations::<T, I>::take(address) {
+            consideration.drop(address)?;
+        }
+        T::GasTank::remove_tank(address)?;
+
+        if ApprovalPolicies::<T, I>::contains_key(address) {
+            Self::try_remove_approval_policy(address)?;
+        }
+
+        if let Some(user) = AccountUserIds::<T, I>::take(address) {
+            UserIdAddresses::<T, I>::remove(user);
+        }
+
+        // Accounts registered before registrars were tracked have no entry here.
+        if let Some((registrar, user)) = AccountRegistrars::<T, I>::take(address) {
+            RegisteredAccounts::<T, I>::remove(&registrar, user);
+            ConsiderationHandler::<
+                T::AccountId,
+                RegistrarConsiderations<T, I>,
+                T::RegistrarConsideration,
+                HashedUserId,
+            >::decrement(&registrar)?;
+        }
+
+        Ok((devices.len() as u32, session_keys.len() as u32))
+    }
+
+    /// Inspects a call, expanding its nested calls. Returns `None` if the call is nested too
+    /// deep.
+    pub(crate) fn inspect_call(call: &T::RuntimeCall) -> Option<CallInspectionOf<T, I>> {
+        CallInspection::inspect::<_, T::CallInspector, T::CallMatcher, T::SpendMatcher>(call)
+    }
+
+    /// Inspects a call and checks it against `filter`. Returns `None` if the filter is not
+    /// active at the current block, or doesn't allow the call.
+    pub(crate) fn check_filter(
+        filter: &DeviceFilterOf<T, I>,
+        call: &T::RuntimeCall,
+    ) -> Option<CallInspectionOf<T, I>> {
+        if !filter.is_active(Self::filter_block_number()) {
+            return None;
+        }
+        Self::inspect_call(call).filter(|inspection| filter.allows_all(inspection))
+    }
+
+    /// The current block number, as used by filter validity windows.
+    fn filter_block_number() -> u32 {
+        T::BlockNumberProvider::current_block_number().saturated_into()
+    }
+
+    /// Returns the spends of an inspected call to charge against the budgets of `filter`, for
+    /// the assets with a budgeted limit. Fails if the budget for the current period of any asset would be
+    /// exceeded.
+    pub(crate) fn check_spend_budgets(
+        address: &T::AccountId,
+        spender: Spender<T::AccountId>,
+        filter: &DeviceFilterOf<T, I>,
+        inspection: &CallInspectionOf<T, I>,
+    ) -> Result<Vec<BudgetedSpendOf<T, I>>, DispatchError> {
+        filter
+            .budgeted_spends(inspection)
+            .into_iter()
+            .map(|(asset, amount, budget)| {
+                let spent = Self::spent_in_period(address, &spender, &asset, budget.period);
+                ensure!(
+                    spent.saturating_add(amount) <= budget.amount,
+                    Error::<T, I>::SpendBudgetExceeded
+                );
+                Ok(BudgetedSpend {
+                    who: address.clone(),
+                    spender: spender.clone(),
+                    asset,
+                    amount,
+                    period: budget.period,
+                })
+            })
+            .collect()
+    }
+
+    /// Like [`Self::check_spend_budgets`], for a call made by a session key. The spends are also
+    /// charged against the budgets of the device that created the session, so a device can't
+    /// spend more than its budgets through several session keys.
+    pub(crate) fn check_session_spend_budgets(
+        address: &T::AccountId,
+        session_key: &T::AccountId,
+        creator: DeviceId,
+        filter: &DeviceFilterOf<T, I>,
+        inspection: &CallInspectionOf<T, I>,
+    ) -> Result<Vec<BudgetedSpendOf<T, I>>, DispatchError> {
+        let mut spends = Self::check_spend_budgets(
+            address,
+            Spender::SessionKey(session_key.clone()),
+            filter,
+            inspection,
+        )?;
+        match DeviceFilters::<T, I>::get(address, creator) {
+            Some(creator_filter) => spends.extend(Self::check_spend_budgets(
+                address,
+                Spender::Device(creator),
+                &creator_filter,
+                inspection,
+            )?),
+            // The budgets of a removed device can't be tracked anymore
+            None => ensure!(spends.is_empty(), Error::<T, I>::SpendBudgetExceeded),
+        }
+        Ok(spends)
+    }
+
+    /// Records a spend checked by [`Self::check_spend_budgets`].
+    pub(crate) fn charge_spend(spend: &BudgetedSpendOf<T, I>) {
+        let BudgetedSpend {
+            who,
+            spender,
+            asset,
+            amount,
+            period,
+        } = spend;
+        let spent = Self::spent_in_period(who, spender, asset, *period);
+        PeriodSpends::<T, I>::insert(
+            (who, spender, asset),
+            (Self::period_start(*period), spent.saturating_add(*amount)),
+        );
+    }
+
+    /// Returns a charged spend to the budget, i.e. if the spending call failed. Spends charged
+    /// in a past period are not refunded.
+    pub(crate) fn refund_spend(spend: &BudgetedSpendOf<T, I>) {
+        let BudgetedSpend {
+            who,
+            spender,
+            asset,
+            amount,
+            period,
+        } = spend;
+        PeriodSpends::<T, I>::mutate_exists((who, spender, asset), |maybe_spent| {
+            if let Some((start, spent)) = maybe_spent {
+                if *start == Self::period_start(*period) {
+                    *spent = spent.saturating_sub(*amount);
+                }
+            }
+        });
+    }
+
+    fn spent_in_period(
+        address: &T::AccountId,
+        spender: &Spender<T::AccountId>,
+        asset: &SpendAssetOf<T, I>,
+        period: u32,
+    ) -> SpendBalanceOf<T, I> {
+        PeriodSpends::<T, I>::get((address, spender, asset))
+            .filter(|(start, _)| *start == Self::period_start(period))
+            .map(|(_, spent)| spent)
+            .unwrap_or_else(Zero::zero)
+    }
+
+    fn period_start(period: u32) -> types::BlockNumberFor<T, I> {
+        let now = T::BlockNumberProvider::current_block_number();
+        now.saturating_sub(now % types::BlockNumberFor::<T, I>::from(period.max(1)))
+    }
+
+    fn ensure_guardian(address: &T::AccountId, guardian: &T::AccountId) -> DispatchResult {
+        let guardian_set =
+            Guardians::<T, I>::get(address).ok_or(Error::<T, I>::GuardiansNotFound)?;
+        ensure!(
+            guardian_set.guardians.contains(guardian),
+            Error::<T, I>::NotGuardian
+        );
+        Ok(())
+    }
+
+    /// Adds the approval of a guardian to the recovery of an account, starting the delay once
+    /// the threshold is met.
+    fn try_approve_recovery(address: &T::AccountId, guardian: T::AccountId) -> DispatchResult {
+        let GuardianSet {
+            threshold, delay, ..
+        } = Guardians::<T, I>::get(address).ok_or(Error::<T, I>::GuardiansNotFound)?;
+
+        Recoveries::<T, I>::try_mutate(address, |maybe_recovery| {
+            let recovery = maybe_recovery
+                .as_mut()
+                .ok_or(Error::<T, I>::RecoveryNotFound)?;
+            ensure!(
+                !recovery.approvals.contains(&guardian),
+                Error::<T, I>::AlreadyApproved
+            );
+            recovery
+                .approvals
+                .try_push(guardian.clone())
+                .map_err(|_| Error::<T, I>::NotGuardian)?;
+
+            Self::deposit_event(Event::<T, I>::RecoveryApproved {
+                who: address.clone(),
+                guardian,
+            });
+
+            if recovery.executable_at.is_none() && recovery.approvals.len() as u32 >= threshold {
+                let executable_at =
+                    T::BlockNumberProvider::current_block_number().saturating_add(delay);
+                recovery.executable_at = Some(executable_at);
+                Self::deposit_event(Event::<T, I>::RecoveryDelayStarted {
+                    who: address.clone(),
+                    executable_at,
+                });
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Cancels the recovery in progress for an account, if any.
+    fn try_cancel_recovery(address: &T::AccountId) -> DispatchResult {
+        if Recoveries::<T, I>::take(address).is_some() {
+            Self::recovery_consideration_decrement(address)?;
+            Self::deposit_event(Event::<T, I>::RecoveryCancelled {
+                who: address.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether a call requires approvals from several devices of the account.
+    pub(crate) fn requires_approval(
+        address: &T::AccountId,
+        inspection: &CallInspectionOf<T, I>,
+    ) -> bool {
+        ApprovalPolicies::<T, I>::get(address)
+            .is_some_and(|policy| policy.requires_approval(inspection))
+    }
+
+    /// Removes the approval policy of an account, along with its calls pending approval,
+    /// releasing their deposits.
+    fn try_remove_approval_policy(address: &T::AccountId) -> DispatchResult {
+        let call_hashes = Proposals::<T, I>::iter_key_prefix(address).collect::<Vec<_>>();
+        for call_hash in call_hashes {
+            Self::try_remove_proposal(address, call_hash)?;
+        }
+        ApprovalPolicies::<T, I>::remove(address);
+        Self::approval_consideration_decrement(address)?;
+        Self::deposit_event(Event::<T, I>::ApprovalPolicyRemoved {
+            who: address.clone(),
+        });
+        Ok(())
+    }
+
+    fn try_remove_proposal(address: &T::AccountId, call_hash: T::Hash) -> DispatchResult {
+        Proposals::<T, I>::remove(address, call_hash);
+        Self::approval_consideration_decrement(address)?;
+        Self::deposit_event(Event::<T, I>::ProposalCancelled {
+            who: address.clone(),
+            call_hash,
+        });
+        Ok(())
+    }
+
+    fn approval_consideration_increment(address: &T::AccountId) -> DispatchResult {
+        ConsiderationHandler::<
+            T::AccountId,
+            ApprovalConsiderations<T, I>,
+            T::DeviceConsideration,
+            ProposalOf<T, I>,
+        >::increment(address)
+    }
+
+    fn approval_consideration_decrement(address: &T::AccountId) -> DispatchResult {
+        ConsiderationHandler::<
+            T::AccountId,
+            ApprovalConsiderations<T, I>,
+            T::DeviceConsideration,
+            ProposalOf<T, I>,
+        >::decrement(address)
+    }
+
+    fn recovery_consideration_increment(address: &T::AccountId) -> DispatchResult {
+        ConsiderationHandler::<
+            T::AccountId,
+            RecoveryConsiderations<T, I>,
+            T::DeviceConsideration,
+            RecoveryOf<T, I>,
+        >::increment(address)
+    }
+
+    fn recovery_consideration_decrement(address: &T::AccountId) -> DispatchResult {
+        ConsiderationHandler::<
+            T::AccountId,
+            RecoveryConsiderations<T, I>,
+            T::DeviceConsideration,
+            RecoveryOf<T, I>,
+        >::decrement(address)
+    }
+
+    /// Removes a previously existing session, releasing its deposit.
     fn try_remove_session_key(session_key: &T::AccountId) -> DispatchResult {
         Self::cancel_scheduled_session_key_removal(session_key);
 
-        if let Some((address, _)) = &Self::pass_account_from_session_key(session_key) {
+        if let Some((address, filter, _)) = Self::pass_account_from_session_key(session_key) {
+            let address = &address;
             SessionKeys::<T, I>::remove(session_key);
-            AccountSessionsCount::<T, I>::insert(
-                address,
-                AccountSessionsCount::<T, I>::get(address).saturating_sub(1),
+            AccountSessionKeys::<T, I>::remove(address, session_key);
+            let _ = PeriodSpends::<T, I>::clear_prefix(
+                (address, Spender::SessionKey(session_key.clone())),
+                T::MaxFilteredAssets::get(),
+                None,
             );
+            let sessions = AccountSessionsCount::<T, I>::get(address).saturating_sub(1);
+            AccountSessionsCount::<T, I>::insert(address, sessions);
+            // Sessions created before they took a deposit hold none, so deposits are only
+            // released once there are more of them than sessions left.
+            let deposits = SessionKeyConsiderations::<T, I>::get(address).map_or(0, |(_, n)| n);
+            if deposits > sessions {
+                ConsiderationHandler::<
+                    T::AccountId,
+                    SessionKeyConsiderations<T, I>,
+                    T::SessionKeyConsideration,
+                    SessionOf<T, I>,
+                >::decrement(address)?;
+            }
 
             Self::deposit_event(Event::<T, I>::SessionRemoved {
                 session_key: session_key.clone(),
+                filter,
             })
         }
 
@@ -583,10 +2028,13 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         sp_core::blake2_256(&("remove_session_key", session_key).encode())
     }
 
+    /// Schedules the removal of a session key after `duration` (capped at
+    /// `MaxSessionDuration`), replacing any previously scheduled removal. Returns the last block
+    /// the session is valid at.
     fn schedule_next_removal(
         session_key: &T::AccountId,
         duration: Option<types::BlockNumberFor<T, I>>,
-    ) -> DispatchResult {
+    ) -> Result<types::BlockNumberFor<T, I>, DispatchError> {
         Self::cancel_scheduled_session_key_removal(session_key);
 
         let duration = duration
@@ -606,7 +2054,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             Bounded::Inline(BoundedVec::truncate_from(call.encode())),
         )?;
 
-        Ok(())
+        Ok(T::BlockNumberProvider::current_block_number().saturating_add(duration))
     }
 
     /// Infallibly cancels an already scheduled session key removal
@@ -0,0 +1,506 @@
+//! Storage migrations of the pallet.
+//!
+//! Every migration is a multi-block one, to run through `pallet-migrations` in this order:
+//!
+//! - v0 to v1 ([`MigrateV0ToV1`]): binds pass accounts to their [`HashedUserId`], instead of
+//!   deriving their addresses from the user id on every access. Once migrated, accounts are
+//!   found through [`UserIdAddresses`], so the runtime can switch to a newer version of its
+//!   `AddressGenerator` without breaking them.
+//! - v1 to v2 ([`MigrateV1ToV2`]): adds spend budgets to the spend limits of device filters.
+//! - v2 to v3 ([`MigrateV2ToV3`]): records the device that created each session key, so its
+//!   spends are charged to that device's budgets. Also indexes the session keys created before
+//!   [`AccountSessionKeys`] existed, so they're removed along with their account.
+
+use super::*;
+use frame_support::{
+    migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+    weights::{Weight, WeightMeter},
+};
+
+const LOG_TARGET: &str = "runtime::pass::migration";
+
+/// Identifies the multi-block migrations of this pallet.
+const PALLET_MIGRATIONS_ID: &[u8; 14] = b"fc-pallet-pass";
+
+/// Migrate from v0 to v1 in as many blocks as needed, binding pass accounts to their
+/// [`HashedUserId`].
+///
+/// Before v1, the only record of the [`HashedUserId`] of an account is the one its registrar
+/// kept in [`RegisteredAccounts`]. Each of them is bound to the account that version `0` of the
+/// `AddressGenerator` derives for it, if there's one, and the registrar is recorded in
+/// [`AccountRegistrars`]. Accounts without such a record can't be bound, and keep being found
+/// with version `0` of the `AddressGenerator`.
+///
+/// Runs through `pallet-migrations`, as part of its `Migrations`. Does nothing unless the
+/// on-chain version is 0.
+pub struct MigrateV0ToV1<T, I = ()>(core::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> MigrateV0ToV1<T, I> {
+    /// The weight of binding a single registered account.
+    pub(crate) fn step_weight() -> Weight {
+        T::DbWeight::get()
+            .reads_writes(4, 3)
+            .saturating_add(Weight::from_parts(
+                0,
+                (<(T::AccountId, HashedUserId)>::max_encoded_len() as u64)
+                    .saturating_mul(3)
+                    .saturating_add(DeviceOf::<T, I>::max_encoded_len() as u64),
+            ))
+    }
+
+    /// Binds the registered account after `cursor`, returning its registrar and user id as the
+    /// cursor for the next one, or `None` once every registered account was visited.
+    fn migrate_next(
+        cursor: Option<(T::AccountId, HashedUserId)>,
+    ) -> Option<(T::AccountId, HashedUserId)> {
+        let mut registered = match cursor {
+            Some((registrar, user)) => RegisteredAccounts::<T, I>::iter_keys_from(
+                RegisteredAccounts::<T, I>::hashed_key_for(registrar, user),
+            ),
+            None => RegisteredAccounts::<T, I>::iter_keys(),
+        };
+        let (registrar, user) = registered.next()?;
+
+        let address = T::AddressGenerator::generate_address_at(0, user);
+        if Devices::<T, I>::contains_prefix(&address)
+            && !UserIdAddresses::<T, I>::contains_key(user)
+            && !AccountUserIds::<T, I>::contains_key(&address)
+        {
+            UserIdAddresses::<T, I>::insert(user, &address);
+            AccountUserIds::<T, I>::insert(&address, user);
+            AccountRegistrars::<T, I>::insert(&address, (registrar.clone(), user));
+        }
+        Some((registrar, user))
+    }
+}
+
+impl<T: Config<I>, I: 'static> SteppedMigration for MigrateV0ToV1<T, I> {
+    type Cursor = (T::AccountId, HashedUserId);
+    type Identifier = MigrationId<14>;
+
+    fn id() -> Self::Identifier {
+        MigrationId {
+            pallet_id: *PALLET_MIGRATIONS_ID,
+            version_from: 0,
+            version_to: 1,
+        }
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        if Pallet::<T, I>::on_chain_storage_version() != 0 {
+            return Ok(None);
+        }
+
+        let required = Self::step_weight();
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        while meter.try_consume(required).is_ok() {
+            cursor = Self::migrate_next(cursor);
+            if cursor.is_none() {
+                StorageVersion::new(1).put::<Pallet<T, I>>();
+                log::info!(target: LOG_TARGET, "Migration to v1 complete");
+                break;
+            }
+        }
+
+        Ok(cursor)
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+        let bindable = RegisteredAccounts::<T, I>::iter_keys()
+            .filter(|(_, user)| {
+                Devices::<T, I>::contains_prefix(T::AddressGenerator::generate_address_at(0, *user))
+            })
+            .count() as u32;
+        Ok(bindable.encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+        let bindable = u32::decode(&mut &state[..]).map_err(|_| {
+            sp_runtime::TryRuntimeError::Other("Failed to decode pre-upgrade state")
+        })?;
+
+        for (registrar, user) in RegisteredAccounts::<T, I>::iter_keys() {
+            let address = T::AddressGenerator::generate_address_at(0, user);
+            if !Devices::<T, I>::contains_prefix(&address) {
+                continue;
+            }
+            frame_support::ensure!(
+                UserIdAddresses::<T, I>::get(user) == Some(address.clone())
+                    && AccountUserIds::<T, I>::get(&address) == Some(user),
+                sp_runtime::TryRuntimeError::Other("Pass account not bound to its user id")
+            );
+            frame_support::ensure!(
+                AccountRegistrars::<T, I>::get(&address) == Some((registrar, user)),
+                sp_runtime::TryRuntimeError::Other("Pass account registrar not recorded")
+            );
+        }
+        frame_support::ensure!(
+            AccountUserIds::<T, I>::iter_keys().count() as u32 == bindable,
+            sp_runtime::TryRuntimeError::Other("Bound accounts count mismatch after migration")
+        );
+        frame_support::ensure!(
+            Pallet::<T, I>::on_chain_storage_version() == 1,
+            sp_runtime::TryRuntimeError::Other("Storage version not updated")
+        );
+
+        Ok(())
+    }
+}
+
+/// The layouts of the storage items changed by [`MigrateV1ToV2`], as they were in v1.
+pub mod v1 {
+    use super::*;
+    use frame_support::storage_alias;
+    use sp_runtime::BoundedBTreeSet;
+
+    /// A spend limit before budgets were added.
+    #[derive(Encode, Decode)]
+    pub struct AssetSpendLimit<AssetId, Balance> {
+        pub asset: AssetId,
+        pub max_amount: Balance,
+    }
+
+    /// A device filter before budgets and scoped filters were added.
+    #[derive(Encode, Decode)]
+    pub enum DeviceFilter<AssetId: Ord, Balance, MaxCalls: Get<u32>, MaxAssets: Get<u32>> {
+        Admin,
+        Pallets(BoundedBTreeSet<u8, MaxCalls>),
+        Calls(BoundedBTreeSet<(u8, u8), MaxCalls>),
+        Spend(BoundedVec<AssetSpendLimit<AssetId, Balance>, MaxAssets>),
+    }
+
+    pub type DeviceFilterOf<T, I> = DeviceFilter<
+        SpendAssetOf<T, I>,
+        SpendBalanceOf<T, I>,
+        <T as Config<I>>::MaxFilteredCalls,
+        <T as Config<I>>::MaxFilteredAssets,
+    >;
+
+    impl<AssetId, Balance, MaxCalls, MaxAssets> DeviceFilter<AssetId, Balance, MaxCalls, MaxAssets>
+    where
+        AssetId: Ord + Clone + Debug + Eq,
+        Balance: Clone + Debug + Eq,
+        MaxCalls: Get<u32>,
+        MaxAssets: Get<u32>,
+    {
+        /// The same filter, with no spend budgets.
+        pub fn migrate(self) -> crate::DeviceFilter<AssetId, Balance, MaxCalls, MaxAssets> {
+            match self {
+                Self::Admin => crate::DeviceFilter::Admin,
+                Self::Pallets(pallets) => crate::DeviceFilter::Pallets(pallets),
+                Self::Calls(calls) => crate::DeviceFilter::Calls(calls),
+                Self::Spend(limits) => crate::DeviceFilter::Spend(BoundedVec::truncate_from(
+                    limits
+                        .into_iter()
+                        .map(|limit| crate::AssetSpendLimit {
+                            asset: limit.asset,
+                            max_amount: limit.max_amount,
+                            budget: None,
+                        })
+                        .collect(),
+                )),
+            }
+        }
+    }
+
+    #[storage_alias]
+    pub type DeviceFilters<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        DeviceId,
+        DeviceFilterOf<T, I>,
+    >;
+
+    #[storage_alias]
+    pub type SessionKeys<T: Config<I>, I: 'static> = CountedStorageMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        (
+            <T as frame_system::Config>::AccountId,
+            types::BlockNumberFor<T, I>,
+            DeviceFilterOf<T, I>,
+        ),
+    >;
+}
+
+/// The layouts of the storage items changed by [`MigrateV2ToV3`], as they were in v2.
+pub mod v2 {
+    use super::*;
+    use frame_support::storage_alias;
+
+    #[storage_alias]
+    pub type SessionKeys<T: Config<I>, I: 'static> = CountedStorageMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        (
+            <T as frame_system::Config>::AccountId,
+            types::BlockNumberFor<T, I>,
+            DeviceFilterOf<T, I>,
+        ),
+    >;
+}
+
+/// The progress of [`MigrateV1ToV2`]: the last migrated key of each storage item.
+#[derive(Encode, Decode, MaxEncodedLen)]
+pub enum FiltersCursor<AccountId> {
+    DeviceFilters(AccountId, DeviceId),
+    SessionKeys(AccountId),
+}
+
+/// Migrate from v1 to v2 in as many blocks as needed, adding an empty spend budget to the
+/// spend limits of every device and session key filter.
+///
+/// Runs through `pallet-migrations`, as part of its `Migrations`. Does nothing unless the
+/// on-chain version is 1.
+pub struct MigrateV1ToV2<T, I = ()>(core::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> MigrateV1ToV2<T, I> {
+    /// The weight of migrating a single filter.
+    pub(crate) fn step_weight() -> Weight {
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(Weight::from_parts(
+                0,
+                SessionOf::<T, I>::max_encoded_len() as u64,
+            ))
+    }
+
+    /// Migrates the filter after `cursor`, returning the cursor for the next one, or `None`
+    /// once every filter was migrated.
+    fn migrate_next(
+        cursor: Option<FiltersCursor<T::AccountId>>,
+    ) -> Option<FiltersCursor<T::AccountId>> {
+        let next_device_filter = match &cursor {
+            None => Some(v1::DeviceFilters::<T, I>::iter()),
+            Some(FiltersCursor::DeviceFilters(address, device_id)) => {
+                Some(v1::DeviceFilters::<T, I>::iter_from(v1::DeviceFilters::<
+                    T,
+                    I,
+                >::hashed_key_for(
+                    address, device_id
+                )))
+            }
+            Some(FiltersCursor::SessionKeys(_)) => None,
+        }
+        .and_then(|mut iter| iter.next());
+        if let Some((address, device_id, filter)) = next_device_filter {
+            DeviceFilters::<T, I>::insert(&address, device_id, filter.migrate());
+            return Some(FiltersCursor::DeviceFilters(address, device_id));
+        }
+
+        let mut sessions = match cursor {
+            Some(FiltersCursor::SessionKeys(session_key)) => v1::SessionKeys::<T, I>::iter_from(
+                v1::SessionKeys::<T, I>::hashed_key_for(session_key),
+            ),
+            _ => v1::SessionKeys::<T, I>::iter(),
+        };
+        let (session_key, (address, until, filter)) = sessions.next()?;
+        v2::SessionKeys::<T, I>::insert(&session_key, (address, until, filter.migrate()));
+        Some(FiltersCursor::SessionKeys(session_key))
+    }
+}
+
+impl<T: Config<I>, I: 'static> SteppedMigration for MigrateV1ToV2<T, I> {
+    type Cursor = FiltersCursor<T::AccountId>;
+    type Identifier = MigrationId<14>;
+
+    fn id() -> Self::Identifier {
+        MigrationId {
+            pallet_id: *PALLET_MIGRATIONS_ID,
+            version_from: 1,
+            version_to: 2,
+        }
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        if Pallet::<T, I>::on_chain_storage_version() != 1 {
+            return Ok(None);
+        }
+
+        let required = Self::step_weight();
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        while meter.try_consume(required).is_ok() {
+            cursor = Self::migrate_next(cursor);
+            if cursor.is_none() {
+                StorageVersion::new(2).put::<Pallet<T, I>>();
+                log::info!(target: LOG_TARGET, "Migration to v2 complete");
+                break;
+            }
+        }
+
+        Ok(cursor)
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+        let filters = v1::DeviceFilters::<T, I>::iter_keys().count() as u32;
+        let sessions = v1::SessionKeys::<T, I>::iter_keys().count() as u32;
+        Ok((filters, sessions).encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+        let (filters, sessions) = <(u32, u32)>::decode(&mut &state[..]).map_err(|_| {
+            sp_runtime::TryRuntimeError::Other("Failed to decode pre-upgrade state")
+        })?;
+
+        frame_support::ensure!(
+            DeviceFilters::<T, I>::iter_values().count() as u32 == filters,
+            sp_runtime::TryRuntimeError::Other("Device filters lost in the migration")
+        );
+        frame_support::ensure!(
+            v2::SessionKeys::<T, I>::iter_values().count() as u32 == sessions,
+            sp_runtime::TryRuntimeError::Other("Session keys lost in the migration")
+        );
+        frame_support::ensure!(
+            Pallet::<T, I>::on_chain_storage_version() == 2,
+            sp_runtime::TryRuntimeError::Other("Storage version not updated")
+        );
+
+        Ok(())
+    }
+}
+
+/// Migrate from v2 to v3 in as many blocks as needed, recording a creator device for every
+/// session key, and indexing it in [`AccountSessionKeys`].
+///
+/// The device that created a session key wasn't tracked before v3, so sessions are attributed
+/// to the first device of their account with an `Admin` filter, as only those don't spend from
+/// budgets, or otherwise to the first device of their account. Runs through `pallet-migrations`,
+/// as part of its `Migrations`. Does nothing unless the on-chain version is 2.
+pub struct MigrateV2ToV3<T, I = ()>(core::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> MigrateV2ToV3<T, I> {
+    /// The weight of migrating a single session key, reading every device filter of its account
+    /// and indexing the session key under it.
+    pub(crate) fn step_weight() -> Weight {
+        let max_devices = T::MaxDevicesPerAccount::get() as u64;
+        T::DbWeight::get()
+            .reads_writes(1 + max_devices, 2)
+            .saturating_add(Weight::from_parts(
+                0,
+                (SessionOf::<T, I>::max_encoded_len() as u64).saturating_add(
+                    max_devices.saturating_mul(DeviceFilterOf::<T, I>::max_encoded_len() as u64),
+                ),
+            ))
+    }
+
+    /// The device a session key of `address` is attributed to.
+    fn creator_of(address: &T::AccountId) -> DeviceId {
+        let mut first_device = None;
+        for (device_id, filter) in DeviceFilters::<T, I>::iter_prefix(address) {
+            if matches!(filter, DeviceFilter::Admin) {
+                return device_id;
+            }
+            first_device.get_or_insert(device_id);
+        }
+        first_device.unwrap_or_default()
+    }
+
+    /// Migrates the session key after `cursor`, returning it as the cursor for the next one, or
+    /// `None` once every session key was migrated.
+    fn migrate_next(cursor: Option<T::AccountId>) -> Option<T::AccountId> {
+        let mut sessions = match cursor {
+            Some(session_key) => v2::SessionKeys::<T, I>::iter_from(
+                v2::SessionKeys::<T, I>::hashed_key_for(session_key),
+            ),
+            None => v2::SessionKeys::<T, I>::iter(),
+        };
+        let (session_key, (address, until, filter)) = sessions.next()?;
+        let creator = Self::creator_of(&address);
+        // Sessions created before they were indexed by account are indexed too, so they're
+        // found when killing the account.
+        AccountSessionKeys::<T, I>::insert(&address, &session_key, ());
+        SessionKeys::<T, I>::insert(&session_key, (address, until, filter, creator));
+        Some(session_key)
+    }
+}
+
+impl<T: Config<I>, I: 'static> SteppedMigration for MigrateV2ToV3<T, I> {
+    type Cursor = T::AccountId;
+    type Identifier = MigrationId<14>;
+
+    fn id() -> Self::Identifier {
+        MigrationId {
+            pallet_id: *PALLET_MIGRATIONS_ID,
+            version_from: 2,
+            version_to: 3,
+        }
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        if Pallet::<T, I>::on_chain_storage_version() != 2 {
+            return Ok(None);
+        }
+
+        let required = Self::step_weight();
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        while meter.try_consume(required).is_ok() {
+            cursor = Self::migrate_next(cursor);
+            if cursor.is_none() {
+                StorageVersion::new(3).put::<Pallet<T, I>>();
+                log::info!(target: LOG_TARGET, "Migration to v3 complete");
+                break;
+            }
+        }
+
+        Ok(cursor)
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+        Ok((v2::SessionKeys::<T, I>::iter_keys().count() as u32).encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+        let sessions = u32::decode(&mut &state[..]).map_err(|_| {
+            sp_runtime::TryRuntimeError::Other("Failed to decode pre-upgrade state")
+        })?;
+
+        frame_support::ensure!(
+            SessionKeys::<T, I>::iter_values().count() as u32 == sessions,
+            sp_runtime::TryRuntimeError::Other("Session keys lost in the migration")
+        );
+        frame_support::ensure!(
+            SessionKeys::<T, I>::count() == sessions,
+            sp_runtime::TryRuntimeError::Other("Session keys count mismatch after migration")
+        );
+        frame_support::ensure!(
+            SessionKeys::<T, I>::iter().all(|(session_key, (address, ..))| {
+                AccountSessionKeys::<T, I>::contains_key(address, session_key)
+            }),
+            sp_runtime::TryRuntimeError::Other("Session keys not indexed by account")
+        );
+        frame_support::ensure!(
+            Pallet::<T, I>::on_chain_storage_version() == 3,
+            sp_runtime::TryRuntimeError::Other("Storage version not updated")
+        );
+
+        Ok(())
+    }
+}
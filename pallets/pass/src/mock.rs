@@ -1,9 +1,13 @@
 //! Test environment for pallet pass.
 
-use crate::{self as pallet_pass, Config, FirstItemIsFree, HoldReason, PassAuthenticate};
+use crate::{
+    self as pallet_pass, CallInspector, Config, FirstItemIsFree, HoldReason, MakeAccountTank,
+    PassAuthenticate, SpendMatcher,
+};
 pub use authenticators::*;
 use codec::{Decode, Encode, MaxEncodedLen};
 use fc_traits_authn::{composite_authenticators, util::AuthorityFromPalletId, Challenger};
+use fc_traits_gas_tank::{GasBurner, MakeTank};
 use frame_support::traits::fungible::HoldConsideration;
 use frame_support::traits::{Consideration, Footprint, LinearStoragePrice};
 use frame_support::weights::FixedFee;
@@ -11,7 +15,7 @@ use frame_support::{
     derive_impl, parameter_types,
     traits::{ConstU32, ConstU64, EitherOf, EqualPrivilegeOnly, OnInitialize},
     weights::Weight,
-    DebugNoBound, EqNoBound, PalletId,
+    Blake2_128Concat, DebugNoBound, EqNoBound, PalletId,
 };
 use frame_system::mocking::MockUncheckedExtrinsic;
 use frame_system::{EnsureRoot, EnsureRootWithSuccess, EnsureSigned};
@@ -20,14 +24,17 @@ use sp_core::{blake2_256, H256};
 use sp_io::TestExternalities;
 use sp_runtime::{
     traits::{IdentifyAccount, IdentityLookup, Verify},
-    DispatchError, MultiSignature,
+    DispatchError, DispatchResult, MultiSignature,
 };
 
 mod authenticators;
 
 pub type TxExtensions = (
     PassAuthenticate<Test>,
-    pallet_transaction_payment::ChargeTransactionPayment<Test>,
+    fc_pallet_gas_transaction_payment::ChargeTransactionPayment<
+        Test,
+        pallet_transaction_payment::ChargeTransactionPayment<Test>,
+    >,
 );
 pub type CheckedExtrinsic =
     sp_runtime::generic::CheckedExtrinsic<AccountId, RuntimeCall, TxExtensions>;
@@ -61,6 +68,10 @@ mod runtime {
     pub type Scheduler = pallet_scheduler;
     #[runtime::pallet_index(2)]
     pub type TransactionPayment = pallet_transaction_payment;
+    #[runtime::pallet_index(3)]
+    pub type Utility = pallet_utility;
+    #[runtime::pallet_index(4)]
+    pub type GasTransactionPayment = fc_pallet_gas_transaction_payment;
     #[runtime::pallet_index(10)]
     pub type Balances = pallet_balances;
     #[runtime::pallet_index(11)]
@@ -92,6 +103,68 @@ impl pallet_transaction_payment::Config for Test {
     type LengthToFee = FixedFee<0, Balance>;
 }
 
+#[frame_support::storage_alias]
+pub type GasTanks = StorageMap<Pass, Blake2_128Concat, AccountId, (Weight, Option<u64>)>;
+
+/// A gas tank per account, holding the gas left in it and its periodicity.
+pub struct AccountGasTanks;
+
+impl GasBurner for AccountGasTanks {
+    type AccountId = AccountId;
+    type Gas = Weight;
+
+    fn check_available_gas(who: &AccountId, estimated: &Weight) -> Option<Weight> {
+        GasTanks::get(who).and_then(|(remaining, _)| remaining.checked_sub(estimated))
+    }
+
+    fn burn_gas(who: &AccountId, expected: &Weight, used: &Weight) -> Weight {
+        GasTanks::mutate(who, |tank| {
+            let (remaining, _) = tank.get_or_insert_default();
+            *remaining = remaining.saturating_sub(*used).max(*expected);
+            *remaining
+        })
+    }
+}
+
+impl MakeTank for AccountGasTanks {
+    type TankId = AccountId;
+    type Gas = Weight;
+    type BlockNumber = u64;
+
+    fn make_tank(
+        id: &AccountId,
+        capacity: Option<Weight>,
+        periodicity: Option<u64>,
+    ) -> DispatchResult {
+        GasTanks::insert(id, (capacity.unwrap_or(Weight::MAX), periodicity));
+        Ok(())
+    }
+
+    fn remove_tank(id: &AccountId) -> DispatchResult {
+        GasTanks::remove(id);
+        Ok(())
+    }
+}
+
+parameter_types! {
+    pub GasTankCapacity: Option<Weight> = Some(Weight::from_parts(1_000_000_000, 0));
+    pub GasTankPeriodicity: Option<u64> = Some(100);
+}
+
+impl fc_pallet_gas_transaction_payment::Config for Test {
+    type WeightInfo = ();
+    type GasTank = AccountGasTanks;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = benchmarks::BenchmarkHelper;
+}
+
+impl pallet_utility::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = ();
+}
+
 parameter_types! {
     pub MaxScheduledPerBlock: u32 = 100;
     pub MaximumWeight: Weight = Weight::MAX;
@@ -120,9 +193,10 @@ parameter_types! {
 }
 
 composite_authenticators! {
+    #[generate_tests]
     pub Pass<AuthorityFromPalletId<PassPalletId>> {
-        authenticator_a::Authenticator,
-        AuthenticatorB::<LastThreeBlocksChallenger>,
+        #[index = 0] authenticator_a::Authenticator,
+        #[index = 1] AuthenticatorB::<LastThreeBlocksChallenger>,
     };
 }
 
@@ -159,6 +233,40 @@ where
     }
 }
 
+/// Treats native balance transfers as spends.
+pub struct TransferMatcher;
+
+impl SpendMatcher<RuntimeCall> for TransferMatcher {
+    type AssetId = ();
+    type Balance = u128;
+
+    fn spending_amount(call: &RuntimeCall) -> Option<((), u128)> {
+        match call {
+            RuntimeCall::Balances(
+                pallet_balances::Call::transfer_allow_death { value, .. }
+                | pallet_balances::Call::transfer_keep_alive { value, .. },
+            ) => Some(((), (*value).into())),
+            _ => None,
+        }
+    }
+}
+
+/// Expands the batches of `pallet-utility`.
+pub struct BatchInspector;
+
+impl CallInspector<RuntimeCall> for BatchInspector {
+    fn inner_calls(call: &RuntimeCall) -> Option<Vec<&RuntimeCall>> {
+        match call {
+            RuntimeCall::Utility(
+                pallet_utility::Call::batch { calls }
+                | pallet_utility::Call::batch_all { calls }
+                | pallet_utility::Call::force_batch { calls },
+            ) => Some(calls.iter().collect()),
+            _ => None,
+        }
+    }
+}
+
 pub type RegistrationStoragePrice = LinearStoragePrice<ExistentialDeposit, ConstU64<1>, Balance>;
 pub type ItemStoragePrice = LinearStoragePrice<ConstU64<100>, ConstU64<1>, Balance>;
 
@@ -171,11 +279,15 @@ impl Config for Test {
         // Anyone else pays
         EnsureSigned<Self::AccountId>,
     >;
+    type GuardianOrigin = EnsureSigned<Self::AccountId>;
+    type RegistrarsOrigin = EnsureRoot<Self::AccountId>;
     type AddressGenerator = ();
     type Balances = Balances;
     type Authenticator = PassAuthenticator;
     type Scheduler = Scheduler;
     type BlockNumberProvider = System;
+    type DevicePriority = ();
+    type GasTank = MakeAccountTank<AccountGasTanks, GasTankCapacity, GasTankPeriodicity>;
     type RegistrarConsideration = RootDoesNotPayConsideration<
         HoldConsideration<AccountId, Balances, HoldAccountRegistration, RegistrationStoragePrice>,
     >;
@@ -184,14 +296,21 @@ impl Config for Test {
     >;
     type SessionKeyConsideration =
         FirstItemIsFree<HoldConsideration<AccountId, Balances, HoldSessionKeys, ItemStoragePrice>>;
-    type SpendMatcher = ();
+    type SpendMatcher = TransferMatcher;
     type CallMatcher = crate::filter::ScaleCallMatcher;
+    type CallInspector = BatchInspector;
     type PalletId = PassPalletId;
     type MaxDevicesPerAccount = ConstU32<2>;
+    type MaxDeviceLabelLen = ConstU32<32>;
     type MaxSessionsPerAccount = ConstU32<2>;
     type MaxSessionDuration = ConstU64<10>;
     type MaxFilteredCalls = ConstU32<10>;
     type MaxFilteredAssets = ConstU32<5>;
+    type MaxGuardians = ConstU32<3>;
+    type MinRecoveryDelay = ConstU64<2>;
+    type MaxPendingProposals = ConstU32<2>;
+    type MaxDeviceTransactions = ConstU32<5>;
+    type DeviceRateLimitPeriod = ConstU32<10>;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelper = benchmarks::BenchmarkHelper;
 }
@@ -222,6 +341,20 @@ mod benchmarks {
         }
     }
 
+    impl fc_pallet_gas_transaction_payment::BenchmarkHelper<Test> for BenchmarkHelper {
+        type Ext = pallet_transaction_payment::ChargeTransactionPayment<Test>;
+
+        fn ext() -> fc_pallet_gas_transaction_payment::ChargeTransactionPayment<Test, Self::Ext> {
+            fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+                pallet_transaction_payment::ChargeTransactionPayment::from(0),
+            )
+        }
+
+        fn setup_account(who: &AccountId, gas: Weight) -> DispatchResult {
+            AccountGasTanks::make_tank(who, Some(gas), None)
+        }
+    }
+
     impl pallet_pass::BenchmarkHelper<Test> for BenchmarkHelper {
         fn device_attestation(xtc: &impl ExtrinsicContext) -> DeviceAttestationOf<Test, ()> {
             PassDeviceAttestation::AuthenticatorB(authenticator_b::DeviceAttestation {
@@ -246,6 +379,20 @@ mod benchmarks {
                 .sign(&device_id),
             )
         }
+
+        fn nested_call(calls: Vec<RuntimeCall>) -> RuntimeCall {
+            pallet_utility::Call::batch { calls }.into()
+        }
+
+        fn spend_call() -> Option<RuntimeCall> {
+            Some(
+                pallet_balances::Call::transfer_keep_alive {
+                    dest: RootAccount::get(),
+                    value: 1,
+                }
+                .into(),
+            )
+        }
     }
 }
 
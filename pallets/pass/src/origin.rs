@@ -0,0 +1,143 @@
+//! Ready-made [`Config::RegisterOrigin`] implementations that gate the creation of pass accounts
+//! behind memberships.
+
+use crate::{AddressGenerator, Config, RegisteredAccounts};
+use core::marker::PhantomData;
+use fc_traits_authn::HashedUserId;
+use fc_traits_memberships::Inspect;
+#[cfg(feature = "runtime-benchmarks")]
+use fc_traits_memberships::{InspectEnumerable, Manager};
+use frame_support::{
+    storage::StorageDoubleMap as _,
+    traits::{EitherOf, EnsureOrigin, EnsureOriginWithArg, Get, OriginTrait},
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Convert;
+#[cfg(feature = "runtime-benchmarks")]
+use sp_runtime::{traits::TrailingZeroInput, DispatchError, DispatchResult};
+
+/// The memberships the origins of this module check. When benchmarking, they must be able to
+/// make an account a member of a group, so the origins can succeed.
+#[cfg(not(feature = "runtime-benchmarks"))]
+pub trait BenchmarkMemberships<AccountId>: Inspect<AccountId> {}
+
+#[cfg(not(feature = "runtime-benchmarks"))]
+impl<AccountId, M: Inspect<AccountId>> BenchmarkMemberships<AccountId> for M {}
+
+/// The memberships the origins of this module check. When benchmarking, they must be able to
+/// make an account a member of a group, so the origins can succeed.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkMemberships<AccountId>: Inspect<AccountId> {
+    /// An account derived from `seed`, to make a member of a group.
+    fn member_account(seed: &[u8]) -> Option<AccountId>;
+    /// Makes `who` a member of `group`.
+    fn add_member(group: &Self::Group, who: &AccountId) -> DispatchResult;
+}
+
+/// Assigns `who` the first membership available to the group.
+#[cfg(feature = "runtime-benchmarks")]
+impl<AccountId, M> BenchmarkMemberships<AccountId> for M
+where
+    AccountId: codec::Decode,
+    M: Manager<AccountId> + InspectEnumerable<AccountId>,
+{
+    fn member_account(seed: &[u8]) -> Option<AccountId> {
+        AccountId::decode(&mut TrailingZeroInput::new(seed)).ok()
+    }
+
+    fn add_member(group: &Self::Group, who: &AccountId) -> DispatchResult {
+        let membership = M::group_available_memberships(group)
+            .next()
+            .ok_or(DispatchError::Other("no memberships available"))?;
+        M::assign(group, &membership, who)
+    }
+}
+
+/// Allows a signed account that holds a membership in the `Group` to register a pass account,
+/// paying for the registration deposit itself.
+///
+/// The [`HashedUserId`] isn't tied to the member, and the pass account itself is derived from the
+/// [`HashedUserId`], so the member is recorded as its registrar. To keep members from squatting
+/// user ids, each member registers a single pass account: members that are already the registrar
+/// of one (see [`RegisteredAccounts`]) are rejected until that account is killed.
+pub struct EnsureMemberOf<T, I, Memberships, Group>(PhantomData<(T, I, Memberships, Group)>);
+
+impl<O, T, I, Memberships, Group> EnsureOriginWithArg<O, HashedUserId>
+    for EnsureMemberOf<T, I, Memberships, Group>
+where
+    O: OriginTrait<AccountId = T::AccountId> + Clone + Into<Result<RawOrigin<T::AccountId>, O>>,
+    T: Config<I>,
+    I: 'static,
+    Memberships: BenchmarkMemberships<T::AccountId>,
+    Group: Get<Memberships::Group>,
+{
+    type Success = T::AccountId;
+
+    fn try_origin(o: O, _: &HashedUserId) -> Result<Self::Success, O> {
+        match o.clone().into() {
+            Ok(RawOrigin::Signed(who))
+                if Memberships::is_member_of(&Group::get(), &who)
+                    && !RegisteredAccounts::<T, I>::contains_prefix(&who) =>
+            {
+                Ok(who)
+            }
+            _ => Err(o),
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin(user_id: &HashedUserId) -> Result<O, ()> {
+        let who = Memberships::member_account(user_id).ok_or(())?;
+        Memberships::add_member(&Group::get(), &who).map_err(|_| ())?;
+        Ok(O::signed(who))
+    }
+}
+
+/// Allows a community origin to register pass accounts on behalf of its members, charging the
+/// registration deposit to the community account.
+///
+/// `CommunityOrigin` resolves the group of the community, and `CommunityAccount` the account that
+/// acts as the registrar. Since the pass account doesn't exist yet, the membership is checked
+/// against the address the `HashedUserId` is going to be registered at.
+pub struct EnsureCommunityFor<T, I, CommunityOrigin, Memberships, CommunityAccount>(
+    PhantomData<(T, I, CommunityOrigin, Memberships, CommunityAccount)>,
+);
+
+impl<O, T, I, CommunityOrigin, Memberships, CommunityAccount> EnsureOriginWithArg<O, HashedUserId>
+    for EnsureCommunityFor<T, I, CommunityOrigin, Memberships, CommunityAccount>
+where
+    O: Clone,
+    T: Config<I>,
+    I: 'static,
+    CommunityOrigin: EnsureOrigin<O, Success = Memberships::Group>,
+    Memberships: BenchmarkMemberships<T::AccountId>,
+    CommunityAccount: Convert<Memberships::Group, T::AccountId>,
+{
+    type Success = T::AccountId;
+
+    fn try_origin(o: O, user_id: &HashedUserId) -> Result<Self::Success, O> {
+        let group = CommunityOrigin::try_origin(o.clone())?;
+        let address = T::AddressGenerator::generate_address(*user_id);
+        if Memberships::is_member_of(&group, &address) {
+            Ok(CommunityAccount::convert(group))
+        } else {
+            Err(o)
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin(user_id: &HashedUserId) -> Result<O, ()> {
+        let origin = CommunityOrigin::try_successful_origin()?;
+        let group = CommunityOrigin::try_origin(origin.clone()).map_err(|_| ())?;
+        Memberships::add_member(&group, &T::AddressGenerator::generate_address(*user_id))
+            .map_err(|_| ())?;
+        Ok(origin)
+    }
+}
+
+/// Allows both the self-registration of the members of `Group`, and communities registering
+/// pass accounts on behalf of their members.
+pub type EnsureMembership<T, I, Memberships, Group, CommunityOrigin, CommunityAccount> = EitherOf<
+    EnsureMemberOf<T, I, Memberships, Group>,
+    EnsureCommunityFor<T, I, CommunityOrigin, Memberships, CommunityAccount>,
+>;
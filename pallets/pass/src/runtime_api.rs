@@ -0,0 +1,34 @@
+//! Runtime API to query pass accounts, their devices and sessions.
+
+use alloc::vec::Vec;
+use codec::Codec;
+use fc_traits_authn::{DeviceId, HashedUserId};
+use sp_runtime::DispatchResult;
+
+use crate::types::{DeviceDetails, SessionDetails};
+
+sp_api::decl_runtime_apis! {
+    /// Lets front-ends inspect pass accounts without decoding raw storage.
+    pub trait PassApi<AccountId, BlockNumber, Filter, Info, Call>
+    where
+        AccountId: Codec,
+        BlockNumber: Codec,
+        Filter: Codec,
+        Info: Codec,
+        Call: Codec,
+    {
+        /// The address of the pass account registered for `user`.
+        fn address_for(user: HashedUserId) -> AccountId;
+
+        /// The devices registered for `who`, along with their filters and metadata.
+        fn devices(who: AccountId) -> Vec<DeviceDetails<Filter, Info>>;
+
+        /// The live session keys of `who`, along with their expiry and filters.
+        fn sessions(who: AccountId) -> Vec<SessionDetails<AccountId, BlockNumber, Filter>>;
+
+        /// Whether `device_id` could currently dispatch `call` on behalf of `who`, checking
+        /// its filter, spend budgets and the approval policy of the account. Returns the error
+        /// the transaction would fail with otherwise.
+        fn can_dispatch(who: AccountId, device_id: DeviceId, call: Call) -> DispatchResult;
+    }
+}
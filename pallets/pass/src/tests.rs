@@ -1,10 +1,10 @@
 //! Tests for pass pallet.
-use super::{Error, Event, PassAuthenticate};
+use super::{Error, Event, PassAuthenticate, SessionOf};
 use crate::filter::DeviceFilter;
 use crate::mock::*;
 
 use codec::{Encode, MaxEncodedLen};
-use fc_traits_authn::{Challenger, DeviceId, HashedUserId};
+use fc_traits_authn::{Challenger, DeviceId, HashedUserId, UserChallengeResponse};
 use frame_support::{
     assert_noop, assert_ok,
     dispatch::PostDispatchInfo,
@@ -187,6 +187,30 @@ fn prepare(user_id: HashedUserId) -> sp_io::TestExternalities {
     t
 }
 
+/// Runs a multi-block migration to completion, with just enough weight to migrate an item per
+/// step, returning how many steps it took. Checks its try-runtime hooks too, when enabled.
+fn run_stepped_migration<M: frame_support::migrations::SteppedMigration>(
+    item_weight: frame_support::weights::Weight,
+) -> u32 {
+    #[cfg(feature = "try-runtime")]
+    let state = M::pre_upgrade().expect("pre_upgrade checks pass");
+
+    let mut cursor = None;
+    let mut steps = 0;
+    loop {
+        let mut meter = frame_support::weights::WeightMeter::with_limit(item_weight);
+        cursor = M::step(cursor, &mut meter).expect("a step migrates an item");
+        steps += 1;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    M::post_upgrade(state).expect("post_upgrade checks pass");
+    steps
+}
+
 const DURATION: u64 = 10;
 
 mod authenticate {
@@ -765,7 +789,8 @@ mod add_session_key {
             System::assert_has_event(
                 Event::<Test>::SessionCreated {
                     session_key_hash: H256(blake2_256(&OTHER.encode())),
-                    until: DURATION,
+                    until: System::block_number() + DURATION,
+                    filter: remark_only_filter(),
                 }
                 .into(),
             );
@@ -810,6 +835,16 @@ mod add_session_key {
     #[test]
     fn max_sessions_per_account_works() {
         prepare(AccountNameA::get()).execute_with(|| {
+            // The second session key takes a deposit
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ExistentialDeposit::get()
+                    + ItemStoragePrice::convert(Footprint::from_parts(
+                        1,
+                        SessionOf::<Test>::max_encoded_len()
+                    ))
+            ));
+
             crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
             assert_ok!(Pass::add_session_key(
                 RuntimeOrigin::signed(Address::get()),
@@ -865,11 +900,191 @@ mod add_session_key {
     }
 }
 
+mod manage_session_keys {
+    use super::*;
+    use crate::{DeviceOf, SessionKeys};
+    use sp_core::{blake2_256, H256};
+
+    fn nothing_filter() -> crate::DeviceFilterOf<Test> {
+        DeviceFilter::Calls(Default::default())
+    }
+
+    /// An account with an `Admin` device, and a second device that can only remark, which
+    /// created the session key `OTHER`. The `Admin` device created the session key `SIGNER`.
+    fn setup_with_sessions() -> sp_io::TestExternalities {
+        let mut t = prepare(AccountNameA::get());
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ExistentialDeposit::get()
+                    + ItemStoragePrice::convert(Footprint::from_parts(
+                        2,
+                        DeviceOf::<Test>::max_encoded_len(),
+                    ))
+                    + ItemStoragePrice::convert(Footprint::from_parts(
+                        2,
+                        SessionOf::<Test>::max_encoded_len(),
+                    ))
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                SIGNER,
+                Some(DURATION),
+                remark_only_filter(),
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                Some(DURATION / 2),
+                remark_only_filter(),
+            ));
+            crate::AuthenticatedDevice::<Test>::kill();
+        });
+        t
+    }
+
+    #[test]
+    fn pass_account_can_revoke_its_session_keys() {
+        setup_with_sessions().execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::remove_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER
+            ));
+            System::assert_has_event(
+                Event::<Test>::SessionRemoved {
+                    session_key: OTHER,
+                    filter: remark_only_filter(),
+                }
+                .into(),
+            );
+            assert!(!SessionKeys::<Test>::contains_key(OTHER));
+
+            // Nothing is left to remove once the session would have expired
+            run_to(1 + DURATION);
+            assert!(!System::events().iter().any(|record| matches!(
+                record.event,
+                RuntimeEvent::Pass(Event::SessionRemoved {
+                    session_key: OTHER,
+                    ..
+                })
+            )));
+        })
+    }
+
+    #[test]
+    fn revoking_requires_the_creator_or_a_more_permissive_device() {
+        setup_with_sessions().execute_with(|| {
+            // Downgrade the device that created `OTHER`.
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::update_device_filter(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE,
+                nothing_filter(),
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::remove_session_key(RuntimeOrigin::signed(Address::get()), SIGNER),
+                Error::<Test>::PermissionEscalation
+            );
+            assert_noop!(
+                Pass::renew_session_key(RuntimeOrigin::signed(Address::get()), SIGNER, None),
+                Error::<Test>::PermissionEscalation
+            );
+
+            // The creator can still revoke its own session
+            assert_ok!(Pass::remove_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER
+            ));
+            assert!(!SessionKeys::<Test>::contains_key(OTHER));
+        })
+    }
+
+    #[test]
+    fn cannot_manage_session_keys_of_other_accounts() {
+        setup_with_sessions().execute_with(|| {
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                AccountNameB::get(),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: THIRD_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((AddressB::get(), THIRD_DEVICE));
+            assert_noop!(
+                Pass::remove_session_key(RuntimeOrigin::signed(AddressB::get()), OTHER),
+                Error::<Test>::SessionNotFound
+            );
+            assert_noop!(
+                Pass::renew_session_key(RuntimeOrigin::signed(AddressB::get()), OTHER, None),
+                Error::<Test>::SessionNotFound
+            );
+            assert_noop!(
+                Pass::remove_session_key(RuntimeOrigin::signed(CHARLIE), OTHER),
+                DispatchError::BadOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn renewing_extends_the_session_up_to_max_duration() {
+        setup_with_sessions().execute_with(|| {
+            // `OTHER` is valid until block 6
+            run_to(4);
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_ok!(Pass::renew_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                Some(DURATION * 2),
+            ));
+            // Capped at `MaxSessionDuration`
+            let until = 4 + DURATION;
+            System::assert_has_event(
+                Event::<Test>::SessionRenewed {
+                    session_key_hash: H256(blake2_256(&OTHER.encode())),
+                    until,
+                    filter: remark_only_filter(),
+                }
+                .into(),
+            );
+            assert_eq!(
+                SessionKeys::<Test>::get(OTHER).map(|(_, until, ..)| until),
+                Some(until)
+            );
+
+            run_to(until);
+            assert!(SessionKeys::<Test>::contains_key(OTHER));
+            run_to(until + 1);
+            assert!(!SessionKeys::<Test>::contains_key(OTHER));
+        })
+    }
+}
+
 mod dispatch {
     use super::*;
     use crate::SessionKeys;
     use frame_support::dispatch::GetDispatchInfo;
-    use sp_core::blake2_256;
     use sp_runtime::transaction_validity::InvalidTransaction;
 
     parameter_types! {
@@ -882,14 +1097,18 @@ mod dispatch {
         }.into();
     }
 
-    fn authenticate(
+    pub(super) fn authenticate(
         device_id: DeviceId,
         credentials: PassCredential,
         call: RuntimeCall,
     ) -> ApplyExtrinsicResultWithInfo<PostDispatchInfo> {
+        let address = Pass::address_for(credentials.user_id());
+        let counter = Pass::device_transactions_in_period(&address, &device_id);
         let extensions: TxExtensions = (
-            PassAuthenticate::<Test>::from(device_id, credentials),
-            pallet_transaction_payment::ChargeTransactionPayment::from(0),
+            PassAuthenticate::<Test>::from(device_id, counter, credentials),
+            fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+                pallet_transaction_payment::ChargeTransactionPayment::from(0),
+            ),
         );
 
         let xt = CheckedExtrinsic {
@@ -900,13 +1119,15 @@ mod dispatch {
         xt.apply::<Test>(&call.get_dispatch_info(), call.encoded_size())
     }
 
-    fn signed(
+    pub(super) fn signed(
         session_key: AccountId,
         call: RuntimeCall,
     ) -> ApplyExtrinsicResultWithInfo<PostDispatchInfo> {
         let extensions: TxExtensions = (
             PassAuthenticate::<Test>::default(),
-            pallet_transaction_payment::ChargeTransactionPayment::from(0),
+            fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+                pallet_transaction_payment::ChargeTransactionPayment::from(0),
+            ),
         );
 
         let xt = CheckedExtrinsic {
@@ -1031,13 +1252,14 @@ mod dispatch {
             ));
             assert_ok!(Balances::mint_into(&Address::get(), Balance::MAX));
 
-            let extrinsic_context = (
-                0u8,                                                                   // extension_version_byte
-                Call::get(),                                                           // call
-                pallet_transaction_payment::ChargeTransactionPayment::<Test>::from(0), // transaction_extensions
-                (),                                                                    // implicit
+            let extrinsic_context = crate::client::extrinsic_context(
+                0,
+                &Call::get(),
+                &fc_pallet_gas_transaction_payment::ChargeTransactionPayment::<Test, _>::new(
+                    pallet_transaction_payment::ChargeTransactionPayment::<Test>::from(0),
+                ),
             )
-                .using_encoded(blake2_256);
+            .expect("no implicit data; qed");
 
             let credentials = PassCredential::AuthenticatorB(
                 authenticator_b::Credential::new(
@@ -1301,6 +1523,7 @@ mod device_filters {
                 vec![AssetSpendLimit {
                     asset: (),
                     max_amount: 1000u128,
+                    budget: None,
                 }]
                 .try_into()
                 .unwrap(),
@@ -1351,6 +1574,7 @@ mod device_filters {
                 vec![AssetSpendLimit {
                     asset: (),
                     max_amount: 1000u128,
+                    budget: None,
                 }]
                 .try_into()
                 .unwrap(),
@@ -1359,6 +1583,7 @@ mod device_filters {
                 vec![AssetSpendLimit {
                     asset: (),
                     max_amount: 500u128,
+                    budget: None,
                 }]
                 .try_into()
                 .unwrap(),
@@ -1367,6 +1592,7 @@ mod device_filters {
                 vec![AssetSpendLimit {
                     asset: (),
                     max_amount: 2000u128,
+                    budget: None,
                 }]
                 .try_into()
                 .unwrap(),
@@ -1466,10 +1692,3377 @@ mod device_filters {
                 filter.clone(),
             ));
 
-            let (account, _, stored_filter) =
+            let (account, _, stored_filter, _) =
                 SessionKeys::<Test>::get(SIGNER).expect("session exists");
             assert_eq!(account, Address::get());
             assert_eq!(stored_filter, filter);
         })
     }
 }
+
+mod recovery {
+    use super::*;
+    use crate::{
+        GuardianSet, Guardians, HoldReason, Recoveries, RecoveryConsiderations, RecoveryOf,
+    };
+    use frame_support::{traits::fungible::InspectHold, BoundedVec};
+
+    const DELAY: u64 = 3;
+    const STRANGER: AccountId = AccountId::new([4u8; 32]);
+
+    fn new_device(device_id: DeviceId) -> PassDeviceAttestation {
+        PassDeviceAttestation::AuthenticatorAAuthenticator(authenticator_a::DeviceAttestation {
+            device_id,
+            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+        })
+    }
+
+    fn guardians(accounts: &[AccountId]) -> BoundedVec<AccountId, ConstU32<3>> {
+        accounts.to_vec().try_into().unwrap()
+    }
+
+    fn recovery_deposit() -> Balance {
+        ItemStoragePrice::convert(Footprint::from_parts(
+            1,
+            RecoveryOf::<Test>::max_encoded_len(),
+        ))
+    }
+
+    fn held_for_devices() -> Balance {
+        Balances::balance_on_hold(&HoldReason::AccountDevices.into(), &Address::get())
+    }
+
+    /// What stays held once a consideration is back to a single, free item.
+    fn base_deposit() -> Balance {
+        ItemStoragePrice::convert(Footprint::default())
+    }
+
+    fn set_guardians(threshold: u32) -> frame_support::dispatch::DispatchResult {
+        crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+        Pass::set_guardians(
+            RuntimeOrigin::signed(Address::get()),
+            guardians(&[SIGNER, OTHER, CHARLIE]),
+            threshold,
+            DELAY,
+        )
+    }
+
+    /// An account with three guardians, requiring two approvals.
+    fn setup_with_guardians() -> sp_io::TestExternalities {
+        let mut t = prepare(AccountNameA::get());
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ExistentialDeposit::get() + 10 * recovery_deposit()
+            ));
+            assert_ok!(set_guardians(2));
+        });
+        t
+    }
+
+    use frame_support::traits::ConstU32;
+
+    #[test]
+    fn set_guardians_requires_an_admin_device() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_noop!(
+                Pass::set_guardians(
+                    RuntimeOrigin::signed(Address::get()),
+                    guardians(&[SIGNER]),
+                    1,
+                    DELAY
+                ),
+                Error::<Test>::NotAuthenticatedByDevice
+            );
+
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ExistentialDeposit::get() + 10 * recovery_deposit()
+            ));
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                new_device(OTHER_DEVICE),
+                remark_only_filter(),
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::set_guardians(
+                    RuntimeOrigin::signed(Address::get()),
+                    guardians(&[SIGNER]),
+                    1,
+                    DELAY
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+        })
+    }
+
+    #[test]
+    fn set_guardians_validates_the_guardian_set() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            for (accounts, threshold, delay) in [
+                (vec![], 0, DELAY),
+                (vec![SIGNER, SIGNER], 1, DELAY),
+                (vec![SIGNER, Address::get()], 1, DELAY),
+                (vec![SIGNER, OTHER], 0, DELAY),
+                (vec![SIGNER, OTHER], 3, DELAY),
+                (vec![SIGNER, OTHER], 1, 1),
+            ] {
+                assert_noop!(
+                    Pass::set_guardians(
+                        RuntimeOrigin::signed(Address::get()),
+                        guardians(&accounts),
+                        threshold,
+                        delay
+                    ),
+                    Error::<Test>::InvalidGuardians
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn set_guardians_works() {
+        setup_with_guardians().execute_with(|| {
+            System::assert_has_event(
+                Event::<Test>::GuardiansSet {
+                    who: Address::get(),
+                    threshold: 2,
+                }
+                .into(),
+            );
+            assert_eq!(
+                Guardians::<Test>::get(Address::get()),
+                Some(GuardianSet {
+                    guardians: guardians(&[SIGNER, OTHER, CHARLIE]),
+                    threshold: 2,
+                    delay: DELAY,
+                })
+            );
+
+            // Replacing the guardians doesn't take another deposit
+            assert_ok!(set_guardians(3));
+            assert_eq!(
+                RecoveryConsiderations::<Test>::get(Address::get()).map(|(_, c)| c),
+                Some(1)
+            );
+        })
+    }
+
+    #[test]
+    fn recovery_storage_is_covered_by_deposits() {
+        setup_with_guardians().execute_with(|| {
+            // The first item is free
+            assert_eq!(held_for_devices(), 0);
+
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            assert_eq!(held_for_devices(), recovery_deposit());
+
+            assert_ok!(Pass::cancel_recovery(RuntimeOrigin::signed(Address::get())));
+            assert_eq!(held_for_devices(), base_deposit());
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::remove_guardians(
+                RuntimeOrigin::signed(Address::get())
+            ));
+            assert_eq!(Guardians::<Test>::get(Address::get()), None);
+            assert!(RecoveryConsiderations::<Test>::get(Address::get()).is_none());
+        })
+    }
+
+    #[test]
+    fn only_guardians_can_recover() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::signed(SIGNER),
+                    Address::get(),
+                    new_device(OTHER_DEVICE),
+                    None,
+                ),
+                Error::<Test>::GuardiansNotFound
+            );
+        });
+
+        setup_with_guardians().execute_with(|| {
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::signed(STRANGER),
+                    Address::get(),
+                    new_device(OTHER_DEVICE),
+                    None,
+                ),
+                Error::<Test>::NotGuardian
+            );
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::root(),
+                    Address::get(),
+                    new_device(OTHER_DEVICE),
+                    None,
+                ),
+                DispatchError::BadOrigin
+            );
+
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            assert_noop!(
+                Pass::approve_recovery(RuntimeOrigin::signed(STRANGER), Address::get()),
+                Error::<Test>::NotGuardian
+            );
+        })
+    }
+
+    #[test]
+    fn fails_if_attestation_is_invalid() {
+        setup_with_guardians().execute_with(|| {
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::signed(SIGNER),
+                    Address::get(),
+                    new_device(THE_DEVICE),
+                    None,
+                ),
+                Error::<Test>::DeviceAlreadyExists
+            );
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::signed(SIGNER),
+                    Address::get(),
+                    PassDeviceAttestation::AuthenticatorB(authenticator_b::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        context: System::block_number(),
+                        challenge: LastThreeBlocksChallenger::generate(
+                            &System::block_number(),
+                            &[]
+                        ),
+                    }),
+                    None,
+                ),
+                Error::<Test>::DeviceAttestationInvalid
+            );
+        })
+    }
+
+    #[test]
+    fn only_one_recovery_at_a_time() {
+        setup_with_guardians().execute_with(|| {
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            assert_noop!(
+                Pass::initiate_recovery(
+                    RuntimeOrigin::signed(OTHER),
+                    Address::get(),
+                    new_device(THIRD_DEVICE),
+                    None,
+                ),
+                Error::<Test>::RecoveryInProgress
+            );
+        })
+    }
+
+    #[test]
+    fn guardians_cannot_approve_twice() {
+        setup_with_guardians().execute_with(|| {
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            assert_noop!(
+                Pass::approve_recovery(RuntimeOrigin::signed(SIGNER), Address::get()),
+                Error::<Test>::AlreadyApproved
+            );
+        })
+    }
+
+    #[test]
+    fn cannot_finish_without_approvals_or_before_delay() {
+        setup_with_guardians().execute_with(|| {
+            assert_noop!(
+                Pass::finish_recovery(RuntimeOrigin::signed(STRANGER), Address::get()),
+                Error::<Test>::RecoveryNotFound
+            );
+
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            run_to(System::block_number() + DELAY);
+            assert_noop!(
+                Pass::finish_recovery(RuntimeOrigin::signed(STRANGER), Address::get()),
+                Error::<Test>::RecoveryNotReady
+            );
+
+            assert_ok!(Pass::approve_recovery(
+                RuntimeOrigin::signed(OTHER),
+                Address::get()
+            ));
+            run_to(System::block_number() + DELAY - 1);
+            assert_noop!(
+                Pass::finish_recovery(RuntimeOrigin::signed(STRANGER), Address::get()),
+                Error::<Test>::RecoveryNotReady
+            );
+        })
+    }
+
+    #[test]
+    fn recovery_works() {
+        setup_with_guardians().execute_with(|| {
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                Some(THE_DEVICE),
+            ));
+            System::assert_has_event(
+                Event::<Test>::RecoveryInitiated {
+                    who: Address::get(),
+                    guardian: SIGNER,
+                    device_id: OTHER_DEVICE,
+                }
+                .into(),
+            );
+
+            assert_ok!(Pass::approve_recovery(
+                RuntimeOrigin::signed(CHARLIE),
+                Address::get()
+            ));
+            let executable_at = System::block_number() + DELAY;
+            System::assert_has_event(
+                Event::<Test>::RecoveryDelayStarted {
+                    who: Address::get(),
+                    executable_at,
+                }
+                .into(),
+            );
+
+            // Late approvals are still accepted, but don't restart the delay
+            run_to(executable_at - 1);
+            assert_ok!(Pass::approve_recovery(
+                RuntimeOrigin::signed(OTHER),
+                Address::get()
+            ));
+            assert_eq!(
+                Recoveries::<Test>::get(Address::get()).and_then(|r| r.executable_at),
+                Some(executable_at)
+            );
+
+            run_to(executable_at);
+            assert_ok!(Pass::finish_recovery(
+                RuntimeOrigin::signed(STRANGER),
+                Address::get()
+            ));
+            System::assert_has_event(
+                Event::<Test>::RecoveryFinished {
+                    who: Address::get(),
+                    device_id: OTHER_DEVICE,
+                }
+                .into(),
+            );
+
+            // The new device is an admin, and the old one is revoked
+            assert_eq!(
+                crate::DeviceFilters::<Test>::get(Address::get(), OTHER_DEVICE),
+                Some(DeviceFilter::Admin)
+            );
+            assert!(!crate::Devices::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+            assert!(Recoveries::<Test>::get(Address::get()).is_none());
+            assert_eq!(held_for_devices(), base_deposit());
+        })
+    }
+
+    #[test]
+    fn recovery_can_keep_the_old_device() {
+        setup_with_guardians().execute_with(|| {
+            assert_ok!(set_guardians(1));
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            run_to(System::block_number() + DELAY);
+            assert_ok!(Pass::finish_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get()
+            ));
+
+            assert!(crate::Devices::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+            assert!(crate::Devices::<Test>::contains_key(
+                Address::get(),
+                OTHER_DEVICE
+            ));
+        })
+    }
+
+    #[test]
+    fn owner_can_cancel_recovery() {
+        setup_with_guardians().execute_with(|| {
+            assert_noop!(
+                Pass::cancel_recovery(RuntimeOrigin::signed(Address::get())),
+                Error::<Test>::RecoveryNotFound
+            );
+
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                Some(THE_DEVICE),
+            ));
+            assert_ok!(Pass::approve_recovery(
+                RuntimeOrigin::signed(OTHER),
+                Address::get()
+            ));
+
+            assert_noop!(
+                Pass::cancel_recovery(RuntimeOrigin::signed(SIGNER)),
+                DispatchError::BadOrigin
+            );
+            assert_ok!(Pass::cancel_recovery(RuntimeOrigin::signed(Address::get())));
+            System::assert_has_event(
+                Event::<Test>::RecoveryCancelled {
+                    who: Address::get(),
+                }
+                .into(),
+            );
+
+            run_to(System::block_number() + DELAY);
+            assert_noop!(
+                Pass::finish_recovery(RuntimeOrigin::signed(SIGNER), Address::get()),
+                Error::<Test>::RecoveryNotFound
+            );
+        })
+    }
+
+    #[test]
+    fn changing_guardians_cancels_recovery() {
+        setup_with_guardians().execute_with(|| {
+            assert_ok!(Pass::initiate_recovery(
+                RuntimeOrigin::signed(SIGNER),
+                Address::get(),
+                new_device(OTHER_DEVICE),
+                None,
+            ));
+            assert_ok!(set_guardians(2));
+            assert!(Recoveries::<Test>::get(Address::get()).is_none());
+            assert_eq!(held_for_devices(), base_deposit());
+        })
+    }
+}
+
+mod kill_account {
+    use super::*;
+    use crate::{
+        AccountRegistrars, AccountSessionKeys, DeviceConsiderations, DeviceFilters, DeviceIds,
+        Devices, Guardians, HoldReason, RecoveryConsiderations, RegisteredAccounts,
+        RegistrarConsiderations, SessionKeyConsiderations, SessionKeys,
+    };
+    use frame_support::{
+        storage::StorageDoubleMap as _,
+        traits::fungible::{Inspect, InspectHold},
+    };
+    use sp_core::blake2_256;
+
+    const BENEFICIARY: AccountId = AccountId::new([4u8; 32]);
+
+    fn registration_deposit() -> Balance {
+        RegistrationStoragePrice::convert(Footprint::from_parts(1, HashedUserId::max_encoded_len()))
+    }
+
+    fn held(reason: HoldReason, who: &AccountId) -> Balance {
+        Balances::balance_on_hold(&reason.into(), who)
+    }
+
+    fn remove_session_task(session_key: &AccountId) -> [u8; 32] {
+        blake2_256(&("remove_session_key", session_key).encode())
+    }
+
+    /// An account registered by `SIGNER`, with two devices, two session keys and guardians.
+    fn setup_full_account() -> sp_io::TestExternalities {
+        let mut t = new_test_ext();
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(
+                &SIGNER,
+                ExistentialDeposit::get() + registration_deposit()
+            ));
+            assert_ok!(Pass::register(
+                RuntimeOrigin::signed(SIGNER),
+                AccountNameA::get(),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: THE_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+            ));
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                10 * ExistentialDeposit::get()
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            for session_key in [OTHER, CHARLIE] {
+                crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+                assert_ok!(Pass::add_session_key(
+                    RuntimeOrigin::signed(Address::get()),
+                    session_key,
+                    Some(DURATION),
+                    remark_only_filter(),
+                ));
+            }
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::set_guardians(
+                RuntimeOrigin::signed(Address::get()),
+                vec![SIGNER].try_into().unwrap(),
+                1,
+                2,
+            ));
+
+            assert_eq!(
+                held(HoldReason::AccountRegistration, &SIGNER),
+                registration_deposit()
+            );
+            assert!(held(HoldReason::AccountDevices, &Address::get()) > 0);
+            assert!(held(HoldReason::SessionKeys, &Address::get()) > 0);
+        });
+        t
+    }
+
+    #[test]
+    fn fails_if_not_authenticated_by_an_admin_device() {
+        setup_full_account().execute_with(|| {
+            assert_noop!(
+                Pass::kill_account(RuntimeOrigin::signed(SIGNER), BENEFICIARY),
+                DispatchError::BadOrigin
+            );
+
+            crate::AuthenticatedDevice::<Test>::kill();
+            assert_noop!(
+                Pass::kill_account(RuntimeOrigin::signed(Address::get()), BENEFICIARY),
+                Error::<Test>::NotAuthenticatedByDevice
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::kill_account(RuntimeOrigin::signed(Address::get()), BENEFICIARY),
+                Error::<Test>::PermissionEscalation
+            );
+        })
+    }
+
+    #[test]
+    fn session_keys_take_a_deposit() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                Some(DURATION),
+                remark_only_filter(),
+            ));
+            assert_eq!(
+                SessionKeyConsiderations::<Test>::get(Address::get()).map(|(_, c)| c),
+                Some(1)
+            );
+
+            // The first session key is free, but the second one requires a deposit
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_noop!(
+                Pass::add_session_key(
+                    RuntimeOrigin::signed(Address::get()),
+                    CHARLIE,
+                    Some(DURATION),
+                    remark_only_filter(),
+                ),
+                TokenError::FundsUnavailable
+            );
+        })
+    }
+
+    #[test]
+    fn it_works() {
+        setup_full_account().execute_with(|| {
+            let balance = Balances::total_balance(&Address::get());
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::kill_account(
+                RuntimeOrigin::signed(Address::get()),
+                BENEFICIARY
+            ));
+            System::assert_has_event(
+                Event::<Test>::AccountKilled {
+                    who: Address::get(),
+                }
+                .into(),
+            );
+            for device_id in [THE_DEVICE, OTHER_DEVICE] {
+                System::assert_has_event(
+                    Event::<Test>::DeviceRemoved {
+                        who: Address::get(),
+                        device_id,
+                    }
+                    .into(),
+                );
+            }
+            for session_key in [OTHER, CHARLIE] {
+                System::assert_has_event(
+                    Event::<Test>::SessionRemoved {
+                        session_key,
+                        filter: remark_only_filter(),
+                    }
+                    .into(),
+                );
+            }
+
+            // Storage is cleared
+            assert!(!Devices::<Test>::contains_prefix(Address::get()));
+            assert!(!DeviceFilters::<Test>::contains_prefix(Address::get()));
+            assert!(!DeviceIds::<Test>::contains_key(THE_DEVICE));
+            assert!(!DeviceIds::<Test>::contains_key(OTHER_DEVICE));
+            assert!(!AccountSessionKeys::<Test>::contains_prefix(Address::get()));
+            assert!(!SessionKeys::<Test>::contains_key(OTHER));
+            assert!(!SessionKeys::<Test>::contains_key(CHARLIE));
+            assert!(!Guardians::<Test>::contains_key(Address::get()));
+            assert!(!AccountRegistrars::<Test>::contains_key(Address::get()));
+            assert!(!RegisteredAccounts::<Test>::contains_key(
+                SIGNER,
+                AccountNameA::get()
+            ));
+            assert!(!DeviceConsiderations::<Test>::contains_key(Address::get()));
+            assert!(!SessionKeyConsiderations::<Test>::contains_key(
+                Address::get()
+            ));
+            assert!(!RecoveryConsiderations::<Test>::contains_key(Address::get()));
+            assert!(!RegistrarConsiderations::<Test>::contains_key(SIGNER));
+
+            // Scheduled session removals are cancelled
+            for session_key in [OTHER, CHARLIE] {
+                assert!(!pallet_scheduler::Lookup::<Test>::contains_key(
+                    remove_session_task(&session_key)
+                ));
+            }
+
+            // Deposits are released, and the balance is transferred to the beneficiary
+            assert_eq!(held(HoldReason::AccountRegistration, &SIGNER), 0);
+            assert_eq!(held(HoldReason::AccountDevices, &Address::get()), 0);
+            assert_eq!(held(HoldReason::SessionKeys, &Address::get()), 0);
+            assert_eq!(Balances::balance(&BENEFICIARY), balance);
+            assert!(!System::account_exists(&Address::get()));
+        })
+    }
+
+    #[test]
+    fn removes_session_keys_created_before_they_were_indexed() {
+        use crate::{
+            migration::{v2, MigrateV2ToV3},
+            AccountSessionsCount,
+        };
+        use frame_support::traits::StorageVersion;
+
+        const LEGACY: [AccountId; 2] = [AccountId::new([5u8; 32]), AccountId::new([6u8; 32])];
+
+        setup_full_account().execute_with(|| {
+            let deposit = held(HoldReason::SessionKeys, &Address::get());
+
+            // Simulate v2 state: session keys don't record their creator, and those created
+            // before they took a deposit aren't indexed by account either.
+            for session_key in [OTHER, CHARLIE] {
+                let (address, until, filter, _) = SessionKeys::<Test>::get(&session_key).unwrap();
+                v2::SessionKeys::<Test, ()>::insert(session_key, (address, until, filter));
+            }
+            for session_key in LEGACY {
+                v2::SessionKeys::<Test, ()>::insert(
+                    session_key,
+                    (Address::get(), 10, remark_only_filter()),
+                );
+                AccountSessionsCount::<Test>::mutate(Address::get(), |count| *count += 1);
+            }
+            StorageVersion::new(2).put::<Pass>();
+
+            run_stepped_migration::<MigrateV2ToV3<Test>>(MigrateV2ToV3::<Test>::step_weight());
+            for session_key in LEGACY {
+                assert!(AccountSessionKeys::<Test>::contains_key(
+                    Address::get(),
+                    session_key
+                ));
+            }
+
+            // Removing a session key that holds no deposit keeps the others' deposits
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::remove_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                LEGACY[0].clone()
+            ));
+            assert_eq!(held(HoldReason::SessionKeys, &Address::get()), deposit);
+            assert_eq!(
+                SessionKeyConsiderations::<Test>::get(Address::get()).map(|(_, c)| c),
+                Some(2)
+            );
+
+            // And the rest are removed along with their account
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::kill_account(
+                RuntimeOrigin::signed(Address::get()),
+                BENEFICIARY
+            ));
+            assert!(!SessionKeys::<Test>::contains_key(&LEGACY[1]));
+            assert!(!AccountSessionKeys::<Test>::contains_prefix(Address::get()));
+            assert_eq!(held(HoldReason::SessionKeys, &Address::get()), 0);
+        })
+    }
+
+    #[test]
+    fn can_register_again_after_killing_an_account() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::kill_account(
+                RuntimeOrigin::signed(Address::get()),
+                BENEFICIARY
+            ));
+            assert!(!System::account_exists(&Address::get()));
+
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                AccountNameA::get(),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: THE_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+            ));
+        })
+    }
+}
+
+mod spend_budgets {
+    use super::*;
+    use crate::{
+        filter::{AssetSpendLimit, SpendBudget},
+        DeviceFilterOf, DeviceOf, PeriodSpends, Spender,
+    };
+    use sp_runtime::transaction_validity::InvalidTransaction;
+
+    const PERIOD: u32 = 10;
+
+    pub(super) fn spend_filter(
+        max_amount: u128,
+        budget: Option<(u128, u32)>,
+    ) -> DeviceFilterOf<Test> {
+        DeviceFilter::Spend(
+            vec![AssetSpendLimit {
+                asset: (),
+                max_amount,
+                budget: budget.map(|(amount, period)| SpendBudget { amount, period }),
+            }]
+            .try_into()
+            .unwrap(),
+        )
+    }
+
+    pub(super) fn transfer(dest: AccountId, value: Balance) -> RuntimeCall {
+        pallet_balances::Call::transfer_keep_alive { dest, value }.into()
+    }
+
+    pub(super) fn spend_with_device(
+        call: RuntimeCall,
+    ) -> ApplyExtrinsicResultWithInfo<PostDispatchInfo> {
+        dispatch::authenticate(
+            OTHER_DEVICE,
+            PassCredential::AuthenticatorAAuthenticator(authenticator_a::Credential {
+                user_id: AccountNameA::get(),
+                challenge: authenticator_a::Authenticator::generate(&(), &[]),
+            }),
+            call,
+        )
+    }
+
+    pub(super) fn spent(spender: Spender<AccountId>) -> Option<(u64, u128)> {
+        PeriodSpends::<Test>::get((Address::get(), spender, ()))
+    }
+
+    /// An account with a spend device, allowed to spend up to 100 per transaction, and 150 per
+    /// period.
+    pub(super) fn setup_with_spend_device() -> sp_io::TestExternalities {
+        let mut t = prepare(AccountNameA::get());
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(&Address::get(), 1_000_000));
+            assert_ok!(Balances::mint_into(&CHARLIE, ExistentialDeposit::get()));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                spend_filter(100, Some((150, PERIOD))),
+            ));
+        });
+        t
+    }
+
+    #[test]
+    fn budget_superset_logic() {
+        new_test_ext().execute_with(|| {
+            let unlimited = spend_filter(100, None);
+            let budget = spend_filter(100, Some((150, PERIOD)));
+
+            // An unlimited limit can delegate any budget
+            assert!(unlimited.is_superset_of(&budget));
+            // A budgeted limit can't delegate an unlimited one
+            assert!(!budget.is_superset_of(&unlimited));
+            // A budget can be delegated with a lower amount, or a longer period
+            assert!(budget.is_superset_of(&spend_filter(100, Some((100, PERIOD)))));
+            assert!(budget.is_superset_of(&spend_filter(100, Some((150, 2 * PERIOD)))));
+            // But not with a higher amount, or a shorter period
+            assert!(!budget.is_superset_of(&spend_filter(100, Some((200, PERIOD)))));
+            assert!(!budget.is_superset_of(&spend_filter(100, Some((150, PERIOD / 2)))));
+        })
+    }
+
+    #[test]
+    fn budgeted_device_cannot_delegate_more_budget() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ItemStoragePrice::convert(Footprint::from_parts(
+                    2,
+                    DeviceOf::<Test>::max_encoded_len()
+                ))
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::add_device(
+                    RuntimeOrigin::signed(Address::get()),
+                    PassDeviceAttestation::AuthenticatorAAuthenticator(
+                        authenticator_a::DeviceAttestation {
+                            device_id: THIRD_DEVICE,
+                            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                        }
+                    ),
+                    spend_filter(100, None),
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+        })
+    }
+
+    #[test]
+    fn budget_limits_cumulative_spends() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 100)));
+
+            // Exceeds the budget for the period
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 60)),
+                InvalidTransaction::Call
+            );
+            assert_ok!(spend_with_device(transfer(CHARLIE, 50)));
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 1)),
+                InvalidTransaction::Call
+            );
+
+            // The budget is reset on the next period
+            run_to(PERIOD as u64);
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+            assert_eq!(
+                spent(Spender::Device(OTHER_DEVICE)),
+                Some((PERIOD as u64, 100))
+            );
+
+            // Per transaction limits still apply
+            run_to(2 * PERIOD as u64);
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 101)),
+                InvalidTransaction::Call
+            );
+        })
+    }
+
+    #[test]
+    fn budget_is_refunded_if_the_call_fails() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+
+            // Transferring less than the existential deposit to a new account fails
+            let result = spend_with_device(transfer(SIGNER, 50));
+            assert!(matches!(result, Ok(Err(_))));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 100)));
+
+            assert_ok!(spend_with_device(transfer(CHARLIE, 50)));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 150)));
+        })
+    }
+
+    #[test]
+    fn session_keys_have_their_own_budget() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                None,
+                spend_filter(100, Some((100, PERIOD))),
+            ));
+
+            assert_ok!(dispatch::signed(OTHER, transfer(CHARLIE, 100)));
+            assert_eq!(spent(Spender::SessionKey(OTHER)), Some((0, 100)));
+            assert_noop!(
+                dispatch::signed(OTHER, transfer(CHARLIE, 1)),
+                InvalidTransaction::Call
+            );
+
+            // Removing the session key clears its spends
+            assert_ok!(Pass::remove_session_key(RuntimeOrigin::root(), OTHER));
+            assert_eq!(spent(Spender::SessionKey(OTHER)), None);
+        })
+    }
+
+    #[test]
+    fn session_keys_spend_from_the_budget_of_their_creator() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+
+            // Each session of the spend device has a budget of its own...
+            let other_session = AccountId::new([4u8; 32]);
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            for session_key in [OTHER, other_session.clone()] {
+                assert_ok!(Pass::add_session_key(
+                    RuntimeOrigin::signed(Address::get()),
+                    session_key,
+                    None,
+                    spend_filter(100, Some((150, PERIOD))),
+                ));
+            }
+
+            // ...but they can't spend more than what's left of the device's budget
+            assert_ok!(dispatch::signed(OTHER, transfer(CHARLIE, 40)));
+            assert_eq!(spent(Spender::SessionKey(OTHER)), Some((0, 40)));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 140)));
+            assert_noop!(
+                dispatch::signed(other_session.clone(), transfer(CHARLIE, 20)),
+                InvalidTransaction::Call
+            );
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 20)),
+                InvalidTransaction::Call
+            );
+            assert_ok!(dispatch::signed(other_session, transfer(CHARLIE, 10)));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 150)));
+        })
+    }
+
+    #[test]
+    fn sessions_of_removed_devices_cannot_spend_from_budgets() {
+        setup_with_spend_device().execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                None,
+                spend_filter(100, Some((150, PERIOD))),
+            ));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::remove_device(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE
+            ));
+
+            assert_noop!(
+                dispatch::signed(OTHER, transfer(CHARLIE, 10)),
+                InvalidTransaction::Call
+            );
+        })
+    }
+
+    #[test]
+    fn migration_adds_no_budget_to_spend_limits() {
+        use crate::migration::{v1, v2, MigrateV1ToV2};
+        use frame_support::{
+            traits::{GetStorageVersion, StorageVersion},
+            BoundedVec,
+        };
+
+        setup_with_spend_device().execute_with(|| {
+            // Simulate v1 state
+            let old_filter = || {
+                v1::DeviceFilter::Spend(BoundedVec::truncate_from(vec![v1::AssetSpendLimit {
+                    asset: (),
+                    max_amount: 100,
+                }]))
+            };
+            v1::DeviceFilters::<Test, ()>::insert(Address::get(), OTHER_DEVICE, old_filter());
+            v1::SessionKeys::<Test, ()>::insert(OTHER, (Address::get(), 10, old_filter()));
+            StorageVersion::new(1).put::<Pass>();
+
+            // Migrates a filter per step
+            let steps =
+                run_stepped_migration::<MigrateV1ToV2<Test>>(MigrateV1ToV2::<Test>::step_weight());
+            // Both devices, the session key, and a last step finding there's nothing left
+            assert_eq!(steps, 4);
+
+            assert_eq!(
+                crate::DeviceFilters::<Test>::get(Address::get(), OTHER_DEVICE),
+                Some(spend_filter(100, None))
+            );
+            assert_eq!(
+                crate::DeviceFilters::<Test>::get(Address::get(), THE_DEVICE),
+                Some(DeviceFilter::Admin)
+            );
+            assert_eq!(
+                v2::SessionKeys::<Test, ()>::get(OTHER),
+                Some((Address::get(), 10, spend_filter(100, None)))
+            );
+            assert_eq!(Pass::on_chain_storage_version(), 2);
+        })
+    }
+
+    #[test]
+    fn migration_attributes_sessions_to_an_admin_device() {
+        use crate::migration::{v2, MigrateV2ToV3};
+        use crate::SessionKeys;
+        use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+        setup_with_spend_device().execute_with(|| {
+            // Simulate v2 state
+            v2::SessionKeys::<Test, ()>::insert(
+                OTHER,
+                (Address::get(), 10, spend_filter(100, Some((150, PERIOD)))),
+            );
+            StorageVersion::new(2).put::<Pass>();
+
+            // Migrates a session key per step
+            let steps =
+                run_stepped_migration::<MigrateV2ToV3<Test>>(MigrateV2ToV3::<Test>::step_weight());
+            // The session key, and a last step finding there's nothing left
+            assert_eq!(steps, 2);
+
+            // The spend device may have created it, but only the admin device is known to
+            // have been able to
+            assert_eq!(
+                SessionKeys::<Test>::get(OTHER),
+                Some((
+                    Address::get(),
+                    10,
+                    spend_filter(100, Some((150, PERIOD))),
+                    THE_DEVICE
+                ))
+            );
+            assert_eq!(SessionKeys::<Test>::count(), 1);
+            assert_eq!(Pass::on_chain_storage_version(), 3);
+        })
+    }
+
+    #[test]
+    fn removing_a_device_clears_its_spends() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+
+            assert_ok!(Pass::remove_device(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE
+            ));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), None);
+        })
+    }
+}
+
+mod nested_calls {
+    use super::*;
+    use crate::{
+        filter::{CallInspection, CallMatcher, InspectedCall, ScaleCallMatcher},
+        CallInspector, DeviceFilterOf, PeriodSpends, Spender,
+    };
+    use sp_runtime::transaction_validity::InvalidTransaction;
+    use spend_budgets::{setup_with_spend_device, spend_filter, spend_with_device, transfer};
+
+    pub(super) fn remark() -> RuntimeCall {
+        frame_system::Call::remark_with_event {
+            remark: b"Hello, world".to_vec(),
+        }
+        .into()
+    }
+
+    pub(super) fn batch(calls: Vec<RuntimeCall>) -> RuntimeCall {
+        pallet_utility::Call::batch { calls }.into()
+    }
+
+    fn inspect(call: &RuntimeCall) -> Option<CallInspection<(), u128>> {
+        Pass::inspect_call(call)
+    }
+
+    pub(super) fn set_filter(filter: DeviceFilterOf<Test>) {
+        crate::DeviceFilters::<Test>::insert(Address::get(), OTHER_DEVICE, filter);
+    }
+
+    #[test]
+    fn inspection_expands_nested_calls() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                inspect(&remark()),
+                Some(CallInspection {
+                    calls: vec![InspectedCall::new(
+                        (0, 7),
+                        &ScaleCallMatcher::call_args(&remark()),
+                        None
+                    )],
+                    only_spends: false,
+                    spends: vec![],
+                })
+            );
+
+            let inspection = inspect(&batch(vec![
+                transfer(CHARLIE, 10),
+                batch(vec![transfer(OTHER, 20), remark()]),
+            ]))
+            .unwrap();
+            assert_eq!(inspection.calls.len(), 5);
+            assert!(inspection.calls.iter().any(|c| c.index == (3, 0)));
+            assert!(inspection.calls.iter().any(|c| c.index == (0, 7)));
+            assert!(!inspection.only_spends);
+            assert_eq!(inspection.spends, vec![((), 30)]);
+
+            let inspection =
+                inspect(&batch(vec![transfer(CHARLIE, 10), transfer(OTHER, 20)])).unwrap();
+            assert!(inspection.only_spends);
+            assert_eq!(inspection.spends, vec![((), 30)]);
+        })
+    }
+
+    #[test]
+    fn inspection_rejects_deeply_nested_calls() {
+        new_test_ext().execute_with(|| {
+            let nested = |depth: u32| (0..depth).fold(remark(), |call, _| batch(vec![call]));
+
+            assert!(inspect(&nested(BatchInspector::MAX_DEPTH)).is_some());
+            assert!(inspect(&nested(BatchInspector::MAX_DEPTH + 1)).is_none());
+        })
+    }
+
+    #[test]
+    fn authentication_weight_depends_on_nested_calls() {
+        new_test_ext().execute_with(|| {
+            use sp_runtime::traits::TransactionExtension;
+
+            let call = batch(vec![
+                transfer(CHARLIE, 10),
+                batch(vec![transfer(OTHER, 20), remark()]),
+            ]);
+            assert_eq!(
+                CallInspection::<(), u128>::count::<_, BatchInspector>(&call),
+                inspect(&call).unwrap().calls.len() as u32
+            );
+
+            let weight = |call: &RuntimeCall| PassAuthenticate::<Test>::default().weight(call);
+            assert!(weight(&call).all_gt(weight(&remark())));
+            assert!(weight(&batch(vec![call.clone(), remark()])).all_gt(weight(&call)));
+        })
+    }
+
+    #[test]
+    fn calls_filter_applies_to_every_nested_call() {
+        setup_with_spend_device().execute_with(|| {
+            let calls = |set: &[(u8, u8)]| {
+                DeviceFilter::Calls(
+                    set.iter()
+                        .copied()
+                        .collect::<alloc::collections::BTreeSet<_>>()
+                        .try_into()
+                        .unwrap(),
+                )
+            };
+
+            // The wrapper must be allowed
+            set_filter(calls(&[(0, 7)]));
+            assert_noop!(
+                spend_with_device(batch(vec![remark()])),
+                InvalidTransaction::Call
+            );
+
+            // And so must be the inner calls
+            set_filter(calls(&[(0, 7), (3, 0)]));
+            assert_ok!(spend_with_device(batch(vec![remark(), remark()])));
+            assert_noop!(
+                spend_with_device(batch(vec![remark(), transfer(CHARLIE, 10)])),
+                InvalidTransaction::Call
+            );
+
+            // Same goes for pallets
+            set_filter(DeviceFilter::Pallets(
+                [3u8]
+                    .into_iter()
+                    .collect::<alloc::collections::BTreeSet<_>>()
+                    .try_into()
+                    .unwrap(),
+            ));
+            assert_noop!(
+                spend_with_device(batch(vec![remark()])),
+                InvalidTransaction::Call
+            );
+        })
+    }
+
+    #[test]
+    fn spend_filter_sums_nested_spends() {
+        setup_with_spend_device().execute_with(|| {
+            set_filter(spend_filter(100, Some((150, 10))));
+
+            // Every leaf call must be a spend
+            assert_noop!(
+                spend_with_device(batch(vec![transfer(CHARLIE, 10), remark()])),
+                InvalidTransaction::Call
+            );
+            // Spends are summed against the limit
+            assert_noop!(
+                spend_with_device(batch(vec![transfer(CHARLIE, 60), transfer(CHARLIE, 60)])),
+                InvalidTransaction::Call
+            );
+
+            assert_ok!(spend_with_device(batch(vec![
+                transfer(CHARLIE, 60),
+                transfer(CHARLIE, 40)
+            ])));
+            assert_eq!(
+                PeriodSpends::<Test>::get((Address::get(), Spender::Device(OTHER_DEVICE), ())),
+                Some((0, 100))
+            );
+
+            // And against the budget
+            assert_noop!(
+                spend_with_device(batch(vec![transfer(CHARLIE, 30), transfer(CHARLIE, 30)])),
+                InvalidTransaction::Call
+            );
+        })
+    }
+}
+
+mod scoped_filters {
+    use super::*;
+    use crate::{
+        filter::{
+            ArgsFilter, AssetSpendLimit, CallMatcher, CallRule, ScaleCallMatcher, ValidityWindow,
+        },
+        DeviceFilterOf, DeviceOf,
+    };
+    use alloc::collections::BTreeSet;
+    use nested_calls::{batch, remark, set_filter};
+    use sp_core::blake2_256;
+    use sp_runtime::transaction_validity::InvalidTransaction;
+    use spend_budgets::{setup_with_spend_device, spend_filter, spend_with_device, transfer};
+
+    type F = DeviceFilterOf<Test>;
+
+    fn rule(
+        pallet: u8,
+        call: Option<u8>,
+        args: ArgsFilter,
+        max_amount: Option<u128>,
+    ) -> CallRule<(), u128> {
+        CallRule {
+            pallet,
+            call,
+            args,
+            spend: max_amount.map(|max_amount| AssetSpendLimit {
+                asset: (),
+                max_amount,
+                budget: None,
+            }),
+        }
+    }
+
+    fn scoped(rules: Vec<CallRule<(), u128>>, validity: Option<(u32, u32)>) -> F {
+        DeviceFilter::Scoped {
+            rules: rules.try_into().unwrap(),
+            validity: validity.map(|(from, until)| ValidityWindow { from, until }),
+        }
+    }
+
+    /// Only `balances.transfer_keep_alive`, up to `max_amount`.
+    fn pay(max_amount: u128) -> CallRule<(), u128> {
+        rule(10, Some(3), ArgsFilter::Any, Some(max_amount))
+    }
+
+    #[test]
+    fn cashier_device_can_only_charge_within_limit() {
+        setup_with_spend_device().execute_with(|| {
+            set_filter(scoped(vec![pay(50)], None));
+
+            assert_ok!(spend_with_device(transfer(CHARLIE, 50)));
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 51)),
+                InvalidTransaction::Call
+            );
+            assert_noop!(spend_with_device(remark()), InvalidTransaction::Call);
+            assert_noop!(
+                spend_with_device(
+                    pallet_balances::Call::transfer_allow_death {
+                        dest: CHARLIE,
+                        value: 10
+                    }
+                    .into()
+                ),
+                InvalidTransaction::Call
+            );
+
+            // Wrappers must be allowed as well
+            assert_noop!(
+                spend_with_device(batch(vec![transfer(CHARLIE, 10)])),
+                InvalidTransaction::Call
+            );
+
+            // Spends of nested calls are summed against the limit
+            set_filter(scoped(
+                vec![pay(50), rule(3, Some(0), ArgsFilter::Any, None)],
+                None,
+            ));
+            assert_noop!(
+                spend_with_device(batch(vec![transfer(CHARLIE, 30), transfer(CHARLIE, 30)])),
+                InvalidTransaction::Call
+            );
+            assert_ok!(spend_with_device(batch(vec![
+                transfer(CHARLIE, 20),
+                transfer(CHARLIE, 30)
+            ])));
+        })
+    }
+
+    #[test]
+    fn budgets_only_apply_to_the_calls_of_their_rule() {
+        use crate::{filter::SpendBudget, Spender};
+        use spend_budgets::spent;
+
+        setup_with_spend_device().execute_with(|| {
+            let mut budgeted = pay(100);
+            budgeted.spend.as_mut().unwrap().budget = Some(SpendBudget {
+                amount: 150,
+                period: 10,
+            });
+            // `balances.transfer_allow_death`, without a budget
+            let unbudgeted = rule(10, Some(0), ArgsFilter::Any, Some(100));
+            set_filter(scoped(vec![budgeted, unbudgeted], None));
+
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 100)));
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 60)),
+                InvalidTransaction::Call
+            );
+
+            // The other rule allows spending the same asset beyond the budget
+            let transfer_allow_death: RuntimeCall = pallet_balances::Call::transfer_allow_death {
+                dest: CHARLIE,
+                value: 100,
+            }
+            .into();
+            assert_ok!(spend_with_device(transfer_allow_death.clone()));
+            assert_ok!(spend_with_device(transfer_allow_death));
+            assert_eq!(spent(Spender::Device(OTHER_DEVICE)), Some((0, 100)));
+        })
+    }
+
+    #[test]
+    fn scoped_filter_is_only_valid_within_window() {
+        setup_with_spend_device().execute_with(|| {
+            set_filter(scoped(vec![pay(50)], Some((2, 5))));
+
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 10)),
+                InvalidTransaction::Call
+            );
+            run_to(2);
+            assert_ok!(spend_with_device(transfer(CHARLIE, 10)));
+            run_to(4);
+            assert_ok!(spend_with_device(transfer(CHARLIE, 10)));
+            run_to(5);
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 10)),
+                InvalidTransaction::Call
+            );
+        })
+    }
+
+    #[test]
+    fn expired_filter_cannot_delegate() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(Balances::mint_into(
+                &Address::get(),
+                ItemStoragePrice::convert(Footprint::from_parts(
+                    2,
+                    DeviceOf::<Test>::max_encoded_len()
+                ))
+            ));
+            set_filter(scoped(vec![pay(50)], Some((1, 5))));
+            run_to(5);
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::add_device(
+                    RuntimeOrigin::signed(Address::get()),
+                    PassDeviceAttestation::AuthenticatorAAuthenticator(
+                        authenticator_a::DeviceAttestation {
+                            device_id: THIRD_DEVICE,
+                            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                        }
+                    ),
+                    scoped(vec![pay(10)], Some((1, 5))),
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+        })
+    }
+
+    #[test]
+    fn scoped_filter_matches_arguments() {
+        setup_with_spend_device().execute_with(|| {
+            let args = ScaleCallMatcher::call_args(&transfer(CHARLIE, 10));
+            set_filter(scoped(
+                vec![rule(10, Some(3), ArgsFilter::Hash(blake2_256(&args)), None)],
+                None,
+            ));
+            assert_ok!(spend_with_device(transfer(CHARLIE, 10)));
+            assert_noop!(
+                spend_with_device(transfer(CHARLIE, 11)),
+                InvalidTransaction::Call
+            );
+
+            // The destination is encoded first, followed by the compact-encoded value
+            let dest = args[..args.len() - 1].to_vec();
+            set_filter(scoped(
+                vec![rule(
+                    10,
+                    Some(3),
+                    ArgsFilter::Prefix(dest.try_into().unwrap()),
+                    None,
+                )],
+                None,
+            ));
+            assert_ok!(spend_with_device(transfer(CHARLIE, 11)));
+            assert_noop!(
+                spend_with_device(transfer(OTHER, 10)),
+                InvalidTransaction::Call
+            );
+        })
+    }
+
+    #[test]
+    fn scoped_superset_logic() {
+        new_test_ext().execute_with(|| {
+            let set = |pallets: &[u8]| -> F {
+                DeviceFilter::Pallets(
+                    pallets
+                        .iter()
+                        .copied()
+                        .collect::<BTreeSet<_>>()
+                        .try_into()
+                        .unwrap(),
+                )
+            };
+            let calls = |calls: &[(u8, u8)]| -> F {
+                DeviceFilter::Calls(
+                    calls
+                        .iter()
+                        .copied()
+                        .collect::<BTreeSet<_>>()
+                        .try_into()
+                        .unwrap(),
+                )
+            };
+            let prefix = |bytes: &[u8]| ArgsFilter::Prefix(bytes.to_vec().try_into().unwrap());
+            let cashier = scoped(vec![pay(50)], Some((10, 20)));
+
+            assert!(F::Admin.is_superset_of(&cashier));
+            assert!(!cashier.is_superset_of(&F::Admin));
+
+            // Pallets/Calls can delegate scoped rules within them
+            assert!(set(&[10]).is_superset_of(&cashier));
+            assert!(!set(&[0]).is_superset_of(&cashier));
+            assert!(calls(&[(10, 3)]).is_superset_of(&cashier));
+            assert!(!calls(&[(10, 3)])
+                .is_superset_of(&scoped(vec![rule(10, None, ArgsFilter::Any, None)], None)));
+
+            // Spend can only delegate spend-limited rules within its limits
+            assert!(spend_filter(50, None).is_superset_of(&cashier));
+            assert!(!spend_filter(40, None).is_superset_of(&cashier));
+            assert!(!spend_filter(50, None).is_superset_of(&scoped(
+                vec![rule(10, Some(3), ArgsFilter::Any, None)],
+                None
+            )));
+            assert!(!cashier.is_superset_of(&spend_filter(50, None)));
+
+            // Scoped can delegate narrower rules, within its window
+            assert!(cashier.is_superset_of(&scoped(vec![pay(20)], Some((12, 15)))));
+            assert!(cashier.is_superset_of(&scoped(
+                vec![rule(10, Some(3), prefix(&[1, 2]), Some(50))],
+                Some((10, 20))
+            )));
+            assert!(!cashier.is_superset_of(&scoped(vec![pay(60)], Some((12, 15)))));
+            assert!(!cashier.is_superset_of(&scoped(vec![pay(20)], Some((5, 15)))));
+            assert!(!cashier.is_superset_of(&scoped(vec![pay(20)], None)));
+            assert!(!cashier.is_superset_of(&scoped(
+                vec![rule(10, Some(3), ArgsFilter::Any, None)],
+                Some((12, 15))
+            )));
+            assert!(!cashier.is_superset_of(&scoped(
+                vec![rule(10, None, ArgsFilter::Any, Some(20))],
+                Some((12, 15))
+            )));
+
+            // Arguments must be covered as well
+            let narrow = scoped(vec![rule(10, Some(3), prefix(&[1, 2]), None)], None);
+            assert!(narrow.is_superset_of(&scoped(
+                vec![rule(10, Some(3), prefix(&[1, 2, 3]), None)],
+                None
+            )));
+            assert!(
+                !narrow.is_superset_of(&scoped(vec![rule(10, Some(3), prefix(&[1]), None)], None))
+            );
+            assert!(!narrow.is_superset_of(&scoped(
+                vec![rule(10, Some(3), ArgsFilter::Hash([0; 32]), None)],
+                None
+            )));
+
+            // Scoped can only delegate Pallets/Calls through unrestricted rules that never expire
+            let unrestricted = scoped(vec![rule(10, None, ArgsFilter::Any, None)], None);
+            assert!(unrestricted.is_superset_of(&set(&[10])));
+            assert!(unrestricted.is_superset_of(&calls(&[(10, 3)])));
+            assert!(!unrestricted.is_superset_of(&set(&[0])));
+            assert!(!narrow.is_superset_of(&calls(&[(10, 3)])));
+            assert!(
+                !scoped(vec![rule(10, None, ArgsFilter::Any, None)], Some((0, 100)))
+                    .is_superset_of(&calls(&[(10, 3)]))
+            );
+        })
+    }
+}
+
+mod approvals {
+    use super::*;
+    use crate::{
+        ApprovalConsiderations, ApprovalPolicies, ApprovalPolicy, ApprovalPolicyOf, DeviceFilters,
+        Proposals,
+    };
+    use frame_support::storage::StorageDoubleMap as _;
+    use nested_calls::{batch, remark};
+    use sp_runtime::traits::Hash as _;
+    use spend_budgets::{setup_with_spend_device, spent, transfer};
+
+    const TIMEOUT: u32 = 5;
+
+    /// Transfers of 50 or more require approvals from both devices.
+    fn policy(calls: Vec<(u8, Option<u8>)>) -> ApprovalPolicyOf<Test> {
+        ApprovalPolicy {
+            threshold: 2,
+            timeout: TIMEOUT,
+            calls: calls.try_into().unwrap(),
+            spends: vec![((), 50)].try_into().unwrap(),
+        }
+    }
+
+    fn with_device(device_id: DeviceId, call: RuntimeCall) -> Result<(), DispatchError> {
+        dispatch::authenticate(
+            device_id,
+            PassCredential::AuthenticatorAAuthenticator(authenticator_a::Credential {
+                user_id: AccountNameA::get(),
+                challenge: authenticator_a::Authenticator::generate(&(), &[]),
+            }),
+            call,
+        )
+        .map_err(|_| DispatchError::Other("invalid transaction"))?
+        .map(|_| ())
+        .map_err(|e| e.error)
+    }
+
+    pub(super) fn approve(call: RuntimeCall) -> RuntimeCall {
+        crate::Call::approve_call {
+            call: Box::new(call),
+        }
+        .into()
+    }
+
+    fn call_hash(call: &RuntimeCall) -> <Test as frame_system::Config>::Hash {
+        <Test as frame_system::Config>::Hashing::hash_of(call)
+    }
+
+    pub(super) fn setup_with_policy() -> sp_io::TestExternalities {
+        let mut t = setup_with_spend_device();
+        t.execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::set_approval_policy(
+                RuntimeOrigin::signed(Address::get()),
+                Some(policy(vec![(0, Some(7))]))
+            ));
+            crate::AuthenticatedDevice::<Test>::kill();
+        });
+        t
+    }
+
+    #[test]
+    fn set_approval_policy_validates_policy() {
+        setup_with_spend_device().execute_with(|| {
+            // Only Admin devices can set the policy
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::set_approval_policy(
+                    RuntimeOrigin::signed(Address::get()),
+                    Some(policy(vec![]))
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            for (threshold, timeout) in [(1, TIMEOUT), (3, TIMEOUT), (2, 0)] {
+                assert_noop!(
+                    Pass::set_approval_policy(
+                        RuntimeOrigin::signed(Address::get()),
+                        Some(ApprovalPolicy {
+                            threshold,
+                            timeout,
+                            ..policy(vec![])
+                        })
+                    ),
+                    Error::<Test>::InvalidApprovalPolicy
+                );
+            }
+            assert_noop!(
+                Pass::set_approval_policy(RuntimeOrigin::signed(Address::get()), None),
+                Error::<Test>::ApprovalPolicyNotFound
+            );
+
+            assert_ok!(Pass::set_approval_policy(
+                RuntimeOrigin::signed(Address::get()),
+                Some(policy(vec![]))
+            ));
+            System::assert_has_event(
+                Event::<Test>::ApprovalPolicySet {
+                    who: Address::get(),
+                    threshold: 2,
+                }
+                .into(),
+            );
+            assert_eq!(
+                ApprovalConsiderations::<Test>::get(Address::get()).map(|(_, count)| count),
+                Some(1)
+            );
+
+            // Changing an existing policy requires approvals
+            assert_noop!(
+                Pass::set_approval_policy(RuntimeOrigin::signed(Address::get()), None),
+                Error::<Test>::ApprovalRequired
+            );
+        })
+    }
+
+    #[test]
+    fn sensitive_calls_require_approvals() {
+        setup_with_policy().execute_with(|| {
+            assert_noop!(
+                with_device(THE_DEVICE, transfer(CHARLIE, 50)),
+                DispatchError::Other("invalid transaction")
+            );
+            assert_noop!(
+                with_device(THE_DEVICE, remark()),
+                DispatchError::Other("invalid transaction")
+            );
+            // Nested calls are inspected as well
+            assert_noop!(
+                with_device(
+                    THE_DEVICE,
+                    batch(vec![transfer(CHARLIE, 30), transfer(CHARLIE, 30)])
+                ),
+                DispatchError::Other("invalid transaction")
+            );
+
+            assert_ok!(with_device(THE_DEVICE, transfer(CHARLIE, 49)));
+        })
+    }
+
+    #[test]
+    fn approved_call_is_dispatched() {
+        setup_with_policy().execute_with(|| {
+            let call = transfer(CHARLIE, 60);
+            let call_hash = call_hash(&call);
+            let balance = Balances::free_balance(CHARLIE);
+
+            assert_ok!(with_device(THE_DEVICE, approve(call.clone())));
+            System::assert_has_event(
+                Event::<Test>::CallProposed {
+                    who: Address::get(),
+                    call_hash,
+                    expires_at: 1 + TIMEOUT as u64,
+                }
+                .into(),
+            );
+            assert!(Proposals::<Test>::contains_key(Address::get(), call_hash));
+            assert_eq!(
+                ApprovalConsiderations::<Test>::get(Address::get()).map(|(_, count)| count),
+                Some(2)
+            );
+            assert_eq!(Balances::free_balance(CHARLIE), balance);
+
+            assert_eq!(
+                with_device(THE_DEVICE, approve(call.clone())),
+                Err(Error::<Test>::AlreadyApproved.into())
+            );
+
+            assert_ok!(with_device(OTHER_DEVICE, approve(call)));
+            System::assert_has_event(
+                Event::<Test>::ProposalExecuted {
+                    who: Address::get(),
+                    call_hash,
+                    result: Ok(()),
+                }
+                .into(),
+            );
+            assert_eq!(Balances::free_balance(CHARLIE), balance + 60);
+            assert!(!Proposals::<Test>::contains_key(Address::get(), call_hash));
+            assert_eq!(
+                ApprovalConsiderations::<Test>::get(Address::get()).map(|(_, count)| count),
+                Some(1)
+            );
+            // The spend is charged against the budget of the proposing device
+            assert_eq!(spent(crate::Spender::Device(OTHER_DEVICE)), None);
+        })
+    }
+
+    #[test]
+    fn spends_are_only_charged_once_executed() {
+        setup_with_policy().execute_with(|| {
+            let call = transfer(CHARLIE, 60);
+            let balance = Balances::free_balance(CHARLIE);
+
+            // Proposals that never execute don't spend the budget of their approvers
+            assert_ok!(with_device(OTHER_DEVICE, approve(call.clone())));
+            assert_eq!(spent(crate::Spender::Device(OTHER_DEVICE)), None);
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_ok!(Pass::cancel_proposal(
+                RuntimeOrigin::signed(Address::get()),
+                call_hash(&call)
+            ));
+            assert_eq!(spent(crate::Spender::Device(OTHER_DEVICE)), None);
+
+            assert_ok!(with_device(OTHER_DEVICE, approve(call.clone())));
+            run_to(1 + TIMEOUT as u64);
+            assert_ok!(Pass::remove_expired_proposal(
+                RuntimeOrigin::signed(CHARLIE),
+                Address::get(),
+                call_hash(&call)
+            ));
+            assert_eq!(spent(crate::Spender::Device(OTHER_DEVICE)), None);
+
+            // Once executed, the spend is charged against the proposer's budget only
+            assert_ok!(with_device(OTHER_DEVICE, approve(call.clone())));
+            assert_ok!(with_device(THE_DEVICE, approve(call)));
+            assert_eq!(Balances::free_balance(CHARLIE), balance + 60);
+            assert_eq!(spent(crate::Spender::Device(OTHER_DEVICE)), Some((0, 60)));
+            assert_eq!(spent(crate::Spender::Device(THE_DEVICE)), None);
+        })
+    }
+
+    #[test]
+    fn approving_devices_must_be_allowed_to_dispatch_the_call() {
+        setup_with_policy().execute_with(|| {
+            let call = transfer(CHARLIE, 120);
+            assert_ok!(with_device(THE_DEVICE, approve(call.clone())));
+            assert_eq!(
+                with_device(OTHER_DEVICE, approve(call)),
+                Err(Error::<Test>::CallNotAllowed.into())
+            );
+
+            // Approvals must come from a device
+            assert_noop!(
+                Pass::approve_call(RuntimeOrigin::signed(Address::get()), Box::new(remark())),
+                Error::<Test>::NotAuthenticatedByDevice
+            );
+        })
+    }
+
+    #[test]
+    fn proposals_expire() {
+        setup_with_policy().execute_with(|| {
+            let call = transfer(CHARLIE, 60);
+            let call_hash = call_hash(&call);
+            assert_ok!(with_device(THE_DEVICE, approve(call.clone())));
+
+            run_to(TIMEOUT as u64);
+            assert_noop!(
+                Pass::remove_expired_proposal(
+                    RuntimeOrigin::signed(CHARLIE),
+                    Address::get(),
+                    call_hash
+                ),
+                Error::<Test>::ProposalNotExpired
+            );
+
+            // Approving an expired proposal starts it over
+            run_to(1 + TIMEOUT as u64);
+            assert_ok!(with_device(OTHER_DEVICE, approve(call)));
+            assert_eq!(
+                Proposals::<Test>::get(Address::get(), call_hash).map(|p| p.approvals.into_inner()),
+                Some(vec![OTHER_DEVICE])
+            );
+
+            run_to(1 + 2 * TIMEOUT as u64);
+            assert_ok!(Pass::remove_expired_proposal(
+                RuntimeOrigin::signed(CHARLIE),
+                Address::get(),
+                call_hash
+            ));
+            System::assert_has_event(
+                Event::<Test>::ProposalCancelled {
+                    who: Address::get(),
+                    call_hash,
+                }
+                .into(),
+            );
+            assert_eq!(
+                ApprovalConsiderations::<Test>::get(Address::get()).map(|(_, count)| count),
+                Some(1)
+            );
+        })
+    }
+
+    #[test]
+    fn only_approvers_can_cancel_proposals() {
+        setup_with_policy().execute_with(|| {
+            let call = transfer(CHARLIE, 60);
+            let call_hash = call_hash(&call);
+            assert_ok!(with_device(THE_DEVICE, approve(call)));
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::cancel_proposal(RuntimeOrigin::signed(Address::get()), call_hash),
+                Error::<Test>::NotApprover
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::cancel_proposal(
+                RuntimeOrigin::signed(Address::get()),
+                call_hash
+            ));
+            assert!(!Proposals::<Test>::contains_key(Address::get(), call_hash));
+        })
+    }
+
+    #[test]
+    fn max_pending_proposals_works() {
+        setup_with_policy().execute_with(|| {
+            assert_ok!(with_device(THE_DEVICE, approve(transfer(CHARLIE, 60))));
+            assert_ok!(with_device(THE_DEVICE, approve(transfer(CHARLIE, 70))));
+            assert_eq!(
+                with_device(THE_DEVICE, approve(transfer(CHARLIE, 80))),
+                Err(Error::<Test>::MaxProposalsExceeded.into())
+            );
+        })
+    }
+
+    #[test]
+    fn policy_changes_require_approvals() {
+        setup_with_policy().execute_with(|| {
+            DeviceFilters::<Test>::insert(Address::get(), OTHER_DEVICE, DeviceFilter::Admin);
+            assert_ok!(with_device(THE_DEVICE, approve(transfer(CHARLIE, 60))));
+
+            let remove_policy: RuntimeCall =
+                crate::Call::set_approval_policy { policy: None }.into();
+            assert_ok!(with_device(THE_DEVICE, approve(remove_policy.clone())));
+            assert_ok!(with_device(OTHER_DEVICE, approve(remove_policy)));
+
+            System::assert_has_event(
+                Event::<Test>::ApprovalPolicyRemoved {
+                    who: Address::get(),
+                }
+                .into(),
+            );
+            assert!(!ApprovalPolicies::<Test>::contains_key(Address::get()));
+            // Pending proposals are cancelled, and all deposits released
+            assert!(!Proposals::<Test>::contains_prefix(Address::get()));
+            assert!(!ApprovalConsiderations::<Test>::contains_key(Address::get()));
+            assert_eq!(crate::ExecutingProposal::<Test>::get(), None);
+
+            assert_ok!(with_device(THE_DEVICE, transfer(CHARLIE, 60)));
+        })
+    }
+
+    #[test]
+    fn kill_account_requires_approvals() {
+        setup_with_policy().execute_with(|| {
+            DeviceFilters::<Test>::insert(Address::get(), OTHER_DEVICE, DeviceFilter::Admin);
+            let balance = Balances::free_balance(CHARLIE);
+
+            // A single device can't sweep the account
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_noop!(
+                Pass::kill_account(RuntimeOrigin::signed(Address::get()), CHARLIE),
+                Error::<Test>::ApprovalRequired
+            );
+            crate::AuthenticatedDevice::<Test>::kill();
+
+            let kill_account: RuntimeCall = crate::Call::kill_account {
+                beneficiary: CHARLIE,
+            }
+            .into();
+            assert_ok!(with_device(THE_DEVICE, approve(kill_account.clone())));
+            assert_ok!(with_device(OTHER_DEVICE, approve(kill_account)));
+
+            System::assert_has_event(
+                Event::<Test>::AccountKilled {
+                    who: Address::get(),
+                }
+                .into(),
+            );
+            assert!(Balances::free_balance(CHARLIE) > balance);
+            assert_eq!(crate::ExecutingProposal::<Test>::get(), None);
+        })
+    }
+
+    #[test]
+    fn kill_account_removes_approvals() {
+        setup_with_policy().execute_with(|| {
+            DeviceFilters::<Test>::insert(Address::get(), OTHER_DEVICE, DeviceFilter::Admin);
+            assert_ok!(with_device(THE_DEVICE, approve(transfer(CHARLIE, 60))));
+
+            let kill_account: RuntimeCall = crate::Call::kill_account {
+                beneficiary: CHARLIE,
+            }
+            .into();
+            assert_ok!(with_device(THE_DEVICE, approve(kill_account.clone())));
+            assert_ok!(with_device(OTHER_DEVICE, approve(kill_account)));
+            assert!(!ApprovalPolicies::<Test>::contains_key(Address::get()));
+            assert!(!Proposals::<Test>::contains_prefix(Address::get()));
+            assert!(!ApprovalConsiderations::<Test>::contains_key(Address::get()));
+        })
+    }
+}
+
+mod update_device {
+    use super::*;
+    use crate::{DeviceFilters, DeviceInfos};
+    use spend_budgets::transfer;
+
+    /// An account with an `Admin` device, and another one that can only remark.
+    fn setup_with_restricted_device() -> sp_io::TestExternalities {
+        let mut t = prepare(AccountNameA::get());
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(&Address::get(), 1_000_000));
+            assert_ok!(Balances::mint_into(&CHARLIE, ExistentialDeposit::get()));
+
+            run_to(3);
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            crate::AuthenticatedDevice::<Test>::kill();
+        });
+        t
+    }
+
+    fn label(bytes: &[u8]) -> crate::DeviceLabelOf<Test> {
+        bytes.to_vec().try_into().unwrap()
+    }
+
+    #[test]
+    fn update_device_filter_works() {
+        setup_with_restricted_device().execute_with(|| {
+            let filter = DeviceFilter::Pallets(
+                [0u8, 10u8]
+                    .into_iter()
+                    .collect::<alloc::collections::BTreeSet<_>>()
+                    .try_into()
+                    .unwrap(),
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::update_device_filter(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE,
+                filter.clone()
+            ));
+            System::assert_has_event(
+                Event::<Test>::DeviceFilterUpdated {
+                    who: Address::get(),
+                    device_id: OTHER_DEVICE,
+                    filter: filter.clone(),
+                }
+                .into(),
+            );
+            assert_eq!(
+                DeviceFilters::<Test>::get(Address::get(), OTHER_DEVICE),
+                Some(filter)
+            );
+
+            // The updated filter applies right away
+            assert_ok!(spend_budgets::spend_with_device(transfer(CHARLIE, 10)));
+        })
+    }
+
+    #[test]
+    fn update_device_filter_cannot_escalate() {
+        setup_with_restricted_device().execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+
+            // Cannot upgrade itself
+            assert_noop!(
+                Pass::update_device_filter(
+                    RuntimeOrigin::signed(Address::get()),
+                    OTHER_DEVICE,
+                    DeviceFilter::Admin
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+            // Nor downgrade a device with more permissions
+            assert_noop!(
+                Pass::update_device_filter(
+                    RuntimeOrigin::signed(Address::get()),
+                    THE_DEVICE,
+                    remark_only_filter()
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+            assert_noop!(
+                Pass::update_device_filter(
+                    RuntimeOrigin::signed(Address::get()),
+                    THIRD_DEVICE,
+                    remark_only_filter()
+                ),
+                Error::<Test>::DeviceNotFound
+            );
+
+            // Restricted devices can downgrade themselves
+            let nothing = DeviceFilter::Calls(Default::default());
+            assert_ok!(Pass::update_device_filter(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE,
+                nothing.clone()
+            ));
+            assert_eq!(
+                DeviceFilters::<Test>::get(Address::get(), OTHER_DEVICE),
+                Some(nothing)
+            );
+        })
+    }
+
+    #[test]
+    fn device_metadata_is_tracked() {
+        setup_with_restricted_device().execute_with(|| {
+            let info = DeviceInfos::<Test>::get(Address::get(), OTHER_DEVICE).unwrap();
+            assert_eq!(info.added_at, 3);
+            assert_eq!(info.last_used, None);
+            assert_eq!(info.label, None);
+
+            run_to(5);
+            assert_ok!(spend_budgets::spend_with_device(nested_calls::remark()));
+            assert_eq!(
+                DeviceInfos::<Test>::get(Address::get(), OTHER_DEVICE)
+                    .unwrap()
+                    .last_used,
+                Some(5)
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::set_device_metadata(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE,
+                Some(label(b"Cashier")),
+                Some(label(b"Android")),
+            ));
+            System::assert_has_event(
+                Event::<Test>::DeviceMetadataSet {
+                    who: Address::get(),
+                    device_id: OTHER_DEVICE,
+                }
+                .into(),
+            );
+            let info = DeviceInfos::<Test>::get(Address::get(), OTHER_DEVICE).unwrap();
+            assert_eq!(info.label, Some(label(b"Cashier")));
+            assert_eq!(info.platform, Some(label(b"Android")));
+            assert_eq!(info.added_at, 3);
+
+            // Restricted devices cannot relabel devices with more permissions
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::set_device_metadata(
+                    RuntimeOrigin::signed(Address::get()),
+                    THE_DEVICE,
+                    Some(label(b"Old phone")),
+                    None,
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::remove_device(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE
+            ));
+            assert!(!DeviceInfos::<Test>::contains_key(
+                Address::get(),
+                OTHER_DEVICE
+            ));
+        })
+    }
+}
+
+mod runtime_api {
+    use super::*;
+    use crate::{DeviceDetails, SessionDetails};
+    use approvals::{approve, setup_with_policy};
+    use nested_calls::remark;
+    use spend_budgets::{setup_with_spend_device, spend_filter, spend_with_device, transfer};
+
+    #[test]
+    fn devices_are_listed_with_their_filters() {
+        setup_with_spend_device().execute_with(|| {
+            assert_eq!(Pass::address_for(AccountNameA::get()), Address::get());
+
+            let mut devices = Pass::devices(Address::get());
+            devices.sort_by_key(|device| device.device_id);
+            assert_eq!(
+                devices
+                    .into_iter()
+                    .map(
+                        |DeviceDetails {
+                             device_id,
+                             filter,
+                             info,
+                         }| (
+                            device_id,
+                            filter,
+                            info.map(|info| info.added_at)
+                        )
+                    )
+                    .collect::<Vec<_>>(),
+                vec![
+                    (THE_DEVICE, Some(DeviceFilter::Admin), Some(1)),
+                    (
+                        OTHER_DEVICE,
+                        Some(spend_filter(100, Some((150, 10)))),
+                        Some(1)
+                    ),
+                ]
+            );
+            assert!(Pass::devices(CHARLIE).is_empty());
+        })
+    }
+
+    #[test]
+    fn live_sessions_are_listed() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_session_key(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER,
+                Some(DURATION / 2),
+                remark_only_filter(),
+            ));
+
+            let until = System::block_number() + DURATION / 2;
+            assert_eq!(
+                Pass::sessions(Address::get()),
+                vec![SessionDetails {
+                    session_key: OTHER,
+                    until,
+                    filter: remark_only_filter(),
+                }]
+            );
+
+            run_to(until + 1);
+            assert!(Pass::sessions(Address::get()).is_empty());
+        })
+    }
+
+    #[test]
+    fn can_dispatch_dry_runs_device_checks() {
+        setup_with_spend_device().execute_with(|| {
+            assert_ok!(Pass::can_dispatch(
+                Address::get(),
+                THE_DEVICE,
+                transfer(CHARLIE, 1_000)
+            ));
+            assert_ok!(Pass::can_dispatch(
+                Address::get(),
+                OTHER_DEVICE,
+                transfer(CHARLIE, 100)
+            ));
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), THIRD_DEVICE, remark()),
+                Error::<Test>::DeviceNotFound
+            );
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), OTHER_DEVICE, remark()),
+                Error::<Test>::CallNotAllowed
+            );
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), OTHER_DEVICE, transfer(CHARLIE, 101)),
+                Error::<Test>::CallNotAllowed
+            );
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), OTHER_DEVICE, approve(remark())),
+                Error::<Test>::ApprovalPolicyNotFound
+            );
+
+            // Budgets account for what the device already spent
+            assert_ok!(spend_with_device(transfer(CHARLIE, 100)));
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), OTHER_DEVICE, transfer(CHARLIE, 100)),
+                Error::<Test>::SpendBudgetExceeded
+            );
+        })
+    }
+
+    #[test]
+    fn can_dispatch_checks_approval_policies() {
+        setup_with_policy().execute_with(|| {
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), THE_DEVICE, remark()),
+                Error::<Test>::ApprovalRequired
+            );
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), THE_DEVICE, transfer(CHARLIE, 50)),
+                Error::<Test>::ApprovalRequired
+            );
+            assert_ok!(Pass::can_dispatch(
+                Address::get(),
+                THE_DEVICE,
+                transfer(CHARLIE, 49)
+            ));
+            assert_ok!(Pass::can_dispatch(
+                Address::get(),
+                THE_DEVICE,
+                approve(remark())
+            ));
+            assert_noop!(
+                Pass::can_dispatch(Address::get(), OTHER_DEVICE, approve(remark())),
+                Error::<Test>::CallNotAllowed
+            );
+        })
+    }
+}
+
+mod registrars {
+    use super::*;
+    use crate::{
+        AccountRegistrars, DeviceDepositSponsors, Devices, HoldReason, RegistrarInfo,
+        RegistrarStatus, Registrars,
+    };
+    use frame_support::traits::fungible::InspectHold;
+
+    fn register(user: HashedUserId, device_id: DeviceId) -> sp_runtime::DispatchResult {
+        Pass::register(
+            RuntimeOrigin::signed(SIGNER),
+            user,
+            PassDeviceAttestation::AuthenticatorAAuthenticator(
+                authenticator_a::DeviceAttestation {
+                    device_id,
+                    challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                },
+            ),
+        )
+    }
+
+    fn set_registrar(info: RegistrarInfo) {
+        assert_ok!(Pass::set_registrar(
+            RuntimeOrigin::root(),
+            SIGNER,
+            Some(info.clone())
+        ));
+        System::assert_has_event(
+            Event::<Test>::RegistrarSet {
+                registrar: SIGNER,
+                info,
+            }
+            .into(),
+        );
+    }
+
+    fn held_for_devices(who: &AccountId) -> Balance {
+        Balances::balance_on_hold(&HoldReason::AccountDevices.into(), who)
+    }
+
+    /// What stays held once a consideration is back to a single, free item.
+    fn base_deposit() -> Balance {
+        ItemStoragePrice::convert(Footprint::default())
+    }
+
+    fn new_test_ext_with_funded_registrar() -> sp_io::TestExternalities {
+        let mut t = new_test_ext();
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(&SIGNER, 1_000_000));
+        });
+        t
+    }
+
+    #[test]
+    fn only_registrars_origin_can_set_registrars() {
+        new_test_ext_with_funded_registrar().execute_with(|| {
+            let info = RegistrarInfo {
+                max_registrations: None,
+                status: RegistrarStatus::Suspended,
+                sponsor_device_deposits: false,
+            };
+            assert_noop!(
+                Pass::set_registrar(RuntimeOrigin::signed(SIGNER), SIGNER, Some(info.clone())),
+                DispatchError::BadOrigin
+            );
+
+            set_registrar(info.clone());
+            assert_eq!(Registrars::<Test>::get(SIGNER), Some(info));
+
+            assert_ok!(Pass::set_registrar(RuntimeOrigin::root(), SIGNER, None));
+            System::assert_has_event(Event::<Test>::RegistrarRemoved { registrar: SIGNER }.into());
+            assert!(!Registrars::<Test>::contains_key(SIGNER));
+        })
+    }
+
+    #[test]
+    fn suspended_registrars_cannot_register_accounts() {
+        new_test_ext_with_funded_registrar().execute_with(|| {
+            assert_ok!(register(AccountNameA::get(), THE_DEVICE));
+
+            set_registrar(RegistrarInfo {
+                max_registrations: None,
+                status: RegistrarStatus::Suspended,
+                sponsor_device_deposits: false,
+            });
+            assert_noop!(
+                register(AccountNameB::get(), OTHER_DEVICE),
+                Error::<Test>::RegistrarSuspended
+            );
+
+            // Existing accounts are not affected
+            assert!(Devices::<Test>::contains_key(Address::get(), THE_DEVICE));
+            assert_eq!(
+                AccountRegistrars::<Test>::get(Address::get()),
+                Some((SIGNER, AccountNameA::get()))
+            );
+
+            // Other registrars can still register accounts
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                AccountNameB::get(),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+            ));
+        })
+    }
+
+    #[test]
+    fn registrars_cannot_exceed_their_quota() {
+        new_test_ext_with_funded_registrar().execute_with(|| {
+            set_registrar(RegistrarInfo {
+                max_registrations: Some(1),
+                status: RegistrarStatus::Active,
+                sponsor_device_deposits: false,
+            });
+
+            assert_ok!(register(AccountNameA::get(), THE_DEVICE));
+            assert_noop!(
+                register(AccountNameB::get(), OTHER_DEVICE),
+                Error::<Test>::MaxRegistrationsExceeded
+            );
+
+            set_registrar(RegistrarInfo {
+                max_registrations: Some(2),
+                status: RegistrarStatus::Active,
+                sponsor_device_deposits: false,
+            });
+            assert_ok!(register(AccountNameB::get(), OTHER_DEVICE));
+        })
+    }
+
+    #[test]
+    fn sponsoring_registrars_pay_for_device_deposits() {
+        new_test_ext_with_funded_registrar().execute_with(|| {
+            set_registrar(RegistrarInfo {
+                max_registrations: None,
+                status: RegistrarStatus::Active,
+                sponsor_device_deposits: true,
+            });
+            assert_ok!(register(AccountNameA::get(), THE_DEVICE));
+            assert_eq!(
+                DeviceDepositSponsors::<Test>::get(Address::get()),
+                Some(SIGNER)
+            );
+
+            // The second device takes a deposit, paid by the registrar
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            assert!(held_for_devices(&SIGNER) > 0);
+            assert_eq!(held_for_devices(&Address::get()), 0);
+
+            // Turning sponsorship off doesn't affect accounts already registered
+            set_registrar(RegistrarInfo {
+                max_registrations: None,
+                status: RegistrarStatus::Active,
+                sponsor_device_deposits: false,
+            });
+            assert_ok!(Pass::remove_device(
+                RuntimeOrigin::signed(Address::get()),
+                OTHER_DEVICE
+            ));
+            assert_eq!(held_for_devices(&SIGNER), base_deposit());
+
+            assert_ok!(Pass::kill_account(
+                RuntimeOrigin::signed(Address::get()),
+                SIGNER
+            ));
+            assert!(!DeviceDepositSponsors::<Test>::contains_key(Address::get()));
+        })
+    }
+}
+
+mod user_ids {
+    use super::*;
+    use crate::mock::RootAccount;
+    use crate::{AccountRegistrars, AccountUserIds, RegisteredAccounts, UserIdAddresses};
+
+    fn credential(user_id: HashedUserId) -> PassCredential {
+        PassCredential::AuthenticatorAAuthenticator(authenticator_a::Credential {
+            user_id,
+            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+        })
+    }
+
+    #[test]
+    fn registering_binds_the_user_id() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_eq!(
+                UserIdAddresses::<Test>::get(AccountNameA::get()),
+                Some(Address::get())
+            );
+            assert_eq!(
+                AccountUserIds::<Test>::get(Address::get()),
+                Some(AccountNameA::get())
+            );
+        })
+    }
+
+    #[test]
+    fn admin_devices_can_rotate_the_user_id() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::rotate_user_id(
+                RuntimeOrigin::signed(Address::get()),
+                AccountNameB::get()
+            ));
+            System::assert_has_event(
+                Event::<Test>::UserIdRotated {
+                    who: Address::get(),
+                    user_id: AccountNameB::get(),
+                }
+                .into(),
+            );
+
+            // The address stays the same
+            assert_eq!(Pass::address_for(AccountNameB::get()), Address::get());
+            assert_eq!(
+                Pass::authenticate(&THE_DEVICE, &credential(AccountNameB::get()), &[]),
+                Ok(Address::get())
+            );
+            assert_noop!(
+                Pass::authenticate(&THE_DEVICE, &credential(AccountNameA::get()), &[]),
+                Error::<Test>::AccountNotFound
+            );
+
+            // Registrar records follow the new user id
+            assert_eq!(
+                AccountRegistrars::<Test>::get(Address::get()),
+                Some((RootAccount::get(), AccountNameB::get()))
+            );
+            assert!(!RegisteredAccounts::<Test>::contains_key(
+                RootAccount::get(),
+                AccountNameA::get()
+            ));
+            assert!(RegisteredAccounts::<Test>::contains_key(
+                RootAccount::get(),
+                AccountNameB::get()
+            ));
+            assert!(!UserIdAddresses::<Test>::contains_key(AccountNameA::get()));
+
+            // The previous user id can't register the same address again
+            assert_noop!(
+                Pass::register(
+                    RuntimeOrigin::root(),
+                    AccountNameA::get(),
+                    PassDeviceAttestation::AuthenticatorAAuthenticator(
+                        authenticator_a::DeviceAttestation {
+                            device_id: OTHER_DEVICE,
+                            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                        }
+                    ),
+                ),
+                Error::<Test>::AccountAlreadyRegistered
+            );
+        })
+    }
+
+    #[test]
+    fn rotating_requires_an_admin_device_and_an_unused_user_id() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_ok!(Balances::mint_into(&Address::get(), 1_000_000));
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                AccountNameB::get(),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: THIRD_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+            ));
+
+            assert_noop!(
+                Pass::rotate_user_id(RuntimeOrigin::signed(Address::get()), AccountNameB::get()),
+                Error::<Test>::UserIdInUse
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::rotate_user_id(RuntimeOrigin::signed(Address::get()), [9u8; 32]),
+                Error::<Test>::PermissionEscalation
+            );
+        })
+    }
+
+    #[test]
+    fn versioned_address_generators_keep_previous_schemes() {
+        use crate::{AddressGenerator, VersionedAddressGenerator};
+
+        struct NewScheme;
+        impl AddressGenerator<Test, ()> for NewScheme {
+            fn generate_address_at(_: u32, id: HashedUserId) -> AccountId {
+                AccountId::new(sp_core::blake2_256(&id))
+            }
+        }
+        type Generator = VersionedAddressGenerator<1, (), NewScheme>;
+
+        let user = AccountNameA::get();
+        assert_eq!(<Generator as AddressGenerator<Test, ()>>::VERSION, 1);
+        assert_eq!(
+            <Generator as AddressGenerator<Test, ()>>::generate_address(user),
+            AccountId::new(sp_core::blake2_256(&user))
+        );
+        assert_eq!(
+            <Generator as AddressGenerator<Test, ()>>::generate_address_at(0, user),
+            <() as AddressGenerator<Test, ()>>::generate_address(user)
+        );
+    }
+
+    #[test]
+    fn migration_binds_registered_accounts() {
+        use crate::migration::MigrateV0ToV1;
+        use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+        prepare(AccountNameA::get()).execute_with(|| {
+            // Simulate v0 state, where only registrars kept a record of the user ids they
+            // registered, including ones whose account no longer exists
+            UserIdAddresses::<Test>::remove(AccountNameA::get());
+            AccountUserIds::<Test>::remove(Address::get());
+            AccountRegistrars::<Test>::remove(Address::get());
+            RegisteredAccounts::<Test>::insert(RootAccount::get(), AccountNameB::get(), ());
+            StorageVersion::new(0).put::<Pass>();
+
+            // Visits a registered account per step
+            let steps =
+                run_stepped_migration::<MigrateV0ToV1<Test>>(MigrateV0ToV1::<Test>::step_weight());
+            // Both registered accounts, and a last step finding there's nothing left
+            assert_eq!(steps, 3);
+
+            assert_eq!(
+                UserIdAddresses::<Test>::get(AccountNameA::get()),
+                Some(Address::get())
+            );
+            assert_eq!(
+                AccountUserIds::<Test>::get(Address::get()),
+                Some(AccountNameA::get())
+            );
+            assert_eq!(
+                AccountRegistrars::<Test>::get(Address::get()),
+                Some((RootAccount::get(), AccountNameA::get()))
+            );
+            assert!(!UserIdAddresses::<Test>::contains_key(AccountNameB::get()));
+            assert_eq!(Pass::on_chain_storage_version(), 1);
+        })
+    }
+}
+
+mod rate_limits {
+    use super::*;
+    use crate::{
+        extensions::RATE_LIMIT_EXCEEDED, DeviceRateLimit, DeviceRateLimits, DeviceTransactions,
+    };
+    use dispatch::authenticate;
+    use frame_support::dispatch::GetDispatchInfo;
+    use sp_runtime::{
+        traits::Applyable,
+        transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidity},
+    };
+
+    parameter_types! {
+        pub Call: RuntimeCall = RuntimeCall::System(frame_system::Call::remark {
+            remark: b"Hello, world".to_vec()
+        });
+    }
+
+    fn credential() -> PassCredential {
+        PassCredential::AuthenticatorAAuthenticator(authenticator_a::Credential {
+            user_id: AccountNameA::get(),
+            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+        })
+    }
+
+    fn setup_funded() -> sp_io::TestExternalities {
+        let mut t = prepare(AccountNameA::get());
+        t.execute_with(|| {
+            assert_ok!(Balances::mint_into(&Address::get(), Balance::MAX));
+        });
+        t
+    }
+
+    #[test]
+    fn device_transactions_are_rate_limited() {
+        setup_funded().execute_with(|| {
+            for _ in 0..5 {
+                assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            }
+            assert_eq!(
+                DeviceTransactions::<Test>::get(Address::get(), THE_DEVICE),
+                Some((0, 5))
+            );
+
+            assert_noop!(
+                authenticate(THE_DEVICE, credential(), Call::get()),
+                InvalidTransaction::Custom(RATE_LIMIT_EXCEEDED)
+            );
+        });
+    }
+
+    #[test]
+    fn rate_limit_resets_on_the_next_period() {
+        setup_funded().execute_with(|| {
+            for _ in 0..5 {
+                assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            }
+
+            run_to(9);
+            assert_noop!(
+                authenticate(THE_DEVICE, credential(), Call::get()),
+                InvalidTransaction::Custom(RATE_LIMIT_EXCEEDED)
+            );
+
+            run_to(10);
+            assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            assert_eq!(
+                DeviceTransactions::<Test>::get(Address::get(), THE_DEVICE),
+                Some((10, 1))
+            );
+        });
+    }
+
+    fn validate(counter: u32) -> TransactionValidity {
+        let call = Call::get();
+        let xt = CheckedExtrinsic {
+            format: ExtrinsicFormat::General(
+                0,
+                (
+                    PassAuthenticate::<Test>::from(THE_DEVICE, counter, credential()),
+                    fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+                        pallet_transaction_payment::ChargeTransactionPayment::from(0),
+                    ),
+                ),
+            ),
+            function: call.clone(),
+        };
+        xt.validate::<Test>(
+            TransactionSource::External,
+            &call.get_dispatch_info(),
+            call.encoded_size(),
+        )
+    }
+
+    #[test]
+    fn device_transactions_follow_each_other_by_their_counter() {
+        setup_funded().execute_with(|| {
+            // A transaction ahead of the next one waits for the previous counter...
+            let validity = validate(2).expect("transaction is valid; qed");
+            assert_eq!(
+                validity.requires,
+                vec![("PassAuthenticate", (Address::get(), THE_DEVICE, 0u64, 1u32)).encode()]
+            );
+            // ...and can't be included before it
+            let call = Call::get();
+            let xt = CheckedExtrinsic {
+                format: ExtrinsicFormat::General(
+                    0,
+                    (
+                        PassAuthenticate::<Test>::from(THE_DEVICE, 1, credential()),
+                        fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+                            pallet_transaction_payment::ChargeTransactionPayment::from(0),
+                        ),
+                    ),
+                ),
+                function: call.clone(),
+            };
+            assert_noop!(
+                xt.apply::<Test>(&call.get_dispatch_info(), call.encoded_size()),
+                InvalidTransaction::Future
+            );
+
+            // Counters already used are stale, and the pool can't hold more transactions than
+            // the rate limit allows
+            assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            assert_eq!(validate(0), Err(InvalidTransaction::Stale.into()));
+            assert_ok!(validate(4));
+            assert_eq!(
+                validate(5),
+                Err(InvalidTransaction::Custom(RATE_LIMIT_EXCEEDED).into())
+            );
+        });
+    }
+
+    #[test]
+    fn devices_might_have_rate_limits_of_their_own() {
+        setup_funded().execute_with(|| {
+            let limit = DeviceRateLimit {
+                max_transactions: 2,
+                period: 20,
+            };
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::set_device_rate_limit(
+                RuntimeOrigin::signed(Address::get()),
+                THE_DEVICE,
+                Some(limit),
+            ));
+            System::assert_has_event(
+                Event::<Test>::DeviceRateLimitSet {
+                    who: Address::get(),
+                    device_id: THE_DEVICE,
+                    limit: Some(limit),
+                }
+                .into(),
+            );
+
+            for _ in 0..2 {
+                assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            }
+            run_to(10);
+            assert_noop!(
+                authenticate(THE_DEVICE, credential(), Call::get()),
+                InvalidTransaction::Custom(RATE_LIMIT_EXCEEDED)
+            );
+            run_to(20);
+            assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+
+            // Back to the default rate limit
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::set_device_rate_limit(
+                RuntimeOrigin::signed(Address::get()),
+                THE_DEVICE,
+                None,
+            ));
+            assert!(!DeviceRateLimits::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+        });
+    }
+
+    #[test]
+    fn rate_limits_must_allow_transactions() {
+        setup_funded().execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            for (max_transactions, period) in [(0, 20), (2, 0)] {
+                assert_noop!(
+                    Pass::set_device_rate_limit(
+                        RuntimeOrigin::signed(Address::get()),
+                        THE_DEVICE,
+                        Some(DeviceRateLimit {
+                            max_transactions,
+                            period,
+                        }),
+                    ),
+                    Error::<Test>::InvalidRateLimit
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn only_admin_devices_set_rate_limits() {
+        setup_funded().execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::add_device(
+                RuntimeOrigin::signed(Address::get()),
+                PassDeviceAttestation::AuthenticatorAAuthenticator(
+                    authenticator_a::DeviceAttestation {
+                        device_id: OTHER_DEVICE,
+                        challenge: authenticator_a::Authenticator::generate(&(), &[]),
+                    }
+                ),
+                remark_only_filter(),
+            ));
+            let limit = Some(DeviceRateLimit {
+                max_transactions: 100,
+                period: 1,
+            });
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), OTHER_DEVICE));
+            assert_noop!(
+                Pass::set_device_rate_limit(
+                    RuntimeOrigin::signed(Address::get()),
+                    OTHER_DEVICE,
+                    limit,
+                ),
+                Error::<Test>::PermissionEscalation
+            );
+
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_noop!(
+                Pass::set_device_rate_limit(
+                    RuntimeOrigin::signed(Address::get()),
+                    THIRD_DEVICE,
+                    limit,
+                ),
+                Error::<Test>::DeviceNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn validity_is_tagged_per_device_and_lives_until_the_period_ends() {
+        setup_funded().execute_with(|| {
+            run_to(3);
+
+            let validity = validate(0).expect("transaction is valid; qed");
+            assert_eq!(validity.longevity, 7);
+            assert_eq!(
+                validity.provides,
+                vec![
+                    ("PassAuthenticate", (Address::get(), THE_DEVICE, 0u64, 0u32)).encode(),
+                    (
+                        "PassAuthenticate",
+                        (
+                            Address::get(),
+                            THE_DEVICE,
+                            credential().using_encoded(sp_core::blake2_256)
+                        )
+                    )
+                        .encode()
+                ]
+            );
+            assert!(validity.requires.is_empty());
+        });
+    }
+
+    #[test]
+    fn removing_a_device_clears_its_rate_limit() {
+        setup_funded().execute_with(|| {
+            assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+            assert!(DeviceTransactions::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+            DeviceRateLimits::<Test>::insert(
+                Address::get(),
+                THE_DEVICE,
+                DeviceRateLimit {
+                    max_transactions: 1,
+                    period: 1,
+                },
+            );
+
+            assert_ok!(Pass::remove_device(
+                RuntimeOrigin::signed(Address::get()),
+                THE_DEVICE
+            ));
+            assert!(!DeviceTransactions::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+            assert!(!DeviceRateLimits::<Test>::contains_key(
+                Address::get(),
+                THE_DEVICE
+            ));
+        });
+    }
+}
+
+mod membership_origins {
+    use super::*;
+    use crate::origin::{EnsureCommunityFor, EnsureMemberOf, EnsureMembership};
+    use alloc::boxed::Box;
+    use frame_support::traits::{EnsureOrigin, EnsureOriginWithArg};
+    use sp_runtime::traits::Convert;
+
+    const COMMUNITY: u16 = 1;
+
+    parameter_types! {
+        pub static Assigned: Vec<(u16, u32, AccountId)> = vec![];
+    }
+
+    /// Members of [`COMMUNITY`] are `SIGNER` and the address of `AccountNameA`, along with the
+    /// accounts [`Assigned`] a membership.
+    struct Memberships;
+    impl fc_traits_memberships::Inspect<AccountId> for Memberships {
+        type Group = u16;
+        type Membership = u32;
+
+        fn user_memberships(
+            who: &AccountId,
+            maybe_group: Option<u16>,
+        ) -> Box<dyn Iterator<Item = (u16, u32)>> {
+            let is_member = *who == SIGNER || *who == Address::get();
+            let assigned = Assigned::get()
+                .into_iter()
+                .filter(|(_, _, member)| member == who)
+                .map(|(g, m, _)| (g, m))
+                .collect::<Vec<_>>();
+            Box::new(
+                is_member
+                    .then_some((COMMUNITY, 1))
+                    .into_iter()
+                    .chain(assigned)
+                    .filter(move |(g, _)| maybe_group.is_none_or(|group| *g == group)),
+            )
+        }
+
+        fn check_membership(who: &AccountId, _: &u32) -> Option<u16> {
+            Self::user_memberships(who, None).next().map(|(g, _)| g)
+        }
+
+        fn members_total(group: &u16) -> u32 {
+            2 + Assigned::get()
+                .iter()
+                .filter(|(g, _, _)| g == group)
+                .count() as u32
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl fc_traits_memberships::InspectEnumerable<AccountId> for Memberships {
+        fn group_available_memberships(group: &u16) -> Box<dyn Iterator<Item = u32>> {
+            Box::new(core::iter::once(
+                <Self as fc_traits_memberships::Inspect<AccountId>>::members_total(group) + 1,
+            ))
+        }
+
+        fn memberships_of(
+            who: &AccountId,
+            maybe_group: Option<u16>,
+        ) -> Box<dyn Iterator<Item = (u16, u32)>> {
+            <Self as fc_traits_memberships::Inspect<AccountId>>::user_memberships(who, maybe_group)
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl fc_traits_memberships::Manager<AccountId> for Memberships {
+        fn assign(group: &u16, m: &u32, who: &AccountId) -> Result<(), DispatchError> {
+            Assigned::mutate(|assigned| assigned.push((*group, *m, who.clone())));
+            Ok(())
+        }
+
+        fn release(group: &u16, m: &u32) -> Result<(), DispatchError> {
+            Assigned::mutate(|assigned| assigned.retain(|(g, n, _)| (g, n) != (group, m)));
+            Ok(())
+        }
+    }
+
+    parameter_types! {
+        pub const Community: u16 = COMMUNITY;
+    }
+
+    /// `OTHER` signs on behalf of [`COMMUNITY`].
+    struct CommunityOrigin;
+    impl EnsureOrigin<RuntimeOrigin> for CommunityOrigin {
+        type Success = u16;
+
+        fn try_origin(o: RuntimeOrigin) -> Result<u16, RuntimeOrigin> {
+            match o.clone().into() {
+                Ok(frame_system::RawOrigin::Signed(who)) if who == OTHER => Ok(COMMUNITY),
+                _ => Err(o),
+            }
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+            Ok(RuntimeOrigin::signed(OTHER))
+        }
+    }
+
+    struct CommunityAccount;
+    impl Convert<u16, AccountId> for CommunityAccount {
+        fn convert(_: u16) -> AccountId {
+            CHARLIE
+        }
+    }
+
+    type SelfRegistration = EnsureMemberOf<Test, (), Memberships, Community>;
+    type CommunityRegistration =
+        EnsureCommunityFor<Test, (), CommunityOrigin, Memberships, CommunityAccount>;
+    type Registration =
+        EnsureMembership<Test, (), Memberships, Community, CommunityOrigin, CommunityAccount>;
+
+    #[test]
+    fn members_can_register_themselves() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                SelfRegistration::try_origin(RuntimeOrigin::signed(SIGNER), &AccountNameB::get())
+                    .ok(),
+                Some(SIGNER)
+            );
+            assert!(SelfRegistration::try_origin(
+                RuntimeOrigin::signed(CHARLIE),
+                &AccountNameB::get()
+            )
+            .is_err());
+            assert!(
+                SelfRegistration::try_origin(RuntimeOrigin::root(), &AccountNameB::get()).is_err()
+            );
+        });
+    }
+
+    #[test]
+    fn members_register_a_single_account() {
+        new_test_ext().execute_with(|| {
+            crate::RegisteredAccounts::<Test>::insert(SIGNER, AccountNameA::get(), ());
+            assert!(SelfRegistration::try_origin(
+                RuntimeOrigin::signed(SIGNER),
+                &AccountNameB::get()
+            )
+            .is_err());
+            assert!(
+                Registration::try_origin(RuntimeOrigin::signed(SIGNER), &AccountNameB::get())
+                    .is_err()
+            );
+
+            // Once their account is gone, members can register another one
+            crate::RegisteredAccounts::<Test>::remove(SIGNER, AccountNameA::get());
+            assert_eq!(
+                SelfRegistration::try_origin(RuntimeOrigin::signed(SIGNER), &AccountNameB::get())
+                    .ok(),
+                Some(SIGNER)
+            );
+        });
+    }
+
+    #[test]
+    fn communities_register_their_members_and_pay_for_it() {
+        new_test_ext().execute_with(|| {
+            // The address of `AccountNameA` is a member; the community account is the registrar.
+            assert_eq!(
+                CommunityRegistration::try_origin(
+                    RuntimeOrigin::signed(OTHER),
+                    &AccountNameA::get()
+                )
+                .ok(),
+                Some(CHARLIE)
+            );
+            // The address of `AccountNameB` is not a member.
+            assert!(CommunityRegistration::try_origin(
+                RuntimeOrigin::signed(OTHER),
+                &AccountNameB::get()
+            )
+            .is_err());
+            // Only the community origin can register on behalf of its members.
+            assert!(CommunityRegistration::try_origin(
+                RuntimeOrigin::signed(SIGNER),
+                &AccountNameA::get()
+            )
+            .is_err());
+        });
+    }
+
+    #[test]
+    fn either_kind_of_registration_is_allowed() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                Registration::try_origin(RuntimeOrigin::signed(SIGNER), &AccountNameB::get()).ok(),
+                Some(SIGNER)
+            );
+            assert_eq!(
+                Registration::try_origin(RuntimeOrigin::signed(OTHER), &AccountNameA::get()).ok(),
+                Some(CHARLIE)
+            );
+            assert!(
+                Registration::try_origin(RuntimeOrigin::signed(CHARLIE), &AccountNameA::get())
+                    .is_err()
+            );
+        });
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    #[test]
+    fn benchmarks_get_successful_origins() {
+        new_test_ext().execute_with(|| {
+            let user_id = AccountNameB::get();
+
+            let origin: RuntimeOrigin = SelfRegistration::try_successful_origin(&user_id)
+                .expect("members can be added; qed");
+            assert!(SelfRegistration::try_origin(origin, &user_id).is_ok());
+
+            let origin: RuntimeOrigin = CommunityRegistration::try_successful_origin(&user_id)
+                .expect("members can be added; qed");
+            assert_eq!(
+                CommunityRegistration::try_origin(origin, &user_id).ok(),
+                Some(CHARLIE)
+            );
+        });
+    }
+}
+
+mod gas_tanks {
+    use super::*;
+    use dispatch::authenticate;
+    use frame_support::traits::fungible::Inspect;
+    use sp_runtime::transaction_validity::InvalidTransaction;
+
+    parameter_types! {
+        pub Call: RuntimeCall = RuntimeCall::System(frame_system::Call::remark {
+            remark: b"Hello, world".to_vec()
+        });
+    }
+
+    fn credential() -> PassCredential {
+        PassCredential::AuthenticatorAAuthenticator(authenticator_a::Credential {
+            user_id: AccountNameA::get(),
+            challenge: authenticator_a::Authenticator::generate(&(), &[]),
+        })
+    }
+
+    #[test]
+    fn registering_creates_a_gas_tank() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_eq!(
+                GasTanks::get(Address::get()),
+                Some((GasTankCapacity::get().unwrap(), GasTankPeriodicity::get()))
+            );
+        });
+    }
+
+    #[test]
+    fn accounts_without_balance_pay_with_their_gas_tank() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            assert_eq!(Balances::balance(&Address::get()), 0);
+
+            assert_ok!(authenticate(THE_DEVICE, credential(), Call::get()));
+
+            assert!(System::events().iter().any(|record| matches!(
+                &record.event,
+                RuntimeEvent::GasTransactionPayment(
+                    fc_pallet_gas_transaction_payment::Event::GasBurned { who, .. }
+                ) if *who == Address::get()
+            )));
+            assert_eq!(Balances::balance(&Address::get()), 0);
+        });
+    }
+
+    #[test]
+    fn accounts_without_balance_nor_gas_cannot_transact() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            GasTanks::remove(Address::get());
+
+            assert_noop!(
+                authenticate(THE_DEVICE, credential(), Call::get()),
+                InvalidTransaction::Payment
+            );
+        });
+    }
+
+    #[test]
+    fn killing_the_account_removes_its_gas_tank() {
+        prepare(AccountNameA::get()).execute_with(|| {
+            crate::AuthenticatedDevice::<Test>::put((Address::get(), THE_DEVICE));
+            assert_ok!(Pass::kill_account(
+                RuntimeOrigin::signed(Address::get()),
+                SIGNER
+            ));
+
+            assert_eq!(GasTanks::get(Address::get()), None);
+        });
+    }
+}
+
+mod client {
+    use super::*;
+    use crate::client;
+    use frame_support::dispatch::GetDispatchInfo;
+    use sp_runtime::transaction_validity::InvalidTransaction;
+
+    parameter_types! {
+        pub Call: RuntimeCall = RuntimeCall::System(frame_system::Call::remark_with_event {
+            remark: b"Hello, world".to_vec()
+        });
+    }
+
+    fn payment(
+        tip: Balance,
+    ) -> fc_pallet_gas_transaction_payment::ChargeTransactionPayment<
+        Test,
+        pallet_transaction_payment::ChargeTransactionPayment<Test>,
+    > {
+        fc_pallet_gas_transaction_payment::ChargeTransactionPayment::new(
+            pallet_transaction_payment::ChargeTransactionPayment::from(tip),
+        )
+    }
+
+    fn sign(xtc: &[u8; 32]) -> PassCredential {
+        PassCredential::AuthenticatorB(
+            authenticator_b::Credential::new(
+                AccountNameA::get(),
+                System::block_number(),
+                0,
+                LastThreeBlocksChallenger::generate(&System::block_number(), xtc),
+            )
+            .sign(&THE_DEVICE),
+        )
+    }
+
+    fn apply(extensions: TxExtensions) -> ApplyExtrinsicResultWithInfo<PostDispatchInfo> {
+        let call = Call::get();
+        let xt = CheckedExtrinsic {
+            format: ExtrinsicFormat::General(0, extensions),
+            function: call.clone(),
+        };
+        xt.apply::<Test>(&call.get_dispatch_info(), call.encoded_size())
+    }
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut ext = super::new_test_ext();
+        ext.execute_with(|| {
+            assert_ok!(Pass::register(
+                RuntimeOrigin::root(),
+                AccountNameA::get(),
+                PassDeviceAttestation::AuthenticatorB(authenticator_b::DeviceAttestation {
+                    device_id: THE_DEVICE,
+                    context: System::block_number(),
+                    challenge: LastThreeBlocksChallenger::generate(
+                        &System::block_number(),
+                        &Address::get(),
+                    ),
+                }),
+            ));
+            assert_ok!(Balances::mint_into(&Address::get(), Balance::MAX));
+        });
+        ext
+    }
+
+    #[test]
+    fn extensions_built_by_the_client_authenticate() {
+        new_test_ext().execute_with(|| {
+            let extensions =
+                client::extensions::<Test, _, _>(0, &Call::get(), THE_DEVICE, 0, payment(0), sign)
+                    .expect("no implicit data; qed");
+
+            assert_ok!(apply(extensions));
+            System::assert_has_event(
+                frame_system::Event::Remarked {
+                    sender: Address::get(),
+                    hash: <Test as frame_system::Config>::Hashing::hash(b"Hello, world"),
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn credentials_are_bound_to_the_following_extensions() {
+        new_test_ext().execute_with(|| {
+            let params = client::authenticate_params::<Test, _, _>(
+                0,
+                &Call::get(),
+                THE_DEVICE,
+                0,
+                &payment(1),
+                sign,
+            )
+            .expect("no implicit data; qed");
+
+            assert_noop!(
+                apply((params.into(), payment(0))),
+                InvalidTransaction::BadSigner
+            );
+        });
+    }
+
+    #[test]
+    fn session_extensions_skip_authentication() {
+        new_test_ext().execute_with(|| {
+            let (authenticate, _) = client::session_extensions::<Test, (), _>(payment(0));
+            assert_eq!(authenticate, PassAuthenticate::<Test>::default());
+        });
+    }
+}
@@ -1,13 +1,15 @@
 use super::*;
-use crate::filter::{DeviceFilter, SpendMatcher};
+use crate::filter::{CallInspection, DeviceFilter, SpendMatcher};
 
 use alloc::borrow::ToOwned;
 use codec::EncodeLike;
-use frame_support::traits::MapSuccess;
+use fc_traits_gas_tank::MakeTank;
+use frame_support::{traits::MapSuccess, CloneNoBound, DebugNoBound, EqNoBound, PartialEqNoBound};
 use frame_system::EnsureSigned;
 use sp_runtime::{
     morph_types,
     traits::{Hash, TrailingZeroInput},
+    transaction_validity::TransactionPriority,
     Saturating,
 };
 
@@ -24,23 +26,271 @@ pub type DeviceAttestationOf<T, I = ()> =
 pub type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
 pub type BalanceOf<T, I = ()> =
     <<T as Config<I>>::Balances as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
-pub type DeviceFilterOf<T, I = ()> =
-    DeviceFilter<
-        <<T as Config<I>>::SpendMatcher as SpendMatcher<
-            <T as frame_system::Config>::RuntimeCall,
-        >>::AssetId,
-        <<T as Config<I>>::SpendMatcher as SpendMatcher<
-            <T as frame_system::Config>::RuntimeCall,
-        >>::Balance,
-        <T as Config<I>>::MaxFilteredCalls,
-        <T as Config<I>>::MaxFilteredAssets,
-    >;
+pub type SpendAssetOf<T, I = ()> = <<T as Config<I>>::SpendMatcher as SpendMatcher<
+    <T as frame_system::Config>::RuntimeCall,
+>>::AssetId;
+pub type SpendBalanceOf<T, I = ()> = <<T as Config<I>>::SpendMatcher as SpendMatcher<
+    <T as frame_system::Config>::RuntimeCall,
+>>::Balance;
+pub type DeviceFilterOf<T, I = ()> = DeviceFilter<
+    SpendAssetOf<T, I>,
+    SpendBalanceOf<T, I>,
+    <T as Config<I>>::MaxFilteredCalls,
+    <T as Config<I>>::MaxFilteredAssets,
+>;
+pub type CallInspectionOf<T, I = ()> =
+    crate::filter::CallInspection<SpendAssetOf<T, I>, SpendBalanceOf<T, I>>;
+pub type BudgetedSpendOf<T, I = ()> =
+    BudgetedSpend<<T as frame_system::Config>::AccountId, SpendAssetOf<T, I>, SpendBalanceOf<T, I>>;
 pub type DepositInformation<T, I = ()> = (
     <T as frame_system::Config>::AccountId,
     BalanceOf<T, I>,
     <T as frame_system::Config>::AccountId,
 );
 
+/// A session: the pass account it acts on behalf of, the block it expires at, its call
+/// filter and the device that created it.
+pub type SessionOf<T, I = ()> = (
+    <T as frame_system::Config>::AccountId,
+    BlockNumberFor<T, I>,
+    DeviceFilterOf<T, I>,
+    DeviceId,
+);
+
+pub type GuardianSetOf<T, I = ()> = GuardianSet<
+    <T as frame_system::Config>::AccountId,
+    BlockNumberFor<T, I>,
+    <T as Config<I>>::MaxGuardians,
+>;
+pub type RecoveryOf<T, I = ()> = Recovery<
+    <T as frame_system::Config>::AccountId,
+    BlockNumberFor<T, I>,
+    DeviceOf<T, I>,
+    <T as Config<I>>::MaxGuardians,
+>;
+pub type ApprovalPolicyOf<T, I = ()> = ApprovalPolicy<
+    SpendAssetOf<T, I>,
+    SpendBalanceOf<T, I>,
+    <T as Config<I>>::MaxFilteredCalls,
+    <T as Config<I>>::MaxFilteredAssets,
+>;
+pub type DeviceLabelOf<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxDeviceLabelLen>;
+pub type DeviceInfoOf<T, I = ()> =
+    DeviceInfo<BlockNumberFor<T, I>, <T as Config<I>>::MaxDeviceLabelLen>;
+pub type DeviceDetailsOf<T, I = ()> = DeviceDetails<DeviceFilterOf<T, I>, DeviceInfoOf<T, I>>;
+pub type SessionDetailsOf<T, I = ()> = SessionDetails<
+    <T as frame_system::Config>::AccountId,
+    BlockNumberFor<T, I>,
+    DeviceFilterOf<T, I>,
+>;
+pub type ProposalOf<T, I = ()> =
+    Proposal<BlockNumberFor<T, I>, <T as Config<I>>::MaxDevicesPerAccount>;
+
+/// Whether a registrar can register new accounts.
+#[derive(
+    Clone,
+    Copy,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub enum RegistrarStatus {
+    Active,
+    /// The registrar can't register new accounts. The accounts it already registered are not
+    /// affected.
+    Suspended,
+}
+
+/// The terms a registrar registers accounts under, as set by governance.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub struct RegistrarInfo {
+    /// The maximum amount of accounts the registrar might have registered at once, if any.
+    pub max_registrations: Option<u32>,
+    pub status: RegistrarStatus,
+    /// Whether the registrar pays for the device deposits of the accounts it registers.
+    pub sponsor_device_deposits: bool,
+}
+
+/// How many transactions a device might authenticate every `period` blocks.
+#[derive(
+    Clone,
+    Copy,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct DeviceRateLimit {
+    pub max_transactions: u32,
+    /// The length, in blocks, of the periods transactions are counted in.
+    pub period: u32,
+}
+
+/// Who spends on behalf of a pass account, so its spend budgets can be tracked.
+#[derive(
+    Clone, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq,
+)]
+pub enum Spender<AccountId> {
+    Device(DeviceId),
+    SessionKey(AccountId),
+}
+
+/// A spend to be charged against the [`SpendBudget`](crate::filter::SpendBudget) of a device
+/// or session key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetedSpend<AccountId, AssetId, Balance> {
+    pub who: AccountId,
+    pub spender: Spender<AccountId>,
+    pub asset: AssetId,
+    pub amount: Balance,
+    /// The period of the budget, in blocks.
+    pub period: u32,
+}
+
+/// The guardians that can recover a pass account, by registering a new `Admin` device on its
+/// behalf.
+#[derive(
+    CloneNoBound,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    DebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+)]
+#[scale_info(skip_type_params(MaxGuardians))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+pub struct GuardianSet<AccountId, BlockNumber, MaxGuardians>
+where
+    AccountId: Clone + Debug + Eq,
+    BlockNumber: Clone + Debug + Eq,
+    MaxGuardians: Get<u32>,
+{
+    pub guardians: BoundedVec<AccountId, MaxGuardians>,
+    /// How many guardians must approve a recovery.
+    pub threshold: u32,
+    /// How long the owner has to cancel a recovery, once approved.
+    pub delay: BlockNumber,
+}
+
+/// A recovery in progress for a pass account.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(MaxGuardians))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen, Device: MaxEncodedLen))]
+pub struct Recovery<AccountId, BlockNumber, Device, MaxGuardians: Get<u32>> {
+    /// The new `Admin` device, verified when the recovery was initiated.
+    pub device: Device,
+    /// A device to revoke once the recovery is finished.
+    pub revoke: Option<DeviceId>,
+    /// The guardians that approved the recovery.
+    pub approvals: BoundedVec<AccountId, MaxGuardians>,
+    /// The block from which the recovery can be finished. Set once the threshold of approvals
+    /// is met.
+    pub executable_at: Option<BlockNumber>,
+}
+
+/// Descriptive information of a device, so users can tell their devices apart.
+#[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq)]
+#[scale_info(skip_type_params(MaxLabelLen))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+pub struct DeviceInfo<BlockNumber, MaxLabelLen: Get<u32>> {
+    /// A name given by the user.
+    pub label: Option<BoundedVec<u8, MaxLabelLen>>,
+    /// The platform of the device, as described by the user (e.g. an OS or browser).
+    pub platform: Option<BoundedVec<u8, MaxLabelLen>>,
+    /// The block the device was added at. Zero for devices added before this was tracked.
+    pub added_at: BlockNumber,
+    /// The last block the device authenticated a transaction at.
+    pub last_used: Option<BlockNumber>,
+}
+
+impl<BlockNumber, MaxLabelLen: Get<u32>> DeviceInfo<BlockNumber, MaxLabelLen> {
+    pub fn new(added_at: BlockNumber) -> Self {
+        Self {
+            label: None,
+            platform: None,
+            added_at,
+            last_used: None,
+        }
+    }
+}
+
+/// The calls of a pass account that require approvals from several of its devices.
+#[derive(
+    CloneNoBound,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    TypeInfo,
+    DebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+)]
+#[scale_info(skip_type_params(MaxCalls, MaxAssets))]
+#[codec(mel_bound(AssetId: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct ApprovalPolicy<
+    AssetId: Clone + Debug + Eq,
+    Balance: Clone + Debug + Eq,
+    MaxCalls: Get<u32>,
+    MaxAssets: Get<u32>,
+> {
+    /// How many devices must approve a sensitive call, including the one proposing it.
+    pub threshold: u32,
+    /// How long a proposed call can wait for approvals, in blocks.
+    pub timeout: u32,
+    /// The `(pallet_index, call_index)` of sensitive calls. A `None` call index matches every
+    /// call in the pallet.
+    pub calls: BoundedVec<(u8, Option<u8>), MaxCalls>,
+    /// Spending at least the given amount of an asset is sensitive.
+    pub spends: BoundedVec<(AssetId, Balance), MaxAssets>,
+}
+
+impl<
+        AssetId: Clone + Debug + Eq,
+        Balance: Ord + Clone + Debug + Eq,
+        MaxCalls: Get<u32>,
+        MaxAssets: Get<u32>,
+    > ApprovalPolicy<AssetId, Balance, MaxCalls, MaxAssets>
+{
+    /// Whether any of the inspected calls is sensitive.
+    pub fn requires_approval(&self, inspection: &CallInspection<AssetId, Balance>) -> bool {
+        inspection.calls.iter().any(|call| {
+            self.calls
+                .iter()
+                .any(|(pallet, c)| *pallet == call.index.0 && c.is_none_or(|c| c == call.index.1))
+        }) || inspection.spends.iter().any(|(asset, amount)| {
+            self.spends
+                .iter()
+                .any(|(a, threshold)| a == asset && amount >= threshold)
+        })
+    }
+}
+
+/// A call waiting for approvals from the devices of a pass account.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(MaxApprovals))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+pub struct Proposal<BlockNumber, MaxApprovals: Get<u32>> {
+    /// The devices that approved the call, starting with the one proposing it.
+    pub approvals: BoundedVec<DeviceId, MaxApprovals>,
+    /// The block from which the proposal can no longer be approved.
+    pub expires_at: BlockNumber,
+}
+
 morph_types! {
     pub type PaymentForCreate<
         AccountId,
@@ -54,14 +304,26 @@ morph_types! {
 pub type EnsureSignedPays<T, Amount, Beneficiary> =
     MapSuccess<EnsureSigned<AccountIdOf<T>>, PaymentForCreate<AccountIdOf<T>, Amount, Beneficiary>>;
 
+/// Derives the address of pass accounts from their [`HashedUserId`], with a versioned scheme.
+/// Registered accounts are bound to their [`HashedUserId`] (see [`crate::UserIdAddresses`]), so
+/// new registrations can use a newer scheme without affecting existing accounts. Accounts
+/// registered before bindings existed are found with the scheme of version `0`.
 pub trait AddressGenerator<T: Config<I>, I: 'static> {
-    /// Generates an account address for a [HashedUserId]. Returns `Some(address)`
-    /// if the process is successful, or
-    fn generate_address(id: HashedUserId) -> T::AccountId;
+    /// The version of the scheme new pass accounts are derived with.
+    const VERSION: u32 = 0;
+
+    /// Generates the address of a new pass account for a [HashedUserId].
+    fn generate_address(id: HashedUserId) -> T::AccountId {
+        Self::generate_address_at(Self::VERSION, id)
+    }
+
+    /// Generates the address the scheme of `version` derives for a [HashedUserId]. Versions
+    /// after [`Self::VERSION`] use the current scheme.
+    fn generate_address_at(version: u32, id: HashedUserId) -> T::AccountId;
 }
 
 impl<T: Config<I>, I: 'static> AddressGenerator<T, I> for () {
-    fn generate_address(id: HashedUserId) -> T::AccountId {
+    fn generate_address_at(_: u32, id: HashedUserId) -> T::AccountId {
         // we know the length of HashedUserId
         let mut input = [0u8; 2 * HASHED_USER_ID_LEN];
         input[HASHED_USER_ID_LEN..].copy_from_slice(&id);
@@ -73,6 +335,85 @@ impl<T: Config<I>, I: 'static> AddressGenerator<T, I> for () {
     }
 }
 
+/// Derives addresses with `Current` from `VERSION` on, and with `Previous` for the versions
+/// before it, so runtimes can switch schemes while still finding accounts derived with the
+/// previous ones.
+pub struct VersionedAddressGenerator<const VERSION: u32, Previous, Current>(
+    core::marker::PhantomData<(Previous, Current)>,
+);
+
+impl<T, I, const VERSION: u32, Previous, Current> AddressGenerator<T, I>
+    for VersionedAddressGenerator<VERSION, Previous, Current>
+where
+    T: Config<I>,
+    I: 'static,
+    Previous: AddressGenerator<T, I>,
+    Current: AddressGenerator<T, I>,
+{
+    const VERSION: u32 = VERSION;
+
+    fn generate_address_at(version: u32, id: HashedUserId) -> T::AccountId {
+        if version < VERSION {
+            Previous::generate_address_at(version, id)
+        } else {
+            Current::generate_address(id)
+        }
+    }
+}
+
+/// Sets the priority of transactions authenticated by pass devices, e.g. to favour `Admin`
+/// devices over restricted ones.
+pub trait DevicePriority<T: Config<I>, I: 'static> {
+    fn priority(who: &T::AccountId, device_id: &DeviceId) -> TransactionPriority;
+}
+
+impl<T: Config<I>, I: 'static> DevicePriority<T, I> for () {
+    fn priority(_: &T::AccountId, _: &DeviceId) -> TransactionPriority {
+        0
+    }
+}
+
+/// Creates the gas tank a new pass account pays for its transactions with, so accounts can
+/// transact without holding any balance, and removes it when the account is killed. Use `()` to
+/// not create gas tanks.
+pub trait AccountGasTank<T: Config<I>, I: 'static> {
+    fn make_tank(address: &T::AccountId) -> DispatchResult;
+    fn remove_tank(address: &T::AccountId) -> DispatchResult;
+}
+
+impl<T: Config<I>, I: 'static> AccountGasTank<T, I> for () {
+    fn make_tank(_: &T::AccountId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn remove_tank(_: &T::AccountId) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// Creates a gas tank for every new pass account through a [`MakeTank`] implementation whose
+/// tanks are identified by the account that burns gas from them, with a `Capacity` of gas per
+/// `Periodicity` blocks.
+pub struct MakeAccountTank<M, Capacity, Periodicity>(PhantomData<(M, Capacity, Periodicity)>);
+
+impl<T, I, M, Capacity, Periodicity> AccountGasTank<T, I>
+    for MakeAccountTank<M, Capacity, Periodicity>
+where
+    T: Config<I>,
+    I: 'static,
+    M: MakeTank<TankId = T::AccountId, Gas = Weight, BlockNumber = BlockNumberFor<T, I>>,
+    Capacity: Get<Option<Weight>>,
+    Periodicity: Get<Option<BlockNumberFor<T, I>>>,
+{
+    fn make_tank(address: &T::AccountId) -> DispatchResult {
+        M::make_tank(address, Capacity::get(), Periodicity::get())
+    }
+
+    fn remove_tank(address: &T::AccountId) -> DispatchResult {
+        M::remove_tank(address)
+    }
+}
+
 #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, Debug, Eq, PartialEq)]
 pub struct FirstItemIsFree<C>(pub(crate) Option<C>);
 
@@ -147,19 +488,31 @@ where
     >,
     BlobType: MaxEncodedLen,
 {
-    /// Makes a mutation on the consideration storage for an address
-    fn mutate_consideration(address: &Account, f: impl FnOnce(&mut u32)) -> DispatchResult {
+    /// Makes a mutation on the consideration storage for an address, whose deposit is taken
+    /// from `payer`.
+    fn mutate_consideration(
+        address: &Account,
+        payer: &Account,
+        f: impl FnOnce(&mut u32),
+    ) -> DispatchResult {
         Storage::try_mutate(address, |maybe_consideration| {
             let (consideration, mut count) = match maybe_consideration {
                 Some(c) => c.to_owned(),
-                _ => (Consideration::new(address, Footprint::default())?, 0),
+                _ => (Consideration::new(payer, Footprint::default())?, 0),
             };
 
             f(&mut count);
 
+            // Once there's nothing left to pay for, the consideration is released entirely.
+            if count == 0 {
+                consideration.drop(payer)?;
+                *maybe_consideration = None;
+                return Ok(());
+            }
+
             *maybe_consideration = Some((
                 consideration.update(
-                    address,
+                    payer,
                     Footprint::from_parts(count as usize, BlobType::max_encoded_len()),
                 )?,
                 count,
@@ -171,15 +524,43 @@ where
 
     /// Increments the consideration count for an address
     pub fn increment(address: &Account) -> DispatchResult {
-        Self::mutate_consideration(address, u32::saturating_inc)
+        Self::mutate_consideration(address, address, u32::saturating_inc)
     }
 
     /// Decrements the consideration count for an address
     pub fn decrement(address: &Account) -> DispatchResult {
-        Self::mutate_consideration(address, u32::saturating_dec)
+        Self::mutate_consideration(address, address, u32::saturating_dec)
+    }
+
+    /// Increments the consideration count for an address, whose deposit is paid by `payer`.
+    pub fn increment_paid_by(address: &Account, payer: &Account) -> DispatchResult {
+        Self::mutate_consideration(address, payer, u32::saturating_inc)
+    }
+
+    /// Decrements the consideration count for an address, whose deposit is paid by `payer`.
+    pub fn decrement_paid_by(address: &Account, payer: &Account) -> DispatchResult {
+        Self::mutate_consideration(address, payer, u32::saturating_dec)
     }
 }
 
+/// A device of a pass account, as returned by [`crate::PassApi`].
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq)]
+pub struct DeviceDetails<Filter, Info> {
+    pub device_id: DeviceId,
+    /// The calls the device can dispatch. Devices without a filter can't dispatch any call.
+    pub filter: Option<Filter>,
+    pub info: Option<Info>,
+}
+
+/// A live session key of a pass account, as returned by [`crate::PassApi`].
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq)]
+pub struct SessionDetails<AccountId, BlockNumber, Filter> {
+    pub session_key: AccountId,
+    /// The last block the session is valid at.
+    pub until: BlockNumber,
+    pub filter: Filter,
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 pub use benchmarks::BenchmarkHelper;
 
@@ -206,5 +587,13 @@ mod benchmarks {
             device_id: DeviceId,
             xtc: &impl ExtrinsicContext,
         ) -> CredentialOf<T, I>;
+        /// Returns a call dispatching `calls` that [`Config::CallInspector`] expands, like a
+        /// batch. Runtimes that don't expand calls can return the first of `calls`.
+        fn nested_call(
+            calls: Vec<<T as frame_system::Config>::RuntimeCall>,
+        ) -> <T as frame_system::Config>::RuntimeCall;
+        /// Returns a call [`Config::SpendMatcher`] matches as a spend, or `None` if the runtime
+        /// has no spends.
+        fn spend_call() -> Option<<T as frame_system::Config>::RuntimeCall>;
     }
 }
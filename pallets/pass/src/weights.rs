@@ -4,16 +4,33 @@
 #![allow(missing_docs)]
 
 use core::marker::PhantomData;
-use frame_support::weights::Weight;
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 
 /// Weight functions needed for pallet_remark.
 pub trait WeightInfo {
 	fn register() -> Weight;
-	fn authenticate() -> Weight;
+	fn authenticate(c: u32, l: u32) -> Weight;
 	fn add_device() -> Weight;
 	fn remove_device() -> Weight;
 	fn add_session_key() -> Weight;
 	fn remove_session_key() -> Weight;
+	fn set_guardians() -> Weight;
+	fn remove_guardians() -> Weight;
+	fn initiate_recovery() -> Weight;
+	fn approve_recovery() -> Weight;
+	fn cancel_recovery() -> Weight;
+	fn finish_recovery() -> Weight;
+	fn kill_account(d: u32, s: u32) -> Weight;
+	fn set_approval_policy() -> Weight;
+	fn approve_call() -> Weight;
+	fn cancel_proposal() -> Weight;
+	fn remove_expired_proposal() -> Weight;
+	fn update_device_filter() -> Weight;
+	fn set_device_metadata() -> Weight;
+	fn renew_session_key() -> Weight;
+	fn set_registrar() -> Weight;
+	fn rotate_user_id() -> Weight;
+	fn set_device_rate_limit() -> Weight;
 }
 
 /// Weights for pallet_remark using the Substrate node and recommended hardware.
@@ -30,8 +47,41 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(Weight::from_parts(1_359, 0))
 	}
 
+	/// Storage: `Pass::AccountUserIds` (r:1 w:0)
+	/// Proof: `Pass::AccountUserIds` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Devices` (r:2 w:1)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:1 w:0)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:1 w:1)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:1 w:1)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalPolicies` (r:1 w:0)
+	/// Proof: `Pass::ApprovalPolicies` (`max_values`: None, `max_size`: Some(168), added: 2643, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:0 w:1)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:100 w:100)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 100]`.
+	/// The range of component `l` is `[0, 16384]`.
+	fn authenticate(c: u32, l: u32) -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22453 + c * (5224 ±0)`
+		Weight::from_parts(45_000_000, 22453)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(5_000, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 5224).saturating_mul(c.into()))
+	}
+
 	/// The range of component `l` is `[1, 1048576]`.
-	fn authenticate() -> Weight {
+	fn add_device() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -42,7 +92,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn add_device() -> Weight {
+	fn remove_device() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -53,7 +103,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn remove_device() -> Weight {
+	fn add_session_key() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -64,7 +114,224 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn add_session_key() -> Weight {
+	fn remove_session_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	fn set_guardians() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22437`
+		Weight::from_parts(8_586_000, 22437)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn remove_guardians() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22437`
+		Weight::from_parts(8_586_000, 22437)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+
+	/// Storage: `Pass::Guardians` (r:1 w:0)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:1 w:0)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn initiate_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `15523`
+		Weight::from_parts(8_586_000, 15523)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	/// Storage: `Pass::Guardians` (r:1 w:0)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	fn approve_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `5331`
+		Weight::from_parts(8_586_000, 5331)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:1 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn cancel_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `12976`
+		Weight::from_parts(8_586_000, 12976)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:2 w:2)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Devices` (r:1 w:2)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceConsiderations` (r:1 w:1)
+	/// Proof: `Pass::DeviceConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceDepositSponsors` (r:1 w:0)
+	/// Proof: `Pass::DeviceDepositSponsors` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:2 w:2)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:0 w:2)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:0 w:2)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:0 w:1)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:1)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:0 w:5)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	fn finish_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `28246`
+		Weight::from_parts(8_586_000, 28246)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(22_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:4 w:2)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:2)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountSessionKeys` (r:3 w:2)
+	/// Proof: `Pass::AccountSessionKeys` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Scheduler::Lookup` (r:2 w:0)
+	/// Proof: `Scheduler::Lookup` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::SessionKeys` (r:2 w:2)
+	/// Proof: `Pass::SessionKeys` (`max_values`: None, `max_size`: Some(1211), added: 3686, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:0 w:20)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountSessionsCount` (r:1 w:1)
+	/// Proof: `Pass::AccountSessionsCount` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::SessionKeyConsiderations` (r:1 w:1)
+	/// Proof: `Pass::SessionKeyConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:0 w:2)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:0 w:2)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:0 w:2)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:2)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceConsiderations` (r:1 w:1)
+	/// Proof: `Pass::DeviceConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceDepositSponsors` (r:1 w:1)
+	/// Proof: `Pass::DeviceDepositSponsors` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalPolicies` (r:1 w:1)
+	/// Proof: `Pass::ApprovalPolicies` (`max_values`: None, `max_size`: Some(168), added: 2643, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Proposals` (r:1 w:2)
+	/// Proof: `Pass::Proposals` (`max_values`: None, `max_size`: Some(153), added: 2628, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalConsiderations` (r:1 w:1)
+	/// Proof: `Pass::ApprovalConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountUserIds` (r:1 w:1)
+	/// Proof: `Pass::AccountUserIds` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::UserIdAddresses` (r:0 w:1)
+	/// Proof: `Pass::UserIdAddresses` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountRegistrars` (r:1 w:1)
+	/// Proof: `Pass::AccountRegistrars` (`max_values`: None, `max_size`: Some(112), added: 2587, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RegisteredAccounts` (r:0 w:1)
+	/// Proof: `Pass::RegisteredAccounts` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RegistrarConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RegistrarConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:2 w:2)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:3 w:3)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// The range of component `d` is `[1, 2]`.
+	/// The range of component `s` is `[0, 2]`.
+	fn kill_account(d: u32, s: u32) -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `58359 + d * (2608 ±0) + s * (8780 ±0)`
+		Weight::from_parts(8_586_000, 58359)
+			.saturating_add(Weight::from_parts(8_586_000, 0).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(8_586_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(23_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(21_u64))
+			.saturating_add(T::DbWeight::get().writes((11_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 2608).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(0, 8780).saturating_mul(s.into()))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_approval_policy() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -75,7 +342,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn remove_session_key() -> Weight {
+	fn approve_call() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -84,6 +351,99 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 0
 			.saturating_add(Weight::from_parts(1_359, 0))
 	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn cancel_proposal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn remove_expired_proposal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn update_device_filter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_device_metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn renew_session_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_registrar() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn rotate_user_id() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(8_586_000, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(1_359, 0))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:1)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn set_device_rate_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `9437`
+		Weight::from_parts(8_586_000, 9437)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -99,8 +459,41 @@ impl WeightInfo for () {
 			.saturating_add(Weight::from_parts(1_359, 0))
 	}
 
+	/// Storage: `Pass::AccountUserIds` (r:1 w:0)
+	/// Proof: `Pass::AccountUserIds` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Devices` (r:2 w:1)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:1 w:0)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:1 w:1)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:1 w:1)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalPolicies` (r:1 w:0)
+	/// Proof: `Pass::ApprovalPolicies` (`max_values`: None, `max_size`: Some(168), added: 2643, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:0 w:1)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:100 w:100)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 100]`.
+	/// The range of component `l` is `[0, 16384]`.
+	fn authenticate(c: u32, l: u32) -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22453 + c * (5224 ±0)`
+		Weight::from_parts(45_000_000, 22453)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(5_000, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 5224).saturating_mul(c.into()))
+	}
+
 	/// The range of component `l` is `[1, 1048576]`.
-	fn authenticate() -> Weight {
+	fn add_device() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -111,7 +504,7 @@ impl WeightInfo for () {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn add_device() -> Weight {
+	fn remove_device() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -122,7 +515,7 @@ impl WeightInfo for () {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn remove_device() -> Weight {
+	fn add_session_key() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -133,7 +526,224 @@ impl WeightInfo for () {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn add_session_key() -> Weight {
+	fn remove_session_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	fn set_guardians() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22437`
+		Weight::from_parts(8_586_000, 22437)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn remove_guardians() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `22437`
+		Weight::from_parts(8_586_000, 22437)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+
+	/// Storage: `Pass::Guardians` (r:1 w:0)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:1 w:0)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn initiate_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `15523`
+		Weight::from_parts(8_586_000, 15523)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+
+	/// Storage: `Pass::Guardians` (r:1 w:0)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	fn approve_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `5331`
+		Weight::from_parts(8_586_000, 5331)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:1 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn cancel_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `12976`
+		Weight::from_parts(8_586_000, 12976)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:2 w:2)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Devices` (r:1 w:2)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceConsiderations` (r:1 w:1)
+	/// Proof: `Pass::DeviceConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceDepositSponsors` (r:1 w:0)
+	/// Proof: `Pass::DeviceDepositSponsors` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:2 w:2)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:0 w:2)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:0 w:2)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:0 w:1)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:1)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:0 w:5)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	fn finish_recovery() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `28246`
+		Weight::from_parts(8_586_000, 28246)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(22_u64))
+	}
+
+	/// Storage: `Pass::Devices` (r:4 w:2)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:2)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountSessionKeys` (r:3 w:2)
+	/// Proof: `Pass::AccountSessionKeys` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Scheduler::Lookup` (r:2 w:0)
+	/// Proof: `Scheduler::Lookup` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::SessionKeys` (r:2 w:2)
+	/// Proof: `Pass::SessionKeys` (`max_values`: None, `max_size`: Some(1211), added: 3686, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::PeriodSpends` (r:0 w:20)
+	/// Proof: `Pass::PeriodSpends` (`max_values`: None, `max_size`: Some(137), added: 2612, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountSessionsCount` (r:1 w:1)
+	/// Proof: `Pass::AccountSessionsCount` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::SessionKeyConsiderations` (r:1 w:1)
+	/// Proof: `Pass::SessionKeyConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceIds` (r:0 w:2)
+	/// Proof: `Pass::DeviceIds` (`max_values`: None, `max_size`: Some(48), added: 2523, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceInfos` (r:0 w:2)
+	/// Proof: `Pass::DeviceInfos` (`max_values`: None, `max_size`: Some(181), added: 2656, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceTransactions` (r:0 w:2)
+	/// Proof: `Pass::DeviceTransactions` (`max_values`: None, `max_size`: Some(108), added: 2583, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:2)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Recoveries` (r:1 w:1)
+	/// Proof: `Pass::Recoveries` (`max_values`: None, `max_size`: Some(224), added: 2699, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RecoveryConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RecoveryConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Guardians` (r:1 w:1)
+	/// Proof: `Pass::Guardians` (`max_values`: None, `max_size`: Some(157), added: 2632, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceConsiderations` (r:1 w:1)
+	/// Proof: `Pass::DeviceConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceDepositSponsors` (r:1 w:1)
+	/// Proof: `Pass::DeviceDepositSponsors` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalPolicies` (r:1 w:1)
+	/// Proof: `Pass::ApprovalPolicies` (`max_values`: None, `max_size`: Some(168), added: 2643, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::Proposals` (r:1 w:2)
+	/// Proof: `Pass::Proposals` (`max_values`: None, `max_size`: Some(153), added: 2628, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::ApprovalConsiderations` (r:1 w:1)
+	/// Proof: `Pass::ApprovalConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountUserIds` (r:1 w:1)
+	/// Proof: `Pass::AccountUserIds` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::UserIdAddresses` (r:0 w:1)
+	/// Proof: `Pass::UserIdAddresses` (`max_values`: None, `max_size`: Some(80), added: 2555, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AccountRegistrars` (r:1 w:1)
+	/// Proof: `Pass::AccountRegistrars` (`max_values`: None, `max_size`: Some(112), added: 2587, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RegisteredAccounts` (r:0 w:1)
+	/// Proof: `Pass::RegisteredAccounts` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::RegistrarConsiderations` (r:1 w:1)
+	/// Proof: `Pass::RegistrarConsiderations` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:2 w:2)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(79), added: 2554, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:3 w:3)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	/// The range of component `d` is `[1, 2]`.
+	/// The range of component `s` is `[0, 2]`.
+	fn kill_account(d: u32, s: u32) -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `58359 + d * (2608 ±0) + s * (8780 ±0)`
+		Weight::from_parts(8_586_000, 58359)
+			.saturating_add(Weight::from_parts(8_586_000, 0).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(8_586_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(23_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(d.into())))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(s.into())))
+			.saturating_add(RocksDbWeight::get().writes(21_u64))
+			.saturating_add(RocksDbWeight::get().writes((11_u64).saturating_mul(d.into())))
+			.saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 2608).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(0, 8780).saturating_mul(s.into()))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_approval_policy() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -144,7 +754,7 @@ impl WeightInfo for () {
 	}
 
 	/// The range of component `l` is `[1, 1048576]`.
-	fn remove_session_key() -> Weight {
+	fn approve_call() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `0`
 		//  Estimated: `0`
@@ -153,4 +763,97 @@ impl WeightInfo for () {
 			// Standard Error: 0
 			.saturating_add(Weight::from_parts(0, 0))
 	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn cancel_proposal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn remove_expired_proposal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn update_device_filter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_device_metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn renew_session_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn set_registrar() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// The range of component `l` is `[1, 1048576]`.
+	fn rotate_user_id() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_471_000 picoseconds.
+		Weight::from_parts(0, 0)
+			// Standard Error: 0
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+
+	/// Storage: `Pass::Devices` (r:2 w:0)
+	/// Proof: `Pass::Devices` (`max_values`: None, `max_size`: Some(133), added: 2608, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::AuthenticatedDevice` (r:1 w:0)
+	/// Proof: `Pass::AuthenticatedDevice` (`max_values`: Some(1), `max_size`: Some(64), added: 559, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceFilters` (r:1 w:0)
+	/// Proof: `Pass::DeviceFilters` (`max_values`: None, `max_size`: Some(1187), added: 3662, mode: `MaxEncodedLen`)
+	/// Storage: `Pass::DeviceRateLimits` (r:0 w:1)
+	/// Proof: `Pass::DeviceRateLimits` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn set_device_rate_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `9437`
+		Weight::from_parts(8_586_000, 9437)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
@@ -3,19 +3,42 @@ use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 use syn::{
-    braced, parse_macro_input, AngleBracketedGenericArguments, Error, GenericArgument, Ident, Path,
-    Result, Token, Type, TypePath, Visibility,
+    braced, parse_macro_input, AngleBracketedGenericArguments, Attribute, Error, Expr, ExprLit,
+    GenericArgument, Ident, Lit, Path, Result, Token, Type, TypePath, Visibility,
 };
 
 struct AuthMacroInput {
     vis: Visibility,
     name: Ident,
     authority: Path,
-    authenticators: Vec<(Ident, Path)>,
+    authenticators: Vec<AuthenticatorInput>,
+    generate_tests: bool,
+}
+
+/// An authenticator of the composite, along with the codec index of its variants.
+#[derive(Clone)]
+struct AuthenticatorInput {
+    id: Ident,
+    path: Path,
+    index: u8,
+    deprecated: bool,
 }
 
 impl Parse for AuthMacroInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut generate_tests = false;
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("generate_tests") {
+                attr.meta.require_path_only()?;
+                generate_tests = true;
+            } else {
+                return Err(Error::new(
+                    attr.span(),
+                    "Unknown attribute, expected `#[generate_tests]`",
+                ));
+            }
+        }
+
         let vis = input.parse()?;
         let name: Path = input.parse()?;
 
@@ -59,10 +82,54 @@ impl Parse for AuthMacroInput {
             }
         }
 
-        for p in content
-            .parse_terminated(Path::parse, Token![,])?
-            .into_iter()
-        {
+        while !content.is_empty() {
+            let mut index = None;
+            let mut deprecated = false;
+            for attr in content.call(Attribute::parse_outer)? {
+                if attr.path().is_ident("index") {
+                    index = Some(match &attr.meta.require_name_value()?.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Int(n), ..
+                        }) => n.base10_parse::<u8>()?,
+                        value => {
+                            return Err(Error::new(
+                                value.span(),
+                                "Expected an index between 0 and 255",
+                            ))
+                        }
+                    });
+                } else if attr.path().is_ident("deprecated") {
+                    attr.meta.require_path_only()?;
+                    deprecated = true;
+                } else {
+                    return Err(Error::new(
+                        attr.span(),
+                        "Unknown attribute, expected `#[index = N]` or `#[deprecated]`",
+                    ));
+                }
+            }
+
+            let p: Path = content.parse()?;
+            if !content.is_empty() {
+                let _: Token![,] = content.parse()?;
+            }
+
+            // Authenticators without a pinned index take the one of their position.
+            let index = match index {
+                Some(index) => index,
+                None => u8::try_from(authenticators.len())
+                    .map_err(|_| Error::new(p.span(), "Too many authenticators"))?,
+            };
+            if authenticators
+                .iter()
+                .any(|a: &AuthenticatorInput| a.index == index)
+            {
+                return Err(Error::new(
+                    p.span(),
+                    format!("The index {index} is already used by another authenticator"),
+                ));
+            }
+
             let id = p
                 .clone()
                 .segments
@@ -79,7 +146,12 @@ impl Parse for AuthMacroInput {
                 .concat();
             let id = Ident::new(&id, Span::call_site().into());
 
-            authenticators.push((id.clone(), p));
+            authenticators.push(AuthenticatorInput {
+                id,
+                path: p,
+                index,
+                deprecated,
+            });
         }
 
         if input.peek(Token![;]) {
@@ -91,10 +163,23 @@ impl Parse for AuthMacroInput {
             name,
             authority,
             authenticators,
+            generate_tests,
         })
     }
 }
 
+fn to_snake_case(s: &str) -> String {
+    s.chars()
+        .enumerate()
+        .fold(String::new(), |mut acc, (i, c)| {
+            if c.is_uppercase() && i > 0 {
+                acc.push('_');
+            }
+            acc.extend(c.to_lowercase());
+            acc
+        })
+}
+
 #[proc_macro]
 pub fn composite_authenticator(input: TokenStream) -> TokenStream {
     let AuthMacroInput {
@@ -102,6 +187,7 @@ pub fn composite_authenticator(input: TokenStream) -> TokenStream {
         name,
         authority,
         authenticators,
+        generate_tests,
     } = parse_macro_input!(input as AuthMacroInput);
 
     // Dynamically create identifiers based on `name`
@@ -114,76 +200,125 @@ pub fn composite_authenticator(input: TokenStream) -> TokenStream {
     let auth_variants = authenticators
         .clone()
         .into_iter()
-        .map(|(id, path)| {
-            quote! {
-                #id(<#path as Authenticator>::DeviceAttestation)
-            }
-        })
+        .map(
+            |AuthenticatorInput {
+                 id, path, index, ..
+             }| {
+                quote! {
+                    #[codec(index = #index)]
+                    #id(<#path as Authenticator>::DeviceAttestation)
+                }
+            },
+        )
         .collect::<Vec<_>>();
 
     let device_variants = authenticators
         .clone()
         .into_iter()
-        .map(|(id, path)| {
-            quote! {
-                #id(<#path as Authenticator>::Device)
-            }
-        })
+        .map(
+            |AuthenticatorInput {
+                 id, path, index, ..
+             }| {
+                quote! {
+                    #[codec(index = #index)]
+                    #id(<#path as Authenticator>::Device)
+                }
+            },
+        )
         .collect::<Vec<_>>();
 
     let credential_variants = authenticators
         .clone()
         .into_iter()
-        .map(|(id, path)| {
-            quote! {
-                #id(<<#path as Authenticator>::Device as UserAuthenticator>::Credential)
-            }
-        })
+        .map(
+            |AuthenticatorInput {
+                 id, path, index, ..
+             }| {
+                quote! {
+                    #[codec(index = #index)]
+                    #id(<<#path as Authenticator>::Device as UserAuthenticator>::Credential)
+                }
+            },
+        )
         .collect::<Vec<_>>();
 
-    let match_attestations = authenticators.clone().into_iter().map(|(id, p)| {
-        quote! {
-            #device_attestation::#id(attestation) => {
-                #device::#id(#p::verify_device(attestation, xtc)?)
+    // Deprecated authenticators can still verify their existing devices, but don't take new ones.
+    let match_attestations = authenticators.clone().into_iter().map(
+        |AuthenticatorInput {
+             id,
+             path,
+             deprecated,
+             ..
+         }| {
+            if deprecated {
+                quote! {
+                    #device_attestation::#id(_) => return None
+                }
+            } else {
+                quote! {
+                    #device_attestation::#id(attestation) => {
+                        #device::#id(#path::verify_device(attestation, xtc)?)
+                    }
+                }
             }
-        }
-    });
+        },
+    );
 
-    let match_device_id_from_attestation = authenticators.clone().into_iter().map(|(id, _)| {
-        quote! {
-            #device_attestation::#id(attestation) => attestation.device_id()
-        }
-    });
+    let match_device_id_from_attestation =
+        authenticators
+            .clone()
+            .into_iter()
+            .map(|AuthenticatorInput { id, .. }| {
+                quote! {
+                    #device_attestation::#id(attestation) => attestation.device_id()
+                }
+            });
 
-    let match_device_id_from_device = authenticators.clone().into_iter().map(|(id, _)| {
-        quote! {
-            #device::#id(device) => device.device_id()
-        }
-    });
+    let match_device_id_from_device =
+        authenticators
+            .clone()
+            .into_iter()
+            .map(|AuthenticatorInput { id, .. }| {
+                quote! {
+                    #device::#id(device) => device.device_id()
+                }
+            });
 
-    let match_verify_user = authenticators.clone().into_iter().map(|(id, _)| {
-        quote! {
-            (
-                #device::#id(device),
-                #credential::#id(credential),
-            ) => device.verify_user(credential, xtc)
-        }
-    });
+    let match_verify_user =
+        authenticators
+            .clone()
+            .into_iter()
+            .map(|AuthenticatorInput { id, .. }| {
+                quote! {
+                    (
+                        #device::#id(device),
+                        #credential::#id(credential),
+                    ) => device.verify_user(credential, xtc)
+                }
+            });
 
-    let match_verify_credential = authenticators.clone().into_iter().map(|(id, _)| {
-        quote! {
-            (
-                #device::#id(device),
-                #credential::#id(credential),
-            ) => device.verify_credential(credential)
-        }
-    });
+    let match_verify_credential =
+        authenticators
+            .clone()
+            .into_iter()
+            .map(|AuthenticatorInput { id, .. }| {
+                quote! {
+                    (
+                        #device::#id(device),
+                        #credential::#id(credential),
+                    ) => device.verify_credential(credential)
+                }
+            });
 
-    let match_user_id = authenticators.clone().into_iter().map(|(id, _)| {
-        quote! {
-            #credential::#id(credential) => credential.user_id()
-        }
-    });
+    let match_user_id =
+        authenticators
+            .clone()
+            .into_iter()
+            .map(|AuthenticatorInput { id, .. }| {
+                quote! {
+                    #credential::#id(credential) => credential.user_id()
+                }
+            });
 
     let prelude_crate = {
         let fc_name = proc_macro_crate::crate_name("fc-traits-authn");
@@ -204,6 +339,76 @@ pub fn composite_authenticator(input: TokenStream) -> TokenStream {
         }
     };
 
+    let tests = generate_tests.then(|| {
+        let tests_mod = format_ident!("{}_composite_tests", to_snake_case(&name.to_string()));
+        let check_variants = |composite: &Ident, check: &Ident| {
+            authenticators
+                .iter()
+                .map(|AuthenticatorInput { id, index, .. }| {
+                    quote! {
+                        #check::<#composite>(#index, |value| matches!(value, #composite::#id(_)));
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let round_trip = format_ident!("round_trip");
+        let round_trip_device = format_ident!("round_trip_device");
+        let check_attestations = check_variants(&device_attestation, &round_trip);
+        let check_devices = check_variants(&device, &round_trip_device);
+        let check_credentials = check_variants(&credential, &round_trip);
+        let check_max_encoded_len = authenticators.iter().map(|AuthenticatorInput { path, .. }| {
+            quote! {
+                assert!(
+                    <#device as MaxEncodedLen>::max_encoded_len()
+                        > <<#path as Authenticator>::Device as MaxEncodedLen>::max_encoded_len()
+                );
+            }
+        });
+
+        quote! {
+            #[cfg(test)]
+            mod #tests_mod {
+                use super::*;
+
+                const INPUT_LEN: usize = 1024;
+
+                /// Decodes a value of the variant at `index` from zeroed input, and checks it
+                /// encodes back to the same bytes. Variants whose inner type can't be decoded from
+                /// zeroed input are skipped. Returns the length of the encoded value, if any.
+                fn round_trip<T: Encode + Decode>(index: u8, is_variant: impl Fn(&T) -> bool) -> Option<usize> {
+                    let mut input = [0u8; INPUT_LEN];
+                    input[0] = index;
+                    let value = T::decode(&mut &input[..]).ok()?;
+                    assert!(is_variant(&value), "index {index} decodes as another variant");
+
+                    let encoded = value.encode();
+                    assert_eq!(&encoded[..], &input[..encoded.len()]);
+                    let decoded = T::decode(&mut &encoded[..]).expect("encoded values decode; qed");
+                    assert_eq!(decoded.encode(), encoded);
+                    Some(encoded.len())
+                }
+
+                fn round_trip_device<T: Encode + Decode + MaxEncodedLen>(index: u8, is_variant: impl Fn(&T) -> bool) {
+                    if let Some(len) = round_trip(index, is_variant) {
+                        assert!(len <= T::max_encoded_len());
+                    }
+                }
+
+                #[test]
+                fn variants_round_trip_at_their_pinned_index() {
+                    #(#check_attestations)*
+                    #(#check_devices)*
+                    #(#check_credentials)*
+                }
+
+                #[test]
+                fn max_encoded_len_covers_every_device() {
+                    #(#check_max_encoded_len)*
+                }
+            }
+        }
+    });
+
     // Generate the full struct and impl code
     let expanded = quote! {
         use #prelude_crate::prelude::*;
@@ -331,6 +536,8 @@ pub fn composite_authenticator(input: TokenStream) -> TokenStream {
                 }
             }
         }
+
+        #tests
     };
 
     TokenStream::from(expanded)
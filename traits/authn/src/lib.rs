@@ -7,6 +7,8 @@ use frame_support::{traits::Get, Parameter};
 #[cfg(feature = "runtime")]
 use scale_info::TypeInfo;
 
+#[cfg(all(test, feature = "runtime"))]
+mod tests;
 pub mod util;
 
 pub use fc_traits_authn_proc::composite_authenticator;
@@ -26,19 +28,41 @@ pub mod prelude {
     };
 }
 
+/// Declares composite authenticators, which combine several authenticators into one.
+///
+/// Each authenticator becomes a variant of the composite device attestation, device and
+/// credential types. Variants take the codec index of their position unless pinned with
+/// `#[index = N]`, so pin them to keep stored devices decodable when authenticators are added or
+/// removed. Authenticators marked as `#[deprecated]` keep verifying the devices that were
+/// registered with them, but reject new device attestations.
+///
+/// `#[generate_tests]` generates round-trip encoding and `MaxEncodedLen` tests for the composite
+/// types.
+///
+/// ```ignore
+/// composite_authenticators! {
+///     #[generate_tests]
+///     pub Pass<AuthorityA> {
+///         #[index = 0] #[deprecated] AuthA,
+///         #[index = 2] AuthB,
+///     };
+/// }
+/// ```
 #[macro_export]
 macro_rules! composite_authenticators {
     // Match a single composite authenticator with the format:
     // pub CompositePassA<AuthorityA> { AuthA, AuthB };
     ($(
+        $(#[$attr:meta])*
         pub $name:path {
-            $($auth:path),* $(,)?
+            $($(#[$auth_attr:meta])* $auth:path),* $(,)?
         };
     )*) => {
         $(
             $crate::composite_authenticator!(
+                $(#[$attr])*
                 pub $name {
-                    $($auth),*
+                    $($(#[$auth_attr])* $auth),*
                 }
             );
         )*
@@ -0,0 +1,187 @@
+use super::*;
+
+use frame_support::{derive_impl, parameter_types, sp_runtime::traits::Hash};
+use frame_system::pallet_prelude::BlockNumberFor;
+use util::BlockHashChallenger;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+    #[runtime::runtime]
+    #[runtime::derive(RuntimeCall, RuntimeEvent, RuntimeError, RuntimeOrigin, RuntimeTask)]
+    pub struct Test;
+
+    #[runtime::pallet_index(0)]
+    pub type System = frame_system;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+}
+
+parameter_types! {
+    pub const ChallengeWindow: BlockNumberFor<Test> = 5;
+}
+
+type RecentBlocks = BlockHashChallenger<Test, ChallengeWindow>;
+
+const XTC: &[u8] = b"extrinsic context";
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext = sp_io::TestExternalities::default();
+    ext.execute_with(|| run_to_block(10));
+    ext
+}
+
+/// Initializes blocks up to `n`, registering the hash of their parents.
+fn run_to_block(n: BlockNumberFor<Test>) {
+    while System::block_number() < n {
+        let number = System::block_number() + 1;
+        let parent_hash = <Test as frame_system::Config>::Hashing::hash_of(&number);
+        System::initialize(&number, &parent_hash, &Default::default());
+    }
+}
+
+#[test]
+fn challenge_depends_on_block_hash_and_extrinsic_context() {
+    new_test_ext().execute_with(|| {
+        assert_ne!(
+            RecentBlocks::generate(&8, &XTC),
+            RecentBlocks::generate(&9, &XTC)
+        );
+        assert_ne!(
+            RecentBlocks::generate(&9, &XTC),
+            RecentBlocks::generate(&9, &b"other context")
+        );
+    });
+}
+
+#[test]
+fn accepts_recent_blocks() {
+    new_test_ext().execute_with(|| {
+        for cx in 5..10 {
+            let challenge = RecentBlocks::generate(&cx, &XTC);
+            assert_eq!(
+                RecentBlocks::check_challenge(&cx, &XTC, &challenge),
+                Some(())
+            );
+        }
+    });
+}
+
+#[test]
+fn rejects_other_extrinsic_context() {
+    new_test_ext().execute_with(|| {
+        let challenge = RecentBlocks::generate(&9, &XTC);
+        assert!(RecentBlocks::check_challenge(&9, &b"other context", &challenge).is_none());
+    });
+}
+
+#[test]
+fn rejects_current_and_future_blocks() {
+    new_test_ext().execute_with(|| {
+        for cx in [10, 11] {
+            let challenge = RecentBlocks::generate(&cx, &XTC);
+            assert!(RecentBlocks::check_challenge(&cx, &XTC, &challenge).is_none());
+        }
+    });
+}
+
+#[test]
+fn challenges_expire_after_window() {
+    new_test_ext().execute_with(|| {
+        let challenge = RecentBlocks::generate(&4, &XTC);
+        assert!(RecentBlocks::check_challenge(&4, &XTC, &challenge).is_none());
+
+        let challenge = RecentBlocks::generate(&9, &XTC);
+        assert_eq!(
+            RecentBlocks::check_challenge(&9, &XTC, &challenge),
+            Some(())
+        );
+        run_to_block(14);
+        assert_eq!(
+            RecentBlocks::check_challenge(&9, &XTC, &challenge),
+            Some(())
+        );
+        run_to_block(15);
+        assert!(RecentBlocks::check_challenge(&9, &XTC, &challenge).is_none());
+    });
+}
+
+#[test]
+fn rejects_blocks_without_known_hash() {
+    new_test_ext().execute_with(|| {
+        frame_system::BlockHash::<Test>::remove(9);
+        let challenge = RecentBlocks::generate(&9, &XTC);
+        assert!(RecentBlocks::check_challenge(&9, &XTC, &challenge).is_none());
+    });
+}
+
+mod composite {
+    use super::*;
+    use crate::util::dummy::{self, DummyAttestation, DummyCredential, DUMMY_USER};
+    use frame_support::sp_runtime::str_array as s;
+
+    parameter_types! {
+        pub const AuthorityA: AuthorityId = s("authority_a");
+        pub const AuthorityB: AuthorityId = s("authority_b");
+    }
+
+    type Legacy = dummy::Dummy<AuthorityA>;
+    type Current = dummy::Dummy<AuthorityB>;
+
+    crate::composite_authenticators! {
+        #[generate_tests]
+        pub Pinned<AuthorityA> {
+            #[index = 3] #[deprecated] Legacy,
+            #[index = 1] Current,
+        };
+    }
+
+    const DEVICE_A: DeviceId = s("device_a");
+    const DEVICE_B: DeviceId = s("device_b");
+
+    #[test]
+    fn variants_are_encoded_with_their_pinned_index() {
+        let attestation = PinnedDeviceAttestation::Legacy(DummyAttestation::new(true, DEVICE_A));
+        assert_eq!(attestation.encode()[0], 3);
+
+        let credential = PinnedCredential::Current(DummyCredential::new(true, DUMMY_USER));
+        assert_eq!(credential.encode()[0], 1);
+    }
+
+    #[test]
+    fn deprecated_authenticators_reject_new_devices() {
+        assert!(PinnedAuthenticator::verify_device(
+            PinnedDeviceAttestation::Legacy(DummyAttestation::new(true, DEVICE_A)),
+            &XTC,
+        )
+        .is_none());
+        assert!(PinnedAuthenticator::verify_device(
+            PinnedDeviceAttestation::Current(DummyAttestation::new(true, DEVICE_B)),
+            &XTC,
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn deprecated_authenticators_still_verify_existing_devices() {
+        // A device registered before the authenticator was deprecated, as read from storage.
+        let mut device = PinnedDevice::decode(
+            &mut &PinnedDevice::Legacy(DummyAttestation::<AuthorityA>::new(true, DEVICE_A).into())
+                .encode()[..],
+        )
+        .expect("encoded devices decode; qed");
+
+        assert_eq!(device.device_id(), &DEVICE_A);
+        assert_eq!(
+            device.verify_user(
+                &PinnedCredential::Legacy(DummyCredential::new(true, DUMMY_USER)),
+                &XTC
+            ),
+            Some(())
+        );
+    }
+}
@@ -7,12 +7,17 @@ mod runtime {
     use core::marker::PhantomData;
 
     use codec::{Decode, DecodeWithMemTracking, Encode, FullCodec, MaxEncodedLen};
-    use frame_support::{sp_runtime::traits::TrailingZeroInput, traits::Get, PalletId};
+    use frame_support::{
+        sp_runtime::traits::{BlakeTwo256, Hash, TrailingZeroInput},
+        traits::Get,
+        PalletId,
+    };
+    use frame_system::pallet_prelude::BlockNumberFor;
     use scale_info::TypeInfo;
 
     use crate::{
-        Authenticator, AuthorityId, Challenger, CxOf, DeviceChallengeResponse, DeviceId,
-        UserAuthenticator, UserChallengeResponse,
+        Authenticator, AuthorityId, Challenge, Challenger, CxOf, DeviceChallengeResponse, DeviceId,
+        ExtrinsicContext, UserAuthenticator, UserChallengeResponse,
     };
 
     use super::VerifyCredential;
@@ -30,6 +35,40 @@ mod runtime {
         }
     }
 
+    /// A [`Challenger`] whose context is the number of a recent block.
+    ///
+    /// Challenges are derived from the hash of that block and the extrinsic context,
+    /// and are only accepted while the block is at most `Window` blocks old. Since the
+    /// hash of the current block is not known yet, contexts must refer to past blocks.
+    ///
+    /// `Window` should not exceed the runtime's `BlockHashCount`, as challenges can't be
+    /// checked once the hash of their block is pruned.
+    pub struct BlockHashChallenger<T, Window>(PhantomData<(T, Window)>);
+
+    impl<T, Window> Challenger for BlockHashChallenger<T, Window>
+    where
+        T: frame_system::Config,
+        Window: Get<BlockNumberFor<T>>,
+    {
+        type Context = BlockNumberFor<T>;
+
+        fn generate(cx: &Self::Context, xtc: &impl ExtrinsicContext) -> Challenge {
+            let block_hash = frame_system::Pallet::<T>::block_hash(cx);
+            BlakeTwo256::hash_of(&(block_hash, xtc.as_ref())).0
+        }
+
+        fn check_challenge(
+            cx: &Self::Context,
+            xtc: &impl ExtrinsicContext,
+            challenge: &[u8],
+        ) -> Option<()> {
+            let now = frame_system::Pallet::<T>::block_number();
+            (*cx < now && now - *cx <= Window::get()).then_some(())?;
+            (frame_system::Pallet::<T>::block_hash(cx) != T::Hash::default()).then_some(())?;
+            Self::generate(cx, xtc).eq(challenge).then_some(())
+        }
+    }
+
     #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, PartialEq, Eq, Debug)]
     #[scale_info(skip_type_params(Dev, Att))]
     /// Convenient auto-implementor of the Authenticator trait
@@ -0,0 +1,165 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Substrate Keys Authenticator
+//!
+//! An [`Authenticator`](fc_traits_authn::Authenticator) whose devices are Substrate
+//! public keys (sr25519, ed25519 or ecdsa), like the ones held by hardware wallets
+//! or browser extensions.
+//!
+//! - Devices are registered with a [`KeyAttestation`], that proves possession of
+//!   the key by signing the challenge generated by the configured [`Challenger`].
+//! - Users are authenticated with a [`KeySignature`] over the challenge generated
+//!   for the extrinsic being authenticated.
+//!
+//! Signatures are accepted either over the raw challenge, or wrapped in
+//! `<Bytes>..</Bytes>`, as done by wallets when signing arbitrary payloads.
+//!
+//! The [`DeviceId`] of a key is the [`AccountId32`] it derives to, so the same key
+//! can't be registered twice on an account.
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use fc_traits_authn::{
+    util::{Auth, Dev, VerifyCredential},
+    AuthorityId, Challenge, Challenger, DeviceChallengeResponse, DeviceId, HashedUserId,
+    UserChallengeResponse,
+};
+use frame_support::Parameter;
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{IdentifyAccount, Verify},
+    AccountId32, MultiSignature, MultiSigner,
+};
+
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(test)]
+mod tests;
+
+type CxOf<Ch> = <Ch as Challenger>::Context;
+
+/// A device that verifies [`KeySignature`]s.
+pub type SubstrateKeyDevice<A, Ch> = Dev<KeyDevice, A, Ch, KeySignature<CxOf<Ch>>>;
+/// An authenticator for Substrate keys, under authority `A`, with challenges generated by `Ch`.
+pub type SubstrateKey<A, Ch> = Auth<SubstrateKeyDevice<A, Ch>, KeyAttestation<CxOf<Ch>>>;
+
+/// The [`DeviceId`] of a key: the account it derives to.
+pub fn device_id_for(signer: &MultiSigner) -> DeviceId {
+    signer.clone().into_account().into()
+}
+
+/// The data used to register a key as a device.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+pub struct KeyAttestation<Cx> {
+    pub authority_id: AuthorityId,
+    /// Must match [`device_id_for`] the `public` key.
+    pub device_id: DeviceId,
+    pub public: MultiSigner,
+    /// The context the challenge was generated with.
+    pub context: Cx,
+    pub challenge: Challenge,
+    /// The signature of `challenge`, made with `public`.
+    pub signature: MultiSignature,
+}
+
+/// The data used to authenticate a user with a registered key.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+pub struct KeySignature<Cx> {
+    pub authority_id: AuthorityId,
+    pub user_id: HashedUserId,
+    /// The context the challenge was generated with.
+    pub context: Cx,
+    pub challenge: Challenge,
+    /// The signature of `challenge`, made with the registered key.
+    pub signature: MultiSignature,
+}
+
+/// A registered key, identified by the account it derives to.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo,
+)]
+pub struct KeyDevice(pub DeviceId);
+
+impl AsRef<DeviceId> for KeyDevice {
+    fn as_ref(&self) -> &DeviceId {
+        &self.0
+    }
+}
+
+impl<Cx> VerifyCredential<KeySignature<Cx>> for KeyDevice {
+    fn verify(&mut self, credential: &KeySignature<Cx>) -> Option<()> {
+        verify_signature(
+            &credential.signature,
+            &credential.challenge,
+            &AccountId32::new(self.0),
+        )
+        .then_some(())
+    }
+}
+
+impl<A, Ch> From<KeyAttestation<CxOf<Ch>>> for SubstrateKeyDevice<A, Ch>
+where
+    Ch: Challenger,
+{
+    fn from(attestation: KeyAttestation<CxOf<Ch>>) -> Self {
+        Dev::new(KeyDevice(attestation.device_id))
+    }
+}
+
+impl<Cx: Parameter + 'static> DeviceChallengeResponse<Cx> for KeyAttestation<Cx> {
+    fn is_valid(&self) -> bool {
+        let who = self.public.clone().into_account();
+        device_id_for(&self.public) == self.device_id
+            && verify_signature(&self.signature, &self.challenge, &who)
+    }
+
+    fn used_challenge(&self) -> (Cx, Challenge) {
+        (self.context.clone(), self.challenge)
+    }
+
+    fn authority(&self) -> AuthorityId {
+        self.authority_id
+    }
+
+    fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+}
+
+impl<Cx: Parameter + 'static> UserChallengeResponse<Cx> for KeySignature<Cx> {
+    fn is_valid(&self) -> bool {
+        // The signature is checked against the device key when verifying the credential
+        true
+    }
+
+    fn used_challenge(&self) -> (Cx, Challenge) {
+        (self.context.clone(), self.challenge)
+    }
+
+    fn authority(&self) -> AuthorityId {
+        self.authority_id
+    }
+
+    fn user_id(&self) -> HashedUserId {
+        self.user_id
+    }
+}
+
+/// Verifies `signature` over the `challenge`, either raw or wrapped in `<Bytes>`.
+fn verify_signature(signature: &MultiSignature, challenge: &Challenge, who: &AccountId32) -> bool {
+    signature.verify(&challenge[..], who) || signature.verify(&wrap_bytes(challenge)[..], who)
+}
+
+const BYTES_PREFIX: &[u8] = b"<Bytes>";
+const BYTES_SUFFIX: &[u8] = b"</Bytes>";
+const WRAPPED_LEN: usize =
+    BYTES_PREFIX.len() + core::mem::size_of::<Challenge>() + BYTES_SUFFIX.len();
+
+fn wrap_bytes(challenge: &Challenge) -> [u8; WRAPPED_LEN] {
+    let mut wrapped = [0u8; WRAPPED_LEN];
+    let (prefix, rest) = wrapped.split_at_mut(BYTES_PREFIX.len());
+    let (payload, suffix) = rest.split_at_mut(challenge.len());
+    prefix.copy_from_slice(BYTES_PREFIX);
+    payload.copy_from_slice(challenge);
+    suffix.copy_from_slice(BYTES_SUFFIX);
+    wrapped
+}
@@ -0,0 +1,75 @@
+//! Builders of attestations and signatures, for testing runtimes and pallets that
+//! use the [`SubstrateKey`](crate::SubstrateKey) authenticator.
+
+use core::marker::PhantomData;
+
+use fc_traits_authn::{AuthorityId, Challenger, ExtrinsicContext, HashedUserId};
+use frame_support::traits::Get;
+use sp_core::Pair;
+use sp_runtime::{MultiSignature, MultiSigner};
+
+use crate::{device_id_for, CxOf, KeyAttestation, KeySignature};
+
+/// Signs attestations and credentials with a key pair, under authority `A` and
+/// with challenges generated by `Ch`.
+///
+/// ```ignore
+/// let signer = KeySigner::<_, Authority, BlockChallenger>::new(sr25519::Pair::from_string("//Alice", None)?);
+/// let device = SubstrateKey::<Authority, BlockChallenger>::verify_device(signer.attest(cx, &xtc), &xtc);
+/// ```
+pub struct KeySigner<P, A, Ch>(P, PhantomData<(A, Ch)>);
+
+impl<P, A, Ch> KeySigner<P, A, Ch>
+where
+    P: Pair,
+    P::Public: Into<MultiSigner>,
+    P::Signature: Into<MultiSignature>,
+    A: Get<AuthorityId>,
+    Ch: Challenger,
+{
+    pub fn new(pair: P) -> Self {
+        Self(pair, PhantomData)
+    }
+
+    pub fn public(&self) -> MultiSigner {
+        self.0.public().into()
+    }
+
+    pub fn device_id(&self) -> fc_traits_authn::DeviceId {
+        device_id_for(&self.public())
+    }
+
+    /// Builds an attestation registering the key, signing the challenge for `context` and `xtc`.
+    pub fn attest(
+        &self,
+        context: CxOf<Ch>,
+        xtc: &impl ExtrinsicContext,
+    ) -> KeyAttestation<CxOf<Ch>> {
+        let challenge = Ch::generate(&context, xtc);
+        KeyAttestation {
+            authority_id: A::get(),
+            device_id: self.device_id(),
+            public: self.public(),
+            context,
+            challenge,
+            signature: self.0.sign(&challenge).into(),
+        }
+    }
+
+    /// Builds a credential for `user_id`, signing the challenge for `context` and `xtc`.
+    pub fn sign(
+        &self,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        xtc: &impl ExtrinsicContext,
+    ) -> KeySignature<CxOf<Ch>> {
+        let challenge = Ch::generate(&context, xtc);
+        KeySignature {
+            authority_id: A::get(),
+            user_id,
+            context,
+            challenge,
+            signature: self.0.sign(&challenge).into(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use super::*;
+use crate::testing::KeySigner;
+use fc_traits_authn::{Authenticator, ExtrinsicContext, UserAuthenticator};
+use frame_support::{parameter_types, sp_runtime::str_array as s};
+use sp_core::{ecdsa, ed25519, hashing::blake2_256, sr25519, Pair};
+
+parameter_types! {
+    pub const KeysAuthority: AuthorityId = s("substrate_keys_authority");
+}
+
+/// Derives challenges from a block number, like a runtime would.
+pub struct BlockChallenger;
+impl Challenger for BlockChallenger {
+    type Context = u32;
+
+    fn generate(cx: &Self::Context, xtc: &impl ExtrinsicContext) -> Challenge {
+        blake2_256(&[&cx.to_le_bytes()[..], xtc.as_ref()].concat())
+    }
+}
+
+type Keys = SubstrateKey<KeysAuthority, BlockChallenger>;
+type Signer<P> = KeySigner<P, KeysAuthority, BlockChallenger>;
+
+const XTC: &[u8] = b"extrinsic context";
+const BLOCK: u32 = 10;
+const USER: HashedUserId = s("user");
+
+fn signer<P: Pair>(seed: &str) -> Signer<P>
+where
+    P::Public: Into<MultiSigner>,
+    P::Signature: Into<MultiSignature>,
+{
+    Signer::new(P::from_string(seed, None).expect("valid seed; qed"))
+}
+
+fn register<P: Pair>(signer: &Signer<P>) -> SubstrateKeyDevice<KeysAuthority, BlockChallenger>
+where
+    P::Public: Into<MultiSigner>,
+    P::Signature: Into<MultiSignature>,
+{
+    Keys::verify_device(signer.attest(BLOCK, &XTC), &XTC).expect("valid attestation; qed")
+}
+
+fn authenticates_with<P: Pair>()
+where
+    P::Public: Into<MultiSigner>,
+    P::Signature: Into<MultiSignature>,
+{
+    let alice = signer::<P>("//Alice");
+    let mut device = register(&alice);
+    assert_eq!(device.device_id(), &alice.device_id());
+    assert_eq!(
+        device.verify_user(&alice.sign(USER, BLOCK, &XTC), &XTC),
+        Some(())
+    );
+
+    let bob = signer::<P>("//Bob");
+    assert!(device
+        .verify_user(&bob.sign(USER, BLOCK, &XTC), &XTC)
+        .is_none());
+}
+
+mod attestation {
+    use super::*;
+
+    #[test]
+    fn device_id_is_the_derived_account() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        assert_eq!(
+            alice.device_id(),
+            <[u8; 32]>::from(alice.public().into_account())
+        );
+        let alice = signer::<ecdsa::Pair>("//Alice");
+        assert_eq!(alice.device_id(), blake2_256(alice.public().as_ref()));
+    }
+
+    #[test]
+    fn fails_with_other_extrinsic_context() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        assert!(Keys::verify_device(alice.attest(BLOCK, &XTC), &b"other context").is_none());
+    }
+
+    #[test]
+    fn fails_with_other_authority() {
+        let mut attestation = signer::<sr25519::Pair>("//Alice").attest(BLOCK, &XTC);
+        attestation.authority_id = s("other_authority");
+        assert!(Keys::verify_device(attestation, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_with_other_device_id() {
+        let mut attestation = signer::<sr25519::Pair>("//Alice").attest(BLOCK, &XTC);
+        attestation.device_id = signer::<sr25519::Pair>("//Bob").device_id();
+        assert!(!attestation.is_valid());
+        assert!(Keys::verify_device(attestation, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_when_signed_by_another_key() {
+        let mut attestation = signer::<ed25519::Pair>("//Alice").attest(BLOCK, &XTC);
+        attestation.signature = signer::<ed25519::Pair>("//Bob")
+            .attest(BLOCK, &XTC)
+            .signature;
+        assert!(Keys::verify_device(attestation, &XTC).is_none());
+    }
+
+    #[test]
+    fn accepts_wrapped_payloads() {
+        let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+        let mut attestation = Signer::new(pair.clone()).attest(BLOCK, &XTC);
+        attestation.signature = pair.sign(&wrap_bytes(&attestation.challenge)).into();
+        assert!(Keys::verify_device(attestation, &XTC).is_some());
+    }
+}
+
+mod credential {
+    use super::*;
+
+    #[test]
+    fn works_with_sr25519() {
+        authenticates_with::<sr25519::Pair>();
+    }
+
+    #[test]
+    fn works_with_ed25519() {
+        authenticates_with::<ed25519::Pair>();
+    }
+
+    #[test]
+    fn works_with_ecdsa() {
+        authenticates_with::<ecdsa::Pair>();
+    }
+
+    #[test]
+    fn fails_with_other_extrinsic_context() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        let mut device = register(&alice);
+        assert!(device
+            .verify_user(&alice.sign(USER, BLOCK, &XTC), &b"other context")
+            .is_none());
+    }
+
+    #[test]
+    fn fails_with_other_context() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        let mut device = register(&alice);
+        let mut credential = alice.sign(USER, BLOCK, &XTC);
+        credential.context = BLOCK + 1;
+        assert!(device.verify_user(&credential, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_with_tampered_challenge() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        let mut device = register(&alice);
+        let mut credential = alice.sign(USER, BLOCK, &XTC);
+        credential.challenge = BlockChallenger::generate(&BLOCK, &b"other context");
+        assert!(device.verify_user(&credential, &b"other context").is_none());
+    }
+
+    #[test]
+    fn accepts_wrapped_payloads() {
+        let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+        let signer = Signer::new(pair.clone());
+        let mut device = register(&signer);
+        let mut credential = signer.sign(USER, BLOCK, &XTC);
+        credential.signature = pair.sign(&wrap_bytes(&credential.challenge)).into();
+        assert_eq!(device.verify_user(&credential, &XTC), Some(()));
+    }
+}
+
+mod composite {
+    use super::*;
+    use fc_traits_authn::{composite_authenticators, util::dummy};
+
+    composite_authenticators! {
+        pub Pass<KeysAuthority> {
+            Keys,
+            dummy::Dummy::<KeysAuthority>,
+        };
+    }
+
+    #[test]
+    fn works_within_composite_authenticators() {
+        let alice = signer::<sr25519::Pair>("//Alice");
+        let mut device = PassAuthenticator::verify_device(
+            PassDeviceAttestation::Keys(alice.attest(BLOCK, &XTC)),
+            &XTC,
+        )
+        .unwrap();
+        assert_eq!(device.device_id(), &alice.device_id());
+
+        assert_eq!(
+            device.verify_user(&PassCredential::Keys(alice.sign(USER, BLOCK, &XTC)), &XTC),
+            Some(())
+        );
+        assert!(device
+            .verify_user(
+                &PassCredential::DummyDummy(dummy::DummyCredential::new(true, USER)),
+                &XTC
+            )
+            .is_none());
+    }
+}
@@ -0,0 +1,267 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # WebAuthn Authenticator
+//!
+//! An [`Authenticator`](fc_traits_authn::Authenticator) for passkeys, using the
+//! [WebAuthn](https://www.w3.org/TR/webauthn-3/) responses handed over by the browser.
+//!
+//! - Devices are registered with an [`Attestation`], built from the response of
+//!   `navigator.credentials.create()`. Only ES256 (P-256) credentials are supported.
+//!   Attestation statements are not verified, so the device is trusted on first use.
+//! - Users are authenticated with an [`Assertion`], built from the response of
+//!   `navigator.credentials.get()`. Its signature is verified with the public key
+//!   stored in the [`Device`], along with its signature counter.
+//!
+//! In both cases, the challenge is taken from the `clientDataJSON`, and must
+//! have been generated by the configured [`Challenger`] for the given context.
+//! The `origin` of the `clientDataJSON` must also be one of the configured allowed
+//! origins, so credentials created or used by other sites sharing the relying party
+//! ID (e.g. a subdomain) are rejected.
+//!
+//! ```ignore
+//! parameter_types! {
+//!     pub const PasskeyOrigins: &'static [&'static str] = &["https://example.org"];
+//! }
+//! type Passkeys = WebAuthn<Authority, Challenger, PasskeyOrigins>;
+//! ```
+
+extern crate alloc;
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use fc_traits_authn::{
+    util::{Auth, Dev, VerifyCredential},
+    AuthorityId, Challenge, Challenger, DeviceChallengeResponse, DeviceId, HashedUserId,
+    UserChallengeResponse,
+};
+use frame_support::{
+    traits::{ConstU32, Get},
+    BoundedVec, CloneNoBound, DebugNoBound, EqNoBound, Parameter, PartialEqNoBound,
+};
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
+
+use parse::{attestation_auth_data, AuthenticatorData, ClientData};
+
+mod parse;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(test)]
+mod tests;
+
+type CxOf<Ch> = <Ch as Challenger>::Context;
+
+/// A compressed SEC1 encoded P-256 public key.
+pub type PublicKey = [u8; 33];
+
+pub type AttestationObject = BoundedVec<u8, ConstU32<2048>>;
+pub type AuthenticatorDataBytes = BoundedVec<u8, ConstU32<512>>;
+pub type ClientDataJson = BoundedVec<u8, ConstU32<1024>>;
+/// A DER encoded ECDSA signature.
+pub type DerSignature = BoundedVec<u8, ConstU32<72>>;
+
+/// A device that verifies [`Assertion`]s made from the origins `O`.
+pub type WebAuthnDevice<A, Ch, O> = Dev<Device, A, Ch, Assertion<CxOf<Ch>, O>>;
+/// An authenticator for passkeys, under authority `A`, with challenges generated by `Ch`,
+/// used from the origins `O`.
+pub type WebAuthn<A, Ch, O> = Auth<WebAuthnDevice<A, Ch, O>, Attestation<CxOf<Ch>, O>>;
+
+/// The [`DeviceId`] of a passkey, derived from its credential ID.
+pub fn device_id_for(credential_id: &[u8]) -> DeviceId {
+    Sha256::digest(credential_id).into()
+}
+
+/// The data used to register a passkey as a device, from one of the origins `O`.
+#[derive(
+    CloneNoBound,
+    DebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    TypeInfo,
+)]
+#[scale_info(skip_type_params(O))]
+pub struct Attestation<Cx, O>
+where
+    Cx: Parameter,
+{
+    pub authority_id: AuthorityId,
+    /// Must match [`device_id_for`] the attested credential.
+    pub device_id: DeviceId,
+    /// The context the challenge was generated with.
+    pub context: Cx,
+    /// The CBOR encoded `attestationObject` of the `AuthenticatorAttestationResponse`.
+    pub attestation_object: AttestationObject,
+    pub client_data: ClientDataJson,
+    #[codec(skip)]
+    pub origins: PhantomData<O>,
+}
+
+/// The data used to authenticate a user with a registered passkey, from one of the origins `O`.
+#[derive(
+    CloneNoBound,
+    DebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    TypeInfo,
+)]
+#[scale_info(skip_type_params(O))]
+pub struct Assertion<Cx, O>
+where
+    Cx: Parameter,
+{
+    pub authority_id: AuthorityId,
+    pub user_id: HashedUserId,
+    /// The context the challenge was generated with.
+    pub context: Cx,
+    /// The `authenticatorData` of the `AuthenticatorAssertionResponse`.
+    pub authenticator_data: AuthenticatorDataBytes,
+    pub client_data: ClientDataJson,
+    pub signature: DerSignature,
+    #[codec(skip)]
+    pub origins: PhantomData<O>,
+}
+
+/// A registered passkey.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo,
+)]
+pub struct Device {
+    pub device_id: DeviceId,
+    /// The hash of the relying party the passkey is scoped to.
+    pub rp_id_hash: [u8; 32],
+    pub public_key: PublicKey,
+    /// The last signature counter seen, to detect cloned authenticators.
+    pub sign_count: u32,
+}
+
+impl Device {
+    fn from_attestation<Cx: Parameter, O>(attestation: &Attestation<Cx, O>) -> Option<Self> {
+        let auth_data =
+            AuthenticatorData::parse(attestation_auth_data(&attestation.attestation_object)?)?;
+        let credential = auth_data.attested_credential?;
+        Some(Self {
+            device_id: device_id_for(credential.credential_id),
+            rp_id_hash: auth_data.rp_id_hash,
+            public_key: credential.public_key,
+            sign_count: auth_data.sign_count,
+        })
+    }
+}
+
+impl AsRef<DeviceId> for Device {
+    fn as_ref(&self) -> &DeviceId {
+        &self.device_id
+    }
+}
+
+impl<Cx: Parameter, O> VerifyCredential<Assertion<Cx, O>> for Device {
+    fn verify(&mut self, assertion: &Assertion<Cx, O>) -> Option<()> {
+        let auth_data = AuthenticatorData::parse(&assertion.authenticator_data)?;
+        (auth_data.rp_id_hash == self.rp_id_hash && auth_data.user_present()).then_some(())?;
+
+        // The signature covers `authenticatorData || SHA-256(clientDataJSON)`
+        let key = VerifyingKey::from_sec1_bytes(&self.public_key).ok()?;
+        let signature = Signature::from_der(&assertion.signature).ok()?;
+        let client_data_hash = Sha256::digest(&assertion.client_data);
+        let message = [&assertion.authenticator_data[..], &client_data_hash[..]].concat();
+        key.verify(&message, &signature).ok()?;
+
+        // Authenticators that don't implement a counter always report zero
+        if auth_data.sign_count != 0 || self.sign_count != 0 {
+            (auth_data.sign_count > self.sign_count).then_some(())?;
+            self.sign_count = auth_data.sign_count;
+        }
+        Some(())
+    }
+}
+
+impl<A, Ch, O> From<Attestation<CxOf<Ch>, O>> for WebAuthnDevice<A, Ch, O>
+where
+    Ch: Challenger,
+{
+    fn from(attestation: Attestation<CxOf<Ch>, O>) -> Self {
+        // Attestations are only unpacked once they're valid, so the fallback (a
+        // device that can't verify any assertion) is never reached.
+        Dev::new(Device::from_attestation(&attestation).unwrap_or(Device {
+            device_id: attestation.device_id,
+            rp_id_hash: [0; 32],
+            public_key: [0; 33],
+            sign_count: u32::MAX,
+        }))
+    }
+}
+
+impl<Cx, O> DeviceChallengeResponse<Cx> for Attestation<Cx, O>
+where
+    Cx: Parameter + 'static,
+    O: Get<&'static [&'static str]> + 'static + Send + Sync,
+{
+    fn is_valid(&self) -> bool {
+        let is_creation = ClientData::parse(&self.client_data).is_some_and(|client_data| {
+            client_data.ty == ClientData::CREATE && is_allowed::<O>(&client_data)
+        });
+        let is_present = attestation_auth_data(&self.attestation_object)
+            .and_then(AuthenticatorData::parse)
+            .is_some_and(|auth_data| auth_data.user_present());
+        is_creation
+            && is_present
+            && Device::from_attestation(self)
+                .is_some_and(|device| device.device_id == self.device_id)
+    }
+
+    fn used_challenge(&self) -> (Cx, Challenge) {
+        (self.context.clone(), challenge_of(&self.client_data))
+    }
+
+    fn authority(&self) -> AuthorityId {
+        self.authority_id
+    }
+
+    fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+}
+
+impl<Cx, O> UserChallengeResponse<Cx> for Assertion<Cx, O>
+where
+    Cx: Parameter + 'static,
+    O: Get<&'static [&'static str]> + 'static + Send + Sync,
+{
+    fn is_valid(&self) -> bool {
+        ClientData::parse(&self.client_data).is_some_and(|client_data| {
+            client_data.ty == ClientData::GET && is_allowed::<O>(&client_data)
+        })
+    }
+
+    fn used_challenge(&self) -> (Cx, Challenge) {
+        (self.context.clone(), challenge_of(&self.client_data))
+    }
+
+    fn authority(&self) -> AuthorityId {
+        self.authority_id
+    }
+
+    fn user_id(&self) -> HashedUserId {
+        self.user_id
+    }
+}
+
+/// Whether `client_data` was collected from one of the origins `O`.
+fn is_allowed<O: Get<&'static [&'static str]>>(client_data: &ClientData) -> bool {
+    O::get().contains(&client_data.origin.as_str())
+}
+
+/// The challenge signed in `client_data`. Unparseable client data yields an
+/// empty challenge, that fails the challenge check.
+fn challenge_of(client_data: &[u8]) -> Challenge {
+    ClientData::parse(client_data)
+        .map(|client_data| client_data.challenge)
+        .unwrap_or_default()
+}
@@ -0,0 +1,154 @@
+//! Parsers for the WebAuthn structures handed over by the browser.
+
+use alloc::string::String;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use fc_traits_authn::Challenge;
+use minicbor::Decoder;
+use p256::ecdsa::VerifyingKey;
+
+use crate::PublicKey;
+
+/// The authenticator confirmed the user was present.
+const FLAG_USER_PRESENT: u8 = 0x01;
+/// The authenticator data includes an attested credential.
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+// COSE key parameters and values for ES256 keys (RFC 9053)
+const COSE_KTY: i64 = 1;
+const COSE_ALG: i64 = 3;
+const COSE_CRV: i64 = -1;
+const COSE_X: i64 = -2;
+const COSE_Y: i64 = -3;
+const COSE_KTY_EC2: i64 = 2;
+const COSE_ALG_ES256: i64 = -7;
+const COSE_CRV_P256: i64 = 1;
+
+/// The relevant fields of a `CollectedClientData`.
+pub(crate) struct ClientData {
+    pub ty: String,
+    pub challenge: Challenge,
+    pub origin: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RawClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+    origin: String,
+}
+
+impl ClientData {
+    pub const CREATE: &'static str = "webauthn.create";
+    pub const GET: &'static str = "webauthn.get";
+
+    /// Parses a `clientDataJSON`, expecting a base64url encoded challenge of
+    /// [`Challenge`] size.
+    pub fn parse(json: &[u8]) -> Option<Self> {
+        let raw: RawClientData = serde_json::from_slice(json).ok()?;
+        let challenge = URL_SAFE_NO_PAD.decode(raw.challenge).ok()?;
+        Some(Self {
+            ty: raw.ty,
+            challenge: challenge.try_into().ok()?,
+            origin: raw.origin,
+        })
+    }
+}
+
+/// A credential attested in the authenticator data.
+pub(crate) struct AttestedCredential<'a> {
+    pub credential_id: &'a [u8],
+    pub public_key: PublicKey,
+}
+
+/// The relevant fields of the authenticator data.
+pub(crate) struct AuthenticatorData<'a> {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential<'a>>,
+}
+
+impl<'a> AuthenticatorData<'a> {
+    /// Parses the authenticator data:
+    /// `rpIdHash (32) | flags (1) | signCount (4) | attestedCredentialData?`.
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let rp_id_hash = data.get(..32)?.try_into().ok()?;
+        let flags = *data.get(32)?;
+        let sign_count = u32::from_be_bytes(data.get(33..37)?.try_into().ok()?);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            // aaguid (16) | credentialIdLength (2) | credentialId | credentialPublicKey
+            let len = u16::from_be_bytes(data.get(53..55)?.try_into().ok()?) as usize;
+            let credential_id = data.get(55..55 + len)?;
+            let public_key = cose_public_key(data.get(55 + len..)?)?;
+            Some(AttestedCredential {
+                credential_id,
+                public_key,
+            })
+        } else {
+            None
+        };
+
+        Some(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+}
+
+/// Extracts `authData` from a CBOR encoded attestation object.
+///
+/// Attestation statements are not verified, so any `fmt` is accepted.
+pub(crate) fn attestation_auth_data(attestation_object: &[u8]) -> Option<&[u8]> {
+    let mut d = Decoder::new(attestation_object);
+    let entries = d.map().ok()??;
+    let mut auth_data = None;
+    for _ in 0..entries {
+        match d.str().ok()? {
+            "authData" => auth_data = Some(d.bytes().ok()?),
+            _ => d.skip().ok()?,
+        }
+    }
+    auth_data
+}
+
+/// Parses an ES256 COSE key into a compressed P-256 public key.
+fn cose_public_key(cose: &[u8]) -> Option<PublicKey> {
+    let mut d = Decoder::new(cose);
+    let entries = d.map().ok()??;
+    let (mut kty, mut alg, mut crv) = (None, None, None);
+    let (mut x, mut y): (Option<&[u8]>, Option<&[u8]>) = (None, None);
+    for _ in 0..entries {
+        match d.i64().ok()? {
+            COSE_KTY => kty = Some(d.i64().ok()?),
+            COSE_ALG => alg = Some(d.i64().ok()?),
+            COSE_CRV => crv = Some(d.i64().ok()?),
+            COSE_X => x = Some(d.bytes().ok()?),
+            COSE_Y => y = Some(d.bytes().ok()?),
+            _ => d.skip().ok()?,
+        }
+    }
+    if (kty, alg, crv)
+        != (
+            Some(COSE_KTY_EC2),
+            Some(COSE_ALG_ES256),
+            Some(COSE_CRV_P256),
+        )
+    {
+        return None;
+    }
+
+    let mut point = [0u8; 65];
+    point[0] = 0x04;
+    point[1..33].copy_from_slice(x.filter(|x| x.len() == 32)?);
+    point[33..].copy_from_slice(y.filter(|y| y.len() == 32)?);
+    let key = VerifyingKey::from_sec1_bytes(&point).ok()?;
+    key.to_encoded_point(true).as_bytes().try_into().ok()
+}
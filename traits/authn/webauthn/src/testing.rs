@@ -0,0 +1,177 @@
+//! A software passkey, for testing runtimes and pallets that use the
+//! [`WebAuthn`](crate::WebAuthn) authenticator. It builds the same responses a browser would
+//! hand over.
+
+use alloc::{format, string::String, vec::Vec};
+use core::marker::PhantomData;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use fc_traits_authn::{
+    AuthorityId, Challenge, Challenger, DeviceId, ExtrinsicContext, HashedUserId,
+};
+use frame_support::traits::Get;
+use minicbor::Encoder;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::{device_id_for, parse::ClientData, Assertion, Attestation, CxOf};
+
+/// The user present flag.
+pub const FLAGS_UP: u8 = 0x01;
+/// The user present and user verified flags.
+pub const FLAGS_UP_UV: u8 = 0x05;
+/// The user present and attested credential data flags.
+pub const FLAGS_UP_AT: u8 = 0x41;
+
+/// A P-256 passkey scoped to a relying party, that registers and authenticates under authority
+/// `A`, with challenges generated by `Ch`.
+///
+/// ```ignore
+/// let mut passkey = SoftwarePasskey::<Authority, BlockChallenger>::new([1; 32], vec![1; 16], "example.org", "https://example.org");
+/// let device = WebAuthn::<Authority, BlockChallenger, Origins>::verify_device(passkey.attest(cx, &xtc), &xtc);
+/// let credential = passkey.assert(user_id, cx, &xtc);
+/// ```
+pub struct SoftwarePasskey<A, Ch> {
+    pub key: SigningKey,
+    pub credential_id: Vec<u8>,
+    pub rp_id: String,
+    pub origin: String,
+    /// The signature counter, increased on every assertion.
+    pub sign_count: u32,
+    _phantom: PhantomData<(A, Ch)>,
+}
+
+impl<A, Ch> SoftwarePasskey<A, Ch>
+where
+    A: Get<AuthorityId>,
+    Ch: Challenger,
+{
+    pub fn new(secret: [u8; 32], credential_id: Vec<u8>, rp_id: &str, origin: &str) -> Self {
+        Self {
+            key: SigningKey::from_bytes(&secret.into()).expect("valid P-256 secret key; qed"),
+            credential_id,
+            rp_id: rp_id.into(),
+            origin: origin.into(),
+            sign_count: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn device_id(&self) -> DeviceId {
+        device_id_for(&self.credential_id)
+    }
+
+    /// The COSE encoded public key, as included in attestations.
+    pub fn cose_key(&self) -> Vec<u8> {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        let mut e = Encoder::new(Vec::new());
+        e.map(5).unwrap();
+        e.i8(1).unwrap().i8(2).unwrap(); // kty: EC2
+        e.i8(3).unwrap().i8(-7).unwrap(); // alg: ES256
+        e.i8(-1).unwrap().i8(1).unwrap(); // crv: P-256
+        e.i8(-2).unwrap().bytes(point.x().unwrap()).unwrap();
+        e.i8(-3).unwrap().bytes(point.y().unwrap()).unwrap();
+        e.into_writer()
+    }
+
+    /// The `authenticatorData`, including the attested credential when a `cose_key` is given.
+    pub fn auth_data(&self, flags: u8, cose_key: Option<&[u8]>) -> Vec<u8> {
+        let mut data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend(self.sign_count.to_be_bytes());
+        if let Some(cose_key) = cose_key {
+            data.extend([0u8; 16]); // aaguid
+            data.extend((self.credential_id.len() as u16).to_be_bytes());
+            data.extend(&self.credential_id);
+            data.extend(cose_key);
+        }
+        data
+    }
+
+    /// The `clientDataJSON` of a ceremony of type `ty`.
+    pub fn client_data(&self, ty: &str, challenge: &Challenge) -> Vec<u8> {
+        let challenge = URL_SAFE_NO_PAD.encode(challenge);
+        let origin = &self.origin;
+        format!(
+            r#"{{"type":"{ty}","challenge":"{challenge}","origin":"{origin}","crossOrigin":false}}"#
+        )
+        .into_bytes()
+    }
+
+    /// Builds an attestation registering the passkey, for the challenge of `context` and `xtc`.
+    pub fn attest<O>(
+        &self,
+        context: CxOf<Ch>,
+        xtc: &impl ExtrinsicContext,
+    ) -> Attestation<CxOf<Ch>, O> {
+        let challenge = Ch::generate(&context, xtc);
+        Attestation {
+            authority_id: A::get(),
+            device_id: self.device_id(),
+            attestation_object: attestation_object(
+                &self.auth_data(FLAGS_UP_AT, Some(&self.cose_key())),
+            )
+            .try_into()
+            .expect("attestation object fits; qed"),
+            client_data: self
+                .client_data(ClientData::CREATE, &challenge)
+                .try_into()
+                .expect("client data fits; qed"),
+            context,
+            origins: PhantomData,
+        }
+    }
+
+    /// Builds an assertion authenticating `user_id`, for the challenge of `context` and `xtc`.
+    pub fn assert<O>(
+        &mut self,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        xtc: &impl ExtrinsicContext,
+    ) -> Assertion<CxOf<Ch>, O> {
+        self.sign_count += 1;
+        let challenge = Ch::generate(&context, xtc);
+        self.assert_with(user_id, context, ClientData::GET, FLAGS_UP_UV, &challenge)
+    }
+
+    /// Builds an assertion signing `challenge` as-is, with the given ceremony type and flags.
+    pub fn assert_with<O>(
+        &self,
+        user_id: HashedUserId,
+        context: CxOf<Ch>,
+        ty: &str,
+        flags: u8,
+        challenge: &Challenge,
+    ) -> Assertion<CxOf<Ch>, O> {
+        let authenticator_data = self.auth_data(flags, None);
+        let client_data = self.client_data(ty, challenge);
+        let message = [&authenticator_data[..], &Sha256::digest(&client_data)[..]].concat();
+        let signature: Signature = self.key.sign(&message);
+        Assertion {
+            authority_id: A::get(),
+            user_id,
+            context,
+            authenticator_data: authenticator_data
+                .try_into()
+                .expect("authenticator data fits; qed"),
+            client_data: client_data.try_into().expect("client data fits; qed"),
+            signature: signature
+                .to_der()
+                .as_bytes()
+                .to_vec()
+                .try_into()
+                .expect("DER signatures fit; qed"),
+            origins: PhantomData,
+        }
+    }
+}
+
+/// The CBOR encoded `attestationObject` of a `none` attestation.
+pub fn attestation_object(auth_data: &[u8]) -> Vec<u8> {
+    let mut e = Encoder::new(Vec::new());
+    e.map(3).unwrap();
+    e.str("fmt").unwrap().str("none").unwrap();
+    e.str("attStmt").unwrap().map(0).unwrap();
+    e.str("authData").unwrap().bytes(auth_data).unwrap();
+    e.into_writer()
+}
@@ -0,0 +1,444 @@
+use super::*;
+use alloc::{format, vec, vec::Vec};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use core::ops::{Deref, DerefMut};
+use fc_traits_authn::{Authenticator, ExtrinsicContext, UserAuthenticator};
+use frame_support::{parameter_types, sp_runtime::str_array as s};
+use minicbor::Encoder;
+use testing::{attestation_object, SoftwarePasskey, FLAGS_UP, FLAGS_UP_AT};
+
+parameter_types! {
+    pub const WebAuthnAuthority: AuthorityId = s("webauthn_authority");
+    pub const Origins: &'static [&'static str] = &[ORIGIN, "https://app.example.org"];
+}
+
+/// Derives challenges from a block number, like a runtime would.
+pub struct BlockChallenger;
+impl Challenger for BlockChallenger {
+    type Context = u32;
+
+    fn generate(cx: &Self::Context, xtc: &impl ExtrinsicContext) -> Challenge {
+        Sha256::new()
+            .chain_update(cx.to_le_bytes())
+            .chain_update(xtc.as_ref())
+            .finalize()
+            .into()
+    }
+}
+
+type Passkeys = WebAuthn<WebAuthnAuthority, BlockChallenger, Origins>;
+
+const RP_ID: &str = "example.org";
+const ORIGIN: &str = "https://example.org";
+const XTC: &[u8] = b"extrinsic context";
+const BLOCK: u32 = 10;
+
+/// A software passkey scoped to [`RP_ID`], asserting for a fixed user at [`BLOCK`].
+struct Passkey(SoftwarePasskey<WebAuthnAuthority, BlockChallenger>);
+
+impl Deref for Passkey {
+    type Target = SoftwarePasskey<WebAuthnAuthority, BlockChallenger>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Passkey {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Passkey {
+    fn new(seed: u8) -> Self {
+        Self(SoftwarePasskey::new(
+            [seed; 32],
+            vec![seed; 16],
+            RP_ID,
+            ORIGIN,
+        ))
+    }
+
+    fn attest_with(&self, ty: &str, flags: u8, cose_key: &[u8]) -> Attestation<u32, Origins> {
+        let challenge = BlockChallenger::generate(&BLOCK, &XTC);
+        Attestation {
+            authority_id: WebAuthnAuthority::get(),
+            device_id: self.device_id(),
+            context: BLOCK,
+            attestation_object: attestation_object(&self.auth_data(flags, Some(cose_key)))
+                .try_into()
+                .unwrap(),
+            client_data: client_data(ty, &challenge).try_into().unwrap(),
+            origins: PhantomData,
+        }
+    }
+
+    fn attest(&self) -> Attestation<u32, Origins> {
+        self.0.attest(BLOCK, &XTC)
+    }
+
+    fn assert_with(&self, ty: &str, flags: u8, challenge: &Challenge) -> Assertion<u32, Origins> {
+        self.0.assert_with(s("user"), BLOCK, ty, flags, challenge)
+    }
+
+    fn assert(&mut self) -> Assertion<u32, Origins> {
+        self.0.assert(s("user"), BLOCK, &XTC)
+    }
+}
+
+fn client_data(ty: &str, challenge: &Challenge) -> Vec<u8> {
+    let challenge = URL_SAFE_NO_PAD.encode(challenge);
+    format!(
+        r#"{{"type":"{ty}","challenge":"{challenge}","origin":"{ORIGIN}","crossOrigin":false}}"#
+    )
+    .into_bytes()
+}
+
+fn register(passkey: &Passkey) -> WebAuthnDevice<WebAuthnAuthority, BlockChallenger, Origins> {
+    Passkeys::verify_device(passkey.attest(), &XTC).expect("valid attestation; qed")
+}
+
+mod attestation {
+    use super::*;
+
+    #[test]
+    fn registers_device() {
+        let passkey = Passkey::new(1);
+        let device = register(&passkey);
+        assert_eq!(device.device_id(), &passkey.device_id());
+    }
+
+    #[test]
+    fn parses_attested_credential() {
+        let passkey = Passkey::new(1);
+        let device = Device::from_attestation(&passkey.attest()).unwrap();
+        assert_eq!(
+            device,
+            Device {
+                device_id: passkey.device_id(),
+                rp_id_hash: Sha256::digest(RP_ID.as_bytes()).into(),
+                public_key: passkey
+                    .key
+                    .verifying_key()
+                    .to_encoded_point(true)
+                    .as_bytes()
+                    .try_into()
+                    .unwrap(),
+                sign_count: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn uses_challenge_from_client_data() {
+        let attestation = Passkey::new(1).attest();
+        assert_eq!(
+            attestation.used_challenge(),
+            (BLOCK, BlockChallenger::generate(&BLOCK, &XTC))
+        );
+    }
+
+    #[test]
+    fn fails_with_other_extrinsic_context() {
+        assert!(Passkeys::verify_device(Passkey::new(1).attest(), &b"other context").is_none());
+    }
+
+    #[test]
+    fn fails_with_other_authority() {
+        let mut attestation = Passkey::new(1).attest();
+        attestation.authority_id = s("other_authority");
+        assert!(Passkeys::verify_device(attestation, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_with_other_device_id() {
+        let mut attestation = Passkey::new(1).attest();
+        attestation.device_id = Passkey::new(2).device_id();
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_for_assertion_client_data() {
+        let passkey = Passkey::new(1);
+        let attestation = passkey.attest_with(ClientData::GET, FLAGS_UP_AT, &passkey.cose_key());
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_without_user_presence() {
+        let passkey = Passkey::new(1);
+        let attestation = passkey.attest_with(ClientData::CREATE, 0x40, &passkey.cose_key());
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_without_attested_credential() {
+        let passkey = Passkey::new(1);
+        let mut attestation = passkey.attest();
+        attestation.attestation_object = attestation_object(&passkey.auth_data(FLAGS_UP, None))
+            .try_into()
+            .unwrap();
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_for_unsupported_algorithms() {
+        let passkey = Passkey::new(1);
+        // An RS256 key
+        let mut e = Encoder::new(Vec::new());
+        e.map(4).unwrap();
+        e.i8(1).unwrap().i8(3).unwrap();
+        e.i8(3).unwrap().i16(-257).unwrap();
+        e.i8(-1).unwrap().bytes(&[1; 256]).unwrap();
+        e.i8(-2).unwrap().bytes(&[1, 0, 1]).unwrap();
+        let attestation = passkey.attest_with(ClientData::CREATE, FLAGS_UP_AT, &e.into_writer());
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_for_points_off_the_curve() {
+        let passkey = Passkey::new(1);
+        let mut e = Encoder::new(Vec::new());
+        e.map(5).unwrap();
+        e.i8(1).unwrap().i8(2).unwrap();
+        e.i8(3).unwrap().i8(-7).unwrap();
+        e.i8(-1).unwrap().i8(1).unwrap();
+        e.i8(-2).unwrap().bytes(&[1; 32]).unwrap();
+        e.i8(-3).unwrap().bytes(&[1; 32]).unwrap();
+        let attestation = passkey.attest_with(ClientData::CREATE, FLAGS_UP_AT, &e.into_writer());
+        assert!(!attestation.is_valid());
+    }
+
+    #[test]
+    fn fails_from_other_origins() {
+        let mut passkey = Passkey::new(1);
+        passkey.origin = "https://evil.example.org".into();
+        assert!(!passkey.attest().is_valid());
+
+        passkey.origin = "https://app.example.org".into();
+        assert!(passkey.attest().is_valid());
+    }
+
+    #[test]
+    fn fails_for_malformed_client_data() {
+        let mut attestation = Passkey::new(1).attest();
+        attestation.client_data = b"{\"type\":\"webauthn.create\""
+            .to_vec()
+            .try_into()
+            .unwrap();
+        assert!(!attestation.is_valid());
+        assert_eq!(attestation.used_challenge().1, [0; 32]);
+    }
+}
+
+mod assertion {
+    use super::*;
+
+    #[test]
+    fn verifies_user() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        assert_eq!(device.verify_user(&passkey.assert(), &XTC), Some(()));
+    }
+
+    #[test]
+    fn fails_with_other_extrinsic_context() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        assert!(device
+            .verify_user(&passkey.assert(), &b"other context")
+            .is_none());
+    }
+
+    #[test]
+    fn fails_when_signed_by_another_key() {
+        let passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        let mut impostor = Passkey::new(2);
+        impostor.credential_id = passkey.credential_id.clone();
+        assert!(device.verify_user(&impostor.assert(), &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_for_tampered_client_data() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        let mut assertion = passkey.assert();
+        let mut client_data = assertion.client_data.to_vec();
+        client_data.extend(b" ");
+        assertion.client_data = client_data.try_into().unwrap();
+        assert!(device.verify_user(&assertion, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_for_attestation_client_data() {
+        let passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        let challenge = BlockChallenger::generate(&BLOCK, &XTC);
+        let assertion = passkey.assert_with(ClientData::CREATE, FLAGS_UP, &challenge);
+        assert!(device.verify_user(&assertion, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_without_user_presence() {
+        let passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        let challenge = BlockChallenger::generate(&BLOCK, &XTC);
+        let assertion = passkey.assert_with(ClientData::GET, 0x04, &challenge);
+        assert!(device.verify_user(&assertion, &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_for_another_relying_party() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        passkey.rp_id = "evil.org".into();
+        assert!(device.verify_user(&passkey.assert(), &XTC).is_none());
+    }
+
+    #[test]
+    fn fails_from_other_origins() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        // Same relying party, but used from a site that's not allowed
+        passkey.origin = "https://evil.example.org".into();
+        assert!(device.verify_user(&passkey.assert(), &XTC).is_none());
+    }
+
+    #[test]
+    fn tracks_signature_counter() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+
+        let first = passkey.assert();
+        let second = passkey.assert();
+        assert_eq!(device.verify_user(&second, &XTC), Some(()));
+
+        // A counter that didn't increase means the authenticator might be cloned
+        assert!(device.verify_user(&first, &XTC).is_none());
+        assert!(device.verify_user(&second, &XTC).is_none());
+
+        assert_eq!(device.verify_user(&passkey.assert(), &XTC), Some(()));
+    }
+
+    #[test]
+    fn allows_authenticators_without_counter() {
+        let mut passkey = Passkey::new(1);
+        let mut device = register(&passkey);
+        let challenge = BlockChallenger::generate(&BLOCK, &XTC);
+        passkey.sign_count = 0;
+        for _ in 0..2 {
+            let assertion = passkey.assert_with(ClientData::GET, FLAGS_UP, &challenge);
+            assert_eq!(device.verify_user(&assertion, &XTC), Some(()));
+        }
+    }
+}
+
+/// Fixed vectors produced by a software authenticator, to catch changes in
+/// encodings or hashing.
+mod vectors {
+    use super::*;
+
+    const CREDENTIAL_ID: [u8; 16] = *b"passkey-vector-1";
+    const DEVICE_ID: &str = "a27c22222b2d9d5c1bd5277f67c95a775af51d924894eba961d07a89a6ae1a85";
+    const PUBLIC_KEY: &str = "020c901d423c831ca85e27c73c263ba132721bb9d7a84c4f0380b2a6756fd60133";
+    const AUTHENTICATOR_DATA: &str =
+        "bfabc37432958b063360d3ad6461c9c4735ae7f8edd46592a5e0f01452b2e4b50500000001";
+    const CLIENT_DATA: &str = r#"{"type":"webauthn.get","challenge":"Qw8ECvceuu44qcSrXKiEtj9KFt3ognBYmstEGRIqef4","origin":"https://example.org","crossOrigin":false}"#;
+    const SIGNATURE: &str = "3045022100d78e4fb57bb8efb071b241e6988f32df3fa8901d727d11aa90c30f0b7f46183402202208abec3d80856f46afff3121c6fa962f43b80ca12e118b5900520eb064db7d";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn device() -> Device {
+        Device {
+            device_id: device_id_for(&CREDENTIAL_ID),
+            rp_id_hash: Sha256::digest(RP_ID.as_bytes()).into(),
+            public_key: hex(PUBLIC_KEY).try_into().unwrap(),
+            sign_count: 0,
+        }
+    }
+
+    fn assertion() -> Assertion<u32, Origins> {
+        Assertion {
+            authority_id: WebAuthnAuthority::get(),
+            user_id: s("user"),
+            context: BLOCK,
+            authenticator_data: hex(AUTHENTICATOR_DATA).try_into().unwrap(),
+            client_data: CLIENT_DATA.as_bytes().to_vec().try_into().unwrap(),
+            signature: hex(SIGNATURE).try_into().unwrap(),
+            origins: PhantomData,
+        }
+    }
+
+    #[test]
+    fn derives_device_id() {
+        assert_eq!(device_id_for(&CREDENTIAL_ID).to_vec(), hex(DEVICE_ID));
+    }
+
+    #[test]
+    fn parses_client_data() {
+        let client_data = ClientData::parse(CLIENT_DATA.as_bytes()).unwrap();
+        assert_eq!(client_data.ty, ClientData::GET);
+        assert_eq!(
+            client_data.challenge,
+            BlockChallenger::generate(&BLOCK, &XTC)
+        );
+    }
+
+    #[test]
+    fn verifies_signature() {
+        let assertion = assertion();
+        assert!(assertion.is_valid());
+        assert_eq!(device().verify(&assertion), Some(()));
+    }
+
+    #[test]
+    fn rejects_flipped_signature_bit() {
+        let mut assertion = assertion();
+        let mut signature = assertion.signature.to_vec();
+        let last = signature.len() - 1;
+        signature[last] ^= 1;
+        assertion.signature = signature.try_into().unwrap();
+        assert!(device().verify(&assertion).is_none());
+    }
+}
+
+mod composite {
+    use super::*;
+    use fc_traits_authn::{composite_authenticators, util::dummy};
+
+    composite_authenticators! {
+        pub Pass<WebAuthnAuthority> {
+            Passkeys,
+            dummy::Dummy::<WebAuthnAuthority>,
+        };
+    }
+
+    #[test]
+    fn works_within_composite_authenticators() {
+        let mut passkey = Passkey::new(1);
+        let mut device = PassAuthenticator::verify_device(
+            PassDeviceAttestation::Passkeys(passkey.attest()),
+            &XTC,
+        )
+        .unwrap();
+        assert_eq!(device.device_id(), &passkey.device_id());
+
+        assert_eq!(
+            device.verify_user(&PassCredential::Passkeys(passkey.assert()), &XTC),
+            Some(())
+        );
+        assert!(device
+            .verify_user(
+                &PassCredential::DummyDummy(dummy::DummyCredential::new(true, s("user"))),
+                &XTC
+            )
+            .is_none());
+    }
+}
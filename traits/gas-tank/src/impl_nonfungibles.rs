@@ -62,6 +62,17 @@ impl<BlockNumber> WeightTank<BlockNumber> {
     {
         F::set_typed_attribute(collection_id, item_id, &ATTR_MEMBERSHIP_GAS, self)
     }
+
+    pub(crate) fn clear<T, F, I>(
+        collection_id: &F::CollectionId,
+        item_id: &F::ItemId,
+    ) -> DispatchResult
+    where
+        T: frame_system::Config,
+        F: nonfungibles_v2::Inspect<T::AccountId> + nonfungibles_v2::Mutate<T::AccountId, I>,
+    {
+        F::clear_typed_attribute(collection_id, item_id, &ATTR_MEMBERSHIP_GAS)
+    }
 }
 
 pub struct Noop;
@@ -214,4 +225,10 @@ where
         WeightTank::<Self::BlockNumber>::new(capacity, P::current_block_number(), periodicity)
             .put::<T, F, ItemConfig>(collection_id, item_id)
     }
+
+    fn remove_tank((collection_id, item_id): &Self::TankId) -> DispatchResult {
+        // A pending payment with the tank's gas has nothing left to burn from
+        let _ = F::clear_typed_attribute(collection_id, item_id, &ATTR_GAS_TX_PAY_WITH_MEMBERSHIP);
+        WeightTank::<Self::BlockNumber>::clear::<T, F, ItemConfig>(collection_id, item_id)
+    }
 }
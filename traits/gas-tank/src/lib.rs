@@ -53,4 +53,7 @@ pub trait MakeTank {
         capacity: Option<Self::Gas>,
         periodicity: Option<Self::BlockNumber>,
     ) -> DispatchResult;
+
+    /// Removes the tank, so its gas can no longer be spent nor refueled.
+    fn remove_tank(id: &Self::TankId) -> DispatchResult;
 }
@@ -344,4 +344,40 @@ mod make_tank {
             );
         })
     }
+
+    #[test]
+    fn removing_the_tank_stops_its_gas() {
+        use frame_support::traits::nonfungibles_v2::Mutate;
+
+        new_test_ext().execute_with(|| {
+            assert_ok!(Memberships::mint_into(
+                &1,
+                &4,
+                &ExtraLargeMember::get(),
+                &Default::default(),
+                true,
+            ));
+            assert_ok!(MembershipsGas::make_tank(
+                &(1, 4),
+                Some(ExtraLargeTank::get()),
+                None
+            ));
+            assert!(MembershipsGas::check_available_gas(
+                &ExtraLargeMember::get(),
+                &SmallTank::get()
+            )
+            .is_some());
+
+            assert_ok!(MembershipsGas::remove_tank(&(1, 4)));
+
+            assert_eq!(
+                MembershipsGas::check_available_gas(&ExtraLargeMember::get(), &SmallTank::get()),
+                None
+            );
+            assert_eq!(
+                MembershipsGas::refuel_gas(&(1, 4), &SmallTank::get()),
+                Weight::zero()
+            );
+        })
+    }
 }